@@ -0,0 +1,113 @@
+//! Array conversion benchmarks: the pre-bulk access paths vs the current `Local<Array>` API.
+//!
+//! Run with `cargo bench -p mquickjs-rs --bench array`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use mquickjs_rs::handles::local::{Array, Local, Value};
+use mquickjs_rs::handles::scope::Scope;
+use mquickjs_rs::mquickjs_ffi;
+use mquickjs_rs::{Context, Env, Global};
+
+const LEN: u32 = 10_000;
+
+/// The original `is_array`: `Array.isArray(v)` through `JS_Call`.
+fn old_is_array(scope: &Scope<'_>, v: Local<'_, Value>) -> bool {
+    let ctx = scope.ctx_raw();
+    unsafe {
+        let global = mquickjs_ffi::JS_GetGlobalObject(ctx);
+        let array_ctor =
+            mquickjs_ffi::JS_GetPropertyStr(ctx, global, b"Array\0".as_ptr() as *const _);
+        let is_array =
+            mquickjs_ffi::JS_GetPropertyStr(ctx, array_ctor, b"isArray\0".as_ptr() as *const _);
+        mquickjs_ffi::JS_PushArg(ctx, v.as_raw());
+        mquickjs_ffi::JS_PushArg(ctx, is_array);
+        mquickjs_ffi::JS_PushArg(ctx, array_ctor);
+        let ret = mquickjs_ffi::JS_Call(ctx, 1);
+        let mut out = 0i32;
+        mquickjs_ffi::JS_ToInt32(ctx, &mut out, ret) == 0 && out != 0
+    }
+}
+
+/// The original per-element conversion: `len()` + `JS_GetPropertyUint32` + `JS_ToNumber`.
+fn old_to_f64_vec(env: &Env<'_>, arr: Local<'_, Array>) -> Vec<f64> {
+    let len = arr.len(env).unwrap();
+    let mut out = Vec::new();
+    for i in 0..len {
+        let raw =
+            unsafe { mquickjs_ffi::JS_GetPropertyUint32(env.scope().ctx_raw(), arr.as_raw(), i) };
+        out.push(env.get_number(env.scope().value(raw)).unwrap());
+    }
+    out
+}
+
+fn new_array<'ctx>(scope: &Scope<'ctx>) -> Local<'ctx, Array> {
+    let a = unsafe { mquickjs_ffi::JS_NewArray(scope.ctx_raw(), 0) };
+    scope.value(a).try_into_array(scope).unwrap()
+}
+
+fn arrays(c: &mut Criterion) {
+    let ctx = Context::new(16 * 1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    // Immediate ints never move, so these `Local`s stay valid across the GCs below.
+    let vals: Vec<_> = (0..LEN as i32).map(|i| env.int_local(i).unwrap()).collect();
+    let arr = env.array().unwrap();
+    let arr = scope.value(arr.as_raw()).try_into_array(&scope).unwrap();
+    arr.extend_from_slice(&env, &vals).unwrap();
+    // The benchmarks allocate, so re-read the (moving) array from a root every iteration.
+    let root = Global::new(&scope, arr);
+    let arr = || scope.value(root.as_raw()).try_into_array(&scope).unwrap();
+
+    let mut group = c.benchmark_group("array");
+    group.throughput(Throughput::Elements(LEN as u64));
+
+    group.bench_function("is_array/old_array_is_array_call", |b| {
+        b.iter(|| {
+            let arr_value = scope.value(arr().as_raw());
+            for _ in 0..LEN {
+                black_box(old_is_array(&scope, arr_value));
+            }
+        })
+    });
+    group.bench_function("is_array/new_class_id", |b| {
+        b.iter(|| {
+            let arr_value = scope.value(arr().as_raw());
+            for _ in 0..LEN {
+                black_box(arr_value.is_array(&scope));
+            }
+        })
+    });
+
+    group.bench_function("to_f64_vec/old_per_element", |b| {
+        b.iter(|| black_box(old_to_f64_vec(&env, arr())))
+    });
+    group.bench_function("to_f64_vec/new_rooted_reads", |b| {
+        b.iter(|| black_box(arr().to_f64_vec(&env).unwrap()))
+    });
+
+    group.bench_function("fill/old_set_per_element", |b| {
+        b.iter(|| {
+            let a = new_array(&scope);
+            for v in &vals {
+                let len = a.len(&env).unwrap();
+                a.set(&env, len, *v).unwrap();
+            }
+            black_box(a.as_raw());
+        })
+    });
+    group.bench_function("fill/new_extend_from_slice", |b| {
+        b.iter(|| {
+            let a = new_array(&scope);
+            black_box(a.extend_from_slice(&env, &vals).unwrap());
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, arrays);
+criterion_main!(benches);
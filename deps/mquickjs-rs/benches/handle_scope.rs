@@ -0,0 +1,115 @@
+//! Rooting benchmarks: one `Box<JSGCRef>` per handle (the previous `HandleScope`) vs the
+//! chunked arena, plus `Env::scoped` in an array loop.
+//!
+//! Run with `cargo bench -p mquickjs-rs --bench handle_scope`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use mquickjs_rs::handles::handle_scope::HandleScope;
+use mquickjs_rs::handles::local::{Array, Local};
+use mquickjs_rs::handles::scope::Scope;
+use mquickjs_rs::mquickjs_ffi;
+use mquickjs_rs::{Context, Env};
+
+const SIZES: [u32; 3] = [100, 1_000, 10_000];
+
+/// The previous rooting strategy: allocate, link, unlink and free one node per handle.
+fn box_per_handle(scope: &Scope<'_>, n: u32) {
+    let ctx = scope.ctx_raw();
+    let mut nodes = Vec::with_capacity(n as usize);
+    for i in 0..n {
+        let gc_ref = Box::into_raw(Box::new(mquickjs_ffi::JSGCRef {
+            val: mquickjs_ffi::JS_UNDEFINED,
+            prev: std::ptr::null_mut(),
+        }));
+        unsafe {
+            let slot = mquickjs_ffi::JS_PushGCRef(ctx, gc_ref);
+            *slot = mquickjs_ffi::JS_NewInt32(ctx, i as i32);
+        }
+        nodes.push(gc_ref);
+    }
+    for gc_ref in nodes.into_iter().rev() {
+        unsafe {
+            black_box(mquickjs_ffi::JS_PopGCRef(ctx, gc_ref));
+            drop(Box::from_raw(gc_ref));
+        }
+    }
+}
+
+fn rooting(c: &mut Criterion) {
+    let ctx = Context::new(4 * 1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut group = c.benchmark_group("rooting");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+
+        group.bench_with_input(BenchmarkId::new("box_per_handle", n), &n, |b, &n| {
+            b.iter(|| box_per_handle(&scope, n))
+        });
+
+        group.bench_with_input(BenchmarkId::new("arena_fresh_scope", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut hs = HandleScope::new(&scope);
+                for i in 0..n {
+                    let v = scope
+                        .value(unsafe { mquickjs_ffi::JS_NewInt32(scope.ctx_raw(), i as i32) });
+                    black_box(hs.handle(v).as_raw());
+                }
+            })
+        });
+
+        let mut hs = HandleScope::new(&scope);
+        group.bench_with_input(BenchmarkId::new("arena_reset", n), &n, |b, &n| {
+            b.iter(|| {
+                for i in 0..n {
+                    let v = scope
+                        .value(unsafe { mquickjs_ffi::JS_NewInt32(scope.ctx_raw(), i as i32) });
+                    black_box(hs.handle(v).as_raw());
+                }
+                hs.reset();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn array_get_loop(c: &mut Criterion) {
+    let mut ctx = Context::new(4 * 1024 * 1024).unwrap();
+    ctx.eval("var arr = []; for (var i = 0; i < 10000; i++) arr.push(i);")
+        .unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut group = c.benchmark_group("array_get");
+    group.throughput(Throughput::Elements(10_000));
+
+    group.bench_function("handles_accumulate", |b| {
+        b.iter(|| {
+            let mut env = Env::new(&scope);
+            let arr = env.global().unwrap().as_local();
+            let arr: Local<Array> = arr.get_path(&env, "arr").unwrap();
+            for i in 0..10_000 {
+                black_box(arr.get(&mut env, i).unwrap().as_raw());
+            }
+        })
+    });
+
+    group.bench_function("env_scoped_per_item", |b| {
+        b.iter(|| {
+            let mut env = Env::new(&scope);
+            let arr = env.global().unwrap().as_local();
+            let arr: Local<Array> = arr.get_path(&env, "arr").unwrap();
+            for i in 0..10_000 {
+                env.scoped(|env| black_box(arr.get(env, i).unwrap().as_raw()));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, rooting, array_get_loop);
+criterion_main!(benches);
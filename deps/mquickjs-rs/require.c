@@ -7,6 +7,13 @@
  * This file is compiled and linked only when ridl-extensions is enabled.
  * It relies on js_ridl_require_table emitted from the generated
  * mquickjs_ridl_register.c (declared in mquickjs_ridl_api.h).
+ *
+ * Specs with no native match fall back to the embedder's script module loader
+ * (mquickjs_rs_require_hook), e.g. require("./util.js").
+ *
+ * Before a native module is instantiated the embedder may veto it
+ * (mquickjs_rs_require_check), e.g. to keep host-capability modules away from
+ * untrusted contexts.
  */
 
 #include "mquickjs_ridl_api.h"
@@ -125,6 +132,35 @@ static JSValue require_not_found(JSContext *ctx, const char *spec) {
     return JS_EXCEPTION;
 }
 
+/*
+ * Script modules (plain JS files) are resolved by the embedder: mquickjs-rs defines this hook
+ * and forwards to the context's ModuleLoader. Declared weak so C-only links still work.
+ * Returns 1 with *out set, 0 if unknown, -1 with an exception pending.
+ */
+extern int mquickjs_rs_require_hook(JSContext *ctx, const char *spec, JSValue referrer, JSValue *out)
+    __attribute__((weak));
+
+// No native module matched `spec`: ask the script module loader, then report `report`.
+static JSValue require_fallback(JSContext *ctx, int argc, JSValue *argv, const char *spec, const char *report) {
+    if (mquickjs_rs_require_hook) {
+        JSValue out = JS_UNDEFINED;
+        // argv[1]: id of the requiring script module (passed by its module-local require).
+        int r = mquickjs_rs_require_hook(ctx, spec, argc >= 2 ? argv[1] : JS_UNDEFINED, &out);
+        if (r < 0)
+            return JS_EXCEPTION;
+        if (r > 0)
+            return out;
+    }
+    return require_not_found(ctx, report);
+}
+
+/*
+ * Per-context capability check for native modules, defined by mquickjs-rs (weak, like the
+ * hook above). Returns 0 to allow, -1 with an exception pending to deny.
+ */
+extern int mquickjs_rs_require_check(JSContext *ctx, const char *module_base, const char *module_full_name)
+    __attribute__((weak));
+
 static int no_ws(const char *s) {
     const char *p = s;
     while (*p) {
@@ -170,7 +206,7 @@ static JSValue js_global_require(JSContext *ctx, JSValue *this_val, int argc, JS
         return JS_EXCEPTION;
 
     if (!no_ws(spec)) {
-        return require_not_found(ctx, "<invalid>");
+        return require_fallback(ctx, argc, argv, spec, "<invalid>");
     }
 
     at = strchr(spec, '@');
@@ -188,25 +224,25 @@ static JSValue js_global_require(JSContext *ctx, JSValue *this_val, int argc, JS
                 best = e;
         }
         if (!best) {
-            return require_not_found(ctx, base);
+            return require_fallback(ctx, argc, argv, spec, base);
         }
     } else {
         // base@...
         size_t base_len = (size_t)(at - spec);
         if (base_len == 0) {
-            return require_not_found(ctx, spec);
+            return require_fallback(ctx, argc, argv, spec, spec);
         }
 
         char base_buf[256];
         if (base_len >= sizeof(base_buf)) {
-            return require_not_found(ctx, spec);
+            return require_fallback(ctx, argc, argv, spec, spec);
         }
         memcpy(base_buf, spec, base_len);
         base_buf[base_len] = '\0';
 
         tail = at + 1;
         if (!*tail) {
-            return require_not_found(ctx, spec);
+            return require_fallback(ctx, argc, argv, spec, spec);
         }
 
         int op = 0;
@@ -228,7 +264,7 @@ static JSValue js_global_require(JSContext *ctx, JSValue *this_val, int argc, JS
 
         uint16_t maj, min, pat;
         if (parse_version_3(tail, &maj, &min, &pat)) {
-            return require_not_found(ctx, spec);
+            return require_fallback(ctx, argc, argv, spec, spec);
         }
 
         for (int i = 0; i < js_ridl_require_table_len; i++) {
@@ -242,10 +278,15 @@ static JSValue js_global_require(JSContext *ctx, JSValue *this_val, int argc, JS
         }
 
         if (!best) {
-            return require_not_found(ctx, spec);
+            return require_fallback(ctx, argc, argv, spec, spec);
         }
     }
 
+    if (mquickjs_rs_require_check &&
+        mquickjs_rs_require_check(ctx, best->module_base, best->module_full_name) < 0) {
+        return JS_EXCEPTION;
+    }
+
     // Create a new module instance each call.
     // Ensure the class prototype exists so exports installed on module prototype are visible.
     JSValue module_proto = JS_EnsureClassProto(ctx, best->module_class_id);
@@ -1,10 +1,18 @@
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
-use std::ffi::{CStr, CString};
+use std::collections::HashMap;
+use std::ffi::CString;
 use std::os::raw::c_void;
+use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::handles::js_call::Builtins;
 use crate::handles::local::{Local, Value};
 use crate::mquickjs_ffi;
+#[cfg(feature = "ridl-extensions")]
+use crate::permissions::Permissions;
+#[cfg(feature = "ridl-extensions")]
+use crate::ridl_runtime::SingletonOverride;
 
 pub struct ContextInner {
     // NOTE: host per-context extensions (initialized by application-generated ridl_context_init).
@@ -13,6 +21,65 @@ pub struct ContextInner {
     ridl_ext_drop: std::cell::UnsafeCell<Option<unsafe fn(*mut c_void)>>,
 
     pub(crate) alive: std::sync::atomic::AtomicBool,
+
+    /// Live `Global`s created on this context (debug accounting; see `Context::globals_count`).
+    pub(crate) globals: std::sync::Mutex<GlobalRegistry>,
+    /// When set, `Context` drop prints still-alive globals to stderr.
+    pub(crate) leak_report: std::sync::atomic::AtomicBool,
+
+    /// Script module loader and cache for `require()` (see `module_loader`).
+    pub(crate) modules: RefCell<crate::module_loader::ModuleState>,
+
+    /// Typed per-context host state (see `ContextToken::host_data`), one value per type.
+    host_data: RefCell<HashMap<TypeId, Rc<dyn Any>>>,
+
+    /// Names of the scripts being evaluated, innermost last (see `ContextToken::current_script`).
+    scripts: RefCell<Vec<String>>,
+
+    /// Host singleton implementations by slot key, consumed by `ridl_context_init`.
+    #[cfg(feature = "ridl-extensions")]
+    singleton_overrides: RefCell<HashMap<&'static str, SingletonOverride>>,
+
+    /// RIDL capabilities of this context (see `ContextBuilder::permissions`); `None` allows all.
+    #[cfg(feature = "ridl-extensions")]
+    permissions: RefCell<Option<Rc<Permissions>>>,
+}
+
+pub(crate) struct ScriptGuard<'a> {
+    inner: &'a ContextInner,
+}
+
+impl Drop for ScriptGuard<'_> {
+    fn drop(&mut self) {
+        self.inner.scripts.borrow_mut().pop();
+    }
+}
+
+/// Bookkeeping entry for one live `Global`.
+pub(crate) struct GlobalRecord {
+    pub(crate) type_name: &'static str,
+    /// Creation backtrace; only captured in debug builds while the leak report is enabled.
+    #[cfg(debug_assertions)]
+    pub(crate) backtrace: Option<std::backtrace::Backtrace>,
+}
+
+#[derive(Default)]
+pub(crate) struct GlobalRegistry {
+    next_id: u64,
+    live: std::collections::BTreeMap<u64, GlobalRecord>,
+}
+
+impl GlobalRegistry {
+    pub(crate) fn register(&mut self, record: GlobalRecord) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.live.insert(id, record);
+        id
+    }
+
+    pub(crate) fn unregister(&mut self, id: u64) {
+        self.live.remove(&id);
+    }
 }
 
 impl ContextInner {
@@ -21,9 +88,63 @@ impl ContextInner {
             ridl_ext_ptr: std::cell::UnsafeCell::new(std::ptr::null_mut()),
             ridl_ext_drop: std::cell::UnsafeCell::new(None),
             alive: std::sync::atomic::AtomicBool::new(true),
+            globals: std::sync::Mutex::new(GlobalRegistry::default()),
+            leak_report: std::sync::atomic::AtomicBool::new(false),
+            modules: RefCell::new(Default::default()),
+            host_data: RefCell::new(HashMap::new()),
+            scripts: RefCell::new(Vec::new()),
+            #[cfg(feature = "ridl-extensions")]
+            singleton_overrides: RefCell::new(HashMap::new()),
+            #[cfg(feature = "ridl-extensions")]
+            permissions: RefCell::new(None),
         }
     }
 
+    #[cfg(feature = "ridl-extensions")]
+    pub(crate) fn set_singleton_override(&self, slot_key: &'static str, value: SingletonOverride) {
+        self.singleton_overrides
+            .borrow_mut()
+            .insert(slot_key, value);
+    }
+
+    /// The host implementation registered for `slot_key`, if any (called by the generated
+    /// `ridl_context_init` before falling back to the module factory).
+    #[cfg(feature = "ridl-extensions")]
+    pub fn take_singleton_override(&self, slot_key: &str) -> Option<SingletonOverride> {
+        self.singleton_overrides.borrow_mut().remove(slot_key)
+    }
+
+    /// Slot keys of overrides no slot has taken (yet).
+    #[cfg(feature = "ridl-extensions")]
+    pub(crate) fn pending_singleton_overrides(&self) -> Vec<&'static str> {
+        let mut keys: Vec<_> = self.singleton_overrides.borrow().keys().copied().collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[cfg(feature = "ridl-extensions")]
+    pub(crate) fn set_permissions(&self, permissions: Permissions) {
+        *self.permissions.borrow_mut() = Some(Rc::new(permissions));
+    }
+
+    #[cfg(feature = "ridl-extensions")]
+    pub fn permissions(&self) -> Option<Rc<Permissions>> {
+        self.permissions.borrow().clone()
+    }
+
+    /// Whether the global singleton `name` is materialized in this context (called by the
+    /// generated `ridl_context_init`).
+    #[cfg(feature = "ridl-extensions")]
+    pub fn singleton_visible(&self, name: &str) -> bool {
+        self.permissions().is_none_or(|p| p.singleton_visible(name))
+    }
+
+    /// Mark `name` as the running script until the guard is dropped.
+    pub(crate) fn enter_script(&self, name: &str) -> ScriptGuard<'_> {
+        self.scripts.borrow_mut().push(name.to_string());
+        ScriptGuard { inner: self }
+    }
+
     /// Safety: must only be set once per ContextInner.
     pub unsafe fn set_ridl_ext(&self, ptr: *mut c_void, drop_fn: unsafe fn(*mut c_void)) {
         let p = unsafe { &mut *self.ridl_ext_ptr.get() };
@@ -38,6 +159,33 @@ impl ContextInner {
     pub fn ridl_ext_ptr(&self) -> *mut c_void {
         unsafe { *self.ridl_ext_ptr.get() }
     }
+
+    pub(crate) fn new_global_record(&self, type_name: &'static str) -> GlobalRecord {
+        GlobalRecord {
+            type_name,
+            #[cfg(debug_assertions)]
+            backtrace: self
+                .leak_report
+                .load(std::sync::atomic::Ordering::Relaxed)
+                .then(std::backtrace::Backtrace::force_capture),
+        }
+    }
+
+    pub(crate) fn globals_report(&self) -> String {
+        let globals = self.globals.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = format!(
+            "{} Global(s) still alive at Context drop:\n",
+            globals.live.len()
+        );
+        for (id, rec) in &globals.live {
+            out.push_str(&format!("  #{id}: Global<{}>\n", rec.type_name));
+            #[cfg(debug_assertions)]
+            if let Some(bt) = &rec.backtrace {
+                out.push_str(&format!("{bt}\n"));
+            }
+        }
+        out
+    }
 }
 
 impl Drop for ContextInner {
@@ -125,9 +273,78 @@ impl ContextToken {
             })
         })
     }
+
+    /// False once the owning `Context` has been dropped; a token must not enter a scope then.
+    pub fn is_alive(&self) -> bool {
+        self.inner.alive.load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    /// Per-context host state of type `T` (e.g. timer queues of a RIDL module).
+    ///
+    /// Host data is dropped at `Context` drop while the JSContext is still alive, so it may
+    /// hold `Global`s.
+    pub fn host_data<T: Any>(&self) -> Option<Rc<T>> {
+        let data = self.inner.host_data.borrow();
+        let v = data.get(&TypeId::of::<T>())?.clone();
+        v.downcast::<T>().ok()
+    }
+
+    /// Replace the host state of type `T`, returning the previous value.
+    pub fn set_host_data<T: Any>(&self, value: T) -> Option<Rc<T>> {
+        let old = self
+            .inner
+            .host_data
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), Rc::new(value));
+        old.and_then(|v| v.downcast::<T>().ok())
+    }
+
+    /// File name of the innermost script being evaluated: the `Context::eval_file` name or a
+    /// `require()`d module id (`eval.js` for `Context::eval`).
+    ///
+    /// `None` outside of any evaluation, e.g. while the host drives timer callbacks.
+    pub fn current_script(&self) -> Option<String> {
+        self.inner.scripts.borrow().last().cloned()
+    }
+
+    pub fn host_data_or_insert_with<T: Any>(&self, f: impl FnOnce() -> T) -> Rc<T> {
+        if let Some(v) = self.host_data::<T>() {
+            return v;
+        }
+        // `f` runs without the map borrowed, so it may itself touch host data.
+        let v: Rc<dyn Any> = Rc::new(f());
+        self.inner
+            .host_data
+            .borrow_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert(v)
+            .clone()
+            .downcast::<T>()
+            .unwrap_or_else(|_| unreachable!("host data keyed by TypeId"))
+    }
 }
 
 impl Context {
+    /// Number of `Global`s created on this context that have not been dropped yet.
+    pub fn globals_count(&self) -> usize {
+        self.inner
+            .globals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .live
+            .len()
+    }
+
+    /// Print the globals still alive when this `Context` is dropped.
+    ///
+    /// In debug builds, globals created while this is enabled also record their creation
+    /// backtrace in the report.
+    pub fn set_leak_report(&self, enabled: bool) {
+        self.inner
+            .leak_report
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+    }
+
     pub fn token(&self) -> ContextToken {
         ContextToken {
             ctx: self.ctx,
@@ -175,19 +392,30 @@ impl Context {
             mquickjs_ffi::JS_SetContextUserData(ctx, arc_ptr, Some(user_data_finalizer));
         }
 
-        Ok(Context {
+        let context = Context {
             ctx,
             inner,
             _memory: memory,
-        })
+        };
+        // Before any script runs, so reflection helpers never see script replacements.
+        let builtins = Builtins::capture(ctx)?;
+        context.token().set_host_data(builtins);
+        Ok(context)
     }
 
     pub fn eval(&mut self, code: &str) -> Result<String, String> {
+        self.eval_file(code, "eval.js")
+    }
+
+    /// Like `eval`, with `filename` reported in error locations and as
+    /// `ContextToken::current_script` while the code runs.
+    pub fn eval_file(&mut self, code: &str, filename: &str) -> Result<String, String> {
         let handle = self.token();
         let _g = handle.enter_current();
+        let _script = self.inner.enter_script(filename);
 
         let c_code = CString::new(code).map_err(|e| e.to_string())?;
-        let filename = CString::new("eval.js").unwrap();
+        let filename = CString::new(filename).map_err(|e| e.to_string())?;
 
         let result = unsafe {
             mquickjs_ffi::JS_Eval(
@@ -205,30 +433,14 @@ impl Context {
         if tag == mquickjs_ffi::JS_TAG_EXCEPTION as u32 {
             let exception = unsafe { mquickjs_ffi::JS_GetException(self.ctx) };
 
-            // 创建一个临时缓冲区用于JS_ToCString
-            let mut cstr_buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
-            let error_ptr =
-                unsafe { mquickjs_ffi::JS_ToCString(self.ctx, exception, &mut cstr_buf) };
-
-            if !error_ptr.is_null() {
-                let error_str = unsafe { CStr::from_ptr(error_ptr).to_string_lossy().into_owned() };
-
-                return Err(error_str);
-            } else {
-                return Err("Unknown error".to_string());
+            match unsafe { crate::handles::string::to_rust_string_raw(self.ctx, exception) } {
+                Some(error_str) => Err(error_str),
+                None => Err("Unknown error".to_string()),
             }
         } else {
-            // 创建一个临时缓冲区用于JS_ToCString
-            let mut cstr_buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
-            let result_ptr = unsafe { mquickjs_ffi::JS_ToCString(self.ctx, result, &mut cstr_buf) };
-
-            if !result_ptr.is_null() {
-                let result_str =
-                    unsafe { CStr::from_ptr(result_ptr).to_string_lossy().into_owned() };
-
-                Ok(result_str)
-            } else {
-                Ok("undefined".to_string())
+            match unsafe { crate::handles::string::to_rust_string_raw(self.ctx, result) } {
+                Some(result_str) => Ok(result_str),
+                None => Ok("undefined".to_string()),
             }
         }
     }
@@ -239,14 +451,9 @@ impl Context {
         scope: &crate::handles::scope::Scope<'a>,
         rust_str: &str,
     ) -> Result<Local<'a, Value>, String> {
-        let c_str = CString::new(rust_str).map_err(|e| e.to_string())?;
-        let js_value = unsafe { mquickjs_ffi::JS_NewString(self.ctx, c_str.as_ptr()) };
-
-        if (js_value as u32) & ((1u32 << (mquickjs_ffi::JS_TAG_SPECIAL_BITS as u32)) - 1)
-            == (mquickjs_ffi::JS_TAG_EXCEPTION as u32)
-        {
-            return Err("Failed to create string".to_string());
-        }
+        // JS_NewStringLen: interior NULs are kept (CString::new would reject them).
+        let js_value = unsafe { crate::handles::string::new_string_raw(self.ctx, rust_str) }
+            .map_err(|_| "Failed to create string".to_string())?;
 
         Ok(scope.value(js_value))
     }
@@ -293,18 +500,11 @@ impl Context {
     }
 
     /// 将值转换为Rust字符串
+    ///
+    /// 按 `ToString` 语义转换并立即复制（mquickjs 返回的指针只在下次 GC 前有效，无需释放）。
     pub fn get_string(&self, value: Local<'_, Value>) -> Result<String, String> {
-        let mut cstr_buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
-        let result_ptr =
-            unsafe { mquickjs_ffi::JS_ToCString(self.ctx, value.as_raw(), &mut cstr_buf) };
-
-        if result_ptr.is_null() {
-            return Err("Failed to convert Value to string".to_string());
-        }
-
-        Ok(unsafe { CStr::from_ptr(result_ptr) }
-            .to_string_lossy()
-            .into_owned())
+        unsafe { crate::handles::string::to_rust_string_raw(self.ctx, value.as_raw()) }
+            .ok_or_else(|| "Failed to convert Value to string".to_string())
     }
 
     /// 获取数字值
@@ -334,6 +534,23 @@ impl Context {
 
 impl Drop for Context {
     fn drop(&mut self) {
+        // Host data may own `Global`s: drop it first, while they can still be unlinked (and
+        // before the leak report, which is about user-held globals).
+        let host_data = std::mem::take(&mut *self.inner.host_data.borrow_mut());
+        drop(host_data);
+
+        if self
+            .inner
+            .leak_report
+            .load(std::sync::atomic::Ordering::Relaxed)
+            && self.globals_count() > 0
+        {
+            eprint!("{}", self.inner.globals_report());
+        }
+
+        self.inner.modules.borrow_mut().release(self.ctx);
+
+        // Globals that outlive this point become inert: their drop skips JS_DeleteGCRef.
         self.inner
             .alive
             .store(false, std::sync::atomic::Ordering::Release);
@@ -0,0 +1,246 @@
+//! `ContextBuilder`: create a `Context` with globals installed before any script runs.
+//!
+//! Typical use is per-tenant parameterization (config objects, constants) without
+//! string-concatenating a JS prelude. Native functions still come from RIDL: mquickjs resolves
+//! C functions through the static ROM function table, so an arbitrary Rust closure cannot be
+//! turned into a JS function at runtime. A `with_env` hook can however re-export RIDL functions
+//! under other global names, or build any object graph through the handle API.
+//!
+//! With `ridl-extensions`, `singleton` replaces a module's singleton implementation for this
+//! context only (a capturing console in tests, a tenant-scoped logger, ...); `ridl_init` runs
+//! the app's generated `ridl_context_init`, which picks the overrides up. `permissions`
+//! and `require_check` decide which RIDL modules and singletons this context gets.
+
+use std::rc::Rc;
+
+use crate::context::{Context, ContextToken};
+use crate::env::Env;
+use crate::handles::convert::IntoJs;
+use crate::handles::js_call::{call_raw, get_global, is_exception, TempRoots};
+use crate::handles::string::new_string_raw;
+use crate::module_loader::{ModuleLoader, NativeModule, RequireCheck};
+use crate::mquickjs_ffi;
+#[cfg(feature = "ridl-extensions")]
+use crate::permissions::Permissions;
+#[cfg(feature = "ridl-extensions")]
+use crate::ridl_runtime::{RidlSingleton, SingletonOverride};
+
+type InitFn = Box<dyn for<'ctx> FnOnce(&mut Env<'ctx>) -> Result<(), String>>;
+
+pub struct ContextBuilder {
+    memory_capacity: usize,
+    leak_report: bool,
+    module_loader: Option<Rc<dyn ModuleLoader>>,
+    require_checks: Vec<RequireCheck>,
+    #[cfg(feature = "ridl-extensions")]
+    ridl_init: Option<Box<dyn FnOnce(&Context) -> Result<(), String>>>,
+    #[cfg(feature = "ridl-extensions")]
+    singletons: Vec<(&'static str, SingletonOverride)>,
+    #[cfg(feature = "ridl-extensions")]
+    permissions: Option<Permissions>,
+    inits: Vec<InitFn>,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextBuilder {
+    pub fn new() -> Self {
+        Self {
+            memory_capacity: 1024 * 1024,
+            leak_report: false,
+            module_loader: None,
+            require_checks: Vec::new(),
+            #[cfg(feature = "ridl-extensions")]
+            ridl_init: None,
+            #[cfg(feature = "ridl-extensions")]
+            singletons: Vec::new(),
+            #[cfg(feature = "ridl-extensions")]
+            permissions: None,
+            inits: Vec::new(),
+        }
+    }
+
+    /// Size of the engine arena in bytes (default 1MB, same as `Context::default()`).
+    pub fn memory_capacity(mut self, bytes: usize) -> Self {
+        self.memory_capacity = bytes;
+        self
+    }
+
+    /// See `Context::set_leak_report`.
+    pub fn leak_report(mut self, enabled: bool) -> Self {
+        self.leak_report = enabled;
+        self
+    }
+
+    /// See `Context::set_module_loader`; installed before the init hooks run, so they can
+    /// `env.require(...)`.
+    pub fn module_loader(mut self, loader: impl ModuleLoader + 'static) -> Self {
+        self.module_loader = Some(Rc::new(loader));
+        self
+    }
+
+    /// See `Context::add_require_check`; installed before any init hook or script runs.
+    pub fn require_check(
+        mut self,
+        check: impl Fn(&ContextToken, &NativeModule<'_>) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.require_checks.push(Rc::new(check));
+        self
+    }
+
+    /// Per-context RIDL initialization, run right after the context is created: the app's
+    /// generated `ridl_context_init` (plus `JS_RIDL_StdlibInit`). An `Err` fails `build()`.
+    #[cfg(feature = "ridl-extensions")]
+    pub fn ridl_init(mut self, f: impl FnOnce(&Context) -> Result<(), String> + 'static) -> Self {
+        self.ridl_init = Some(Box::new(f));
+        self
+    }
+
+    /// Use `value` instead of the module's `create_<name>_singleton()` for this context, e.g.
+    /// `.singleton::<dyn ConsoleSingleton>(Box::new(MyConsole::default()))`.
+    ///
+    /// `build()` fails if `ridl_init` does not consume it (no `ridl_init`, or the module is not
+    /// linked into the app).
+    #[cfg(feature = "ridl-extensions")]
+    pub fn singleton<S: ?Sized + RidlSingleton + 'static>(mut self, value: Box<S>) -> Self {
+        self.singletons
+            .push((S::SLOT_KEY, SingletonOverride::new(value)));
+        self
+    }
+
+    /// Limit the RIDL modules and global singletons of this context (see `Permissions`).
+    ///
+    /// Applied before `ridl_init`, so hidden singletons are never created; module rules are
+    /// checked by every `require`, before any `require_check`.
+    #[cfg(feature = "ridl-extensions")]
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// `globalThis[name] = value`.
+    pub fn global<V>(self, name: &str, value: V) -> Self
+    where
+        V: for<'ctx> IntoJs<'ctx> + 'static,
+    {
+        let name = name.to_string();
+        self.with_env(move |env| {
+            let scope = env.scope();
+            // Root the converted value and read it back at the store (the GC moves objects).
+            let mut roots = TempRoots::new(scope.ctx_raw());
+            let value = roots.push(value.into_js(scope)?.as_raw());
+            set_global(scope, &name, roots.get(value))
+        })
+    }
+
+    /// `globalThis[name] = JSON.parse(json)`; the natural form for config objects.
+    pub fn global_json(self, name: &str, json: impl Into<String>) -> Self {
+        let name = name.to_string();
+        let json = json.into();
+        self.with_env(move |env| {
+            let scope = env.scope();
+            let ctx = scope.ctx_raw();
+            // The lookups below allocate; keep each value rooted and re-read it before use.
+            let mut roots = TempRoots::new(ctx);
+            let text = roots.push(unsafe { new_string_raw(ctx, &json)? });
+            let json_obj = roots.push(get_global(ctx, b"JSON\0")?);
+            let parse = unsafe {
+                mquickjs_ffi::JS_GetPropertyStr(
+                    ctx,
+                    roots.get(json_obj),
+                    b"parse\0".as_ptr() as *const _,
+                )
+            };
+            if is_exception(parse) {
+                return Err("Exception during JSON.parse lookup".to_string());
+            }
+            let parse = roots.push(parse);
+            let parsed = call_raw(
+                ctx,
+                roots.get(parse),
+                roots.get(json_obj),
+                &[roots.get(text)],
+                &format!("JSON.parse({name})"),
+            )?;
+            let parsed = roots.push(parsed);
+            set_global(scope, &name, roots.get(parsed))
+        })
+    }
+
+    /// Run arbitrary setup against the fresh context; hooks run in registration order.
+    pub fn with_env(
+        mut self,
+        f: impl for<'ctx> FnOnce(&mut Env<'ctx>) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.inits.push(Box::new(f));
+        self
+    }
+
+    pub fn build(self) -> Result<Context, Box<dyn std::error::Error>> {
+        let ctx = Context::new(self.memory_capacity)?;
+        ctx.set_leak_report(self.leak_report);
+        if let Some(loader) = self.module_loader {
+            ctx.install_module_loader(loader);
+        }
+        for check in self.require_checks {
+            ctx.install_require_check(check);
+        }
+        #[cfg(feature = "ridl-extensions")]
+        {
+            if let Some(permissions) = self.permissions {
+                ctx.inner.set_permissions(permissions);
+            }
+            for (key, value) in self.singletons {
+                ctx.inner.set_singleton_override(key, value);
+            }
+            if let Some(init) = self.ridl_init {
+                init(&ctx).map_err(|e| format!("ContextBuilder: ridl_init failed: {e}"))?;
+            }
+            let unused = ctx.inner.pending_singleton_overrides();
+            if !unused.is_empty() {
+                return Err(format!(
+                    "ContextBuilder: singleton override not used: {} (missing ridl_init, or module not linked)",
+                    unused.join(", ")
+                )
+                .into());
+            }
+        }
+        {
+            let token = ctx.token();
+            let scope = token.enter_scope();
+            for init in self.inits {
+                // Each hook gets its own Env so its handles are released before the next one.
+                let mut env = Env::new(&scope);
+                init(&mut env).map_err(|e| format!("ContextBuilder init failed: {e}"))?;
+            }
+        }
+        Ok(ctx)
+    }
+}
+
+/// `globalThis[name] = value`; `value` must be read from its root right before the call.
+fn set_global(
+    scope: &crate::handles::scope::Scope<'_>,
+    name: &str,
+    value: mquickjs_ffi::JSValue,
+) -> Result<(), String> {
+    let global = unsafe { mquickjs_ffi::JS_GetGlobalObject(scope.ctx_raw()) };
+    if is_exception(global) {
+        return Err("Exception during JS_GetGlobalObject".to_string());
+    }
+    scope
+        .value(global)
+        .try_into_object(scope)?
+        .set_property(scope, name, scope.value(value))
+        .map_err(|e| format!("{e} ({name})"))
+}
+
+impl Context {
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+}
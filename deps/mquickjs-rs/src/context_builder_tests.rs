@@ -0,0 +1,151 @@
+use crate::handles::local::Local;
+use crate::{Context, Env};
+
+#[test]
+fn builder_installs_globals_before_first_eval() {
+    let mut ctx = Context::builder()
+        .memory_capacity(1024 * 1024)
+        .global("TENANT", "acme")
+        .global("LIMIT", 42)
+        .global("DEBUG", true)
+        .global_json("config", r#"{"region": "eu", "quota": {"max": 10}}"#)
+        .with_env(|env| {
+            let scope = env.scope();
+            let obj = env.obj()?.as_local();
+            let v = env.int_local(7)?;
+            obj.set_property(scope, "seven", v)?;
+            let global = env.global()?.as_local();
+            global.set_property(scope, "extra", scope.value(obj.as_raw()))
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(ctx.eval("TENANT + ':' + LIMIT").unwrap(), "acme:42");
+    assert_eq!(ctx.eval("DEBUG").unwrap(), "true");
+    assert_eq!(
+        ctx.eval("config.region + config.quota.max").unwrap(),
+        "eu10"
+    );
+    assert_eq!(ctx.eval("extra.seven").unwrap(), "7");
+
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let max: i32 = global.get_path(&env, "config.quota.max").unwrap();
+    assert_eq!(max, 10);
+    let _: Local = global.get_path(&env, "TENANT").unwrap();
+}
+
+#[test]
+fn global_json_parses_documents_that_trigger_gc() {
+    // Big enough that JSON.parse collects (and moves objects) while the text is in flight.
+    let items: Vec<String> = (0..5000).map(|i| format!(r#"{{"id": {i}}}"#)).collect();
+    let json = format!(r#"{{"items": [{}]}}"#, items.join(","));
+    let mut ctx = Context::builder()
+        .memory_capacity(1024 * 1024)
+        .global_json("big", json)
+        .build()
+        .unwrap();
+
+    assert_eq!(ctx.eval("big.items.length").unwrap(), "5000");
+    assert_eq!(ctx.eval("big.items[4999].id").unwrap(), "4999");
+}
+
+#[test]
+fn builder_reports_init_errors() {
+    let err = Context::builder()
+        .global_json("config", "{not json")
+        .build()
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("JSON.parse(config)"));
+}
+
+#[cfg(feature = "ridl-extensions")]
+mod singleton_overrides {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::ridl_runtime::RidlSingleton;
+    use crate::Context;
+
+    // Stand-in for a generated singleton trait (api.rs).
+    trait GreeterSingleton {
+        fn greet(&mut self) -> String;
+    }
+
+    unsafe impl RidlSingleton for dyn GreeterSingleton {
+        const SLOT_KEY: &'static str = "singleton_global_greeter";
+    }
+
+    struct Hello(Rc<Cell<bool>>);
+
+    impl GreeterSingleton for Hello {
+        fn greet(&mut self) -> String {
+            "hello".to_string()
+        }
+    }
+
+    impl Drop for Hello {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[test]
+    fn override_reaches_ridl_init_as_the_glue_layout() {
+        let dropped = Rc::new(Cell::new(false));
+        let greeting = Rc::new(std::cell::RefCell::new(String::new()));
+        let out = greeting.clone();
+        let ctx = Context::builder()
+            .singleton::<dyn GreeterSingleton>(Box::new(Hello(dropped.clone())))
+            .ridl_init(move |ctx| {
+                // What the generated ridl_context_init + glue do with the slot.
+                let o = ctx
+                    .token()
+                    .inner
+                    .take_singleton_override("singleton_global_greeter")
+                    .unwrap();
+                let (p, drop_fn) = o.into_raw();
+                let holder = unsafe { &mut *(p as *mut Box<dyn GreeterSingleton>) };
+                *out.borrow_mut() = holder.greet();
+                unsafe { drop_fn(p) };
+                Ok(())
+            })
+            .build()
+            .unwrap();
+        assert_eq!(*greeting.borrow(), "hello");
+        assert!(dropped.get());
+        drop(ctx);
+    }
+
+    #[test]
+    fn unused_override_fails_build() {
+        let dropped = Rc::new(Cell::new(false));
+        let err = Context::builder()
+            .singleton::<dyn GreeterSingleton>(Box::new(Hello(dropped.clone())))
+            .build()
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("singleton_global_greeter"),
+            "{err}"
+        );
+        assert!(dropped.get());
+    }
+
+    #[test]
+    fn ridl_init_error_fails_build() {
+        let err = Context::builder()
+            .ridl_init(|_| Err("field installer threw".to_string()))
+            .build()
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("ridl_init failed: field installer threw"),
+            "{err}"
+        );
+    }
+}
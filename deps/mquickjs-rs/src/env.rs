@@ -30,6 +30,16 @@ impl<'ctx> Env<'ctx> {
         self.hs.handle(v)
     }
 
+    /// Run `f` and release the handles it created; see `HandleScope::scoped`.
+    ///
+    /// Use it for loop bodies that call `env.handle` (directly or via `Array::get` etc.).
+    pub fn scoped<R>(&mut self, f: impl FnOnce(&mut Env<'ctx>) -> R) -> R {
+        let mark = self.hs.len();
+        let r = f(self);
+        self.hs.truncate(mark);
+        r
+    }
+
     pub fn return_safe<T>(&mut self, v: Local<'ctx, T>) -> ReturnSafe<T> {
         // Pin via HandleScope so the value is reachable during the native->JS return boundary.
         let h = self.handle(v);
@@ -48,6 +58,15 @@ impl<'ctx> Env<'ctx> {
         v.raw()
     }
 
+    /// `globalThis`.
+    pub fn global<'hs>(&'hs mut self) -> Result<Handle<'hs, 'ctx, Object>, String> {
+        let raw = unsafe { mquickjs_ffi::JS_GetGlobalObject(self.scope.ctx_raw()) };
+        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+            return Err("Exception during JS_GetGlobalObject".to_string());
+        }
+        Ok(self.handle(self.scope.value(raw).try_into_object(self.scope)?))
+    }
+
     pub fn obj<'hs>(&'hs mut self) -> Result<Handle<'hs, 'ctx, Object>, String> {
         let raw = unsafe { mquickjs_ffi::JS_NewObject(self.scope.ctx_raw()) };
         if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
@@ -72,11 +91,7 @@ impl<'ctx> Env<'ctx> {
     }
 
     pub fn str<'hs>(&'hs mut self, s: &str) -> Result<Handle<'hs, 'ctx, Value>, String> {
-        let c = std::ffi::CString::new(s).map_err(|_| "Invalid string".to_string())?;
-        let raw = unsafe { mquickjs_ffi::JS_NewString(self.scope.ctx_raw(), c.as_ptr()) };
-        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
-            return Err("Exception during JS_NewString".to_string());
-        }
+        let raw = unsafe { crate::handles::string::new_string_raw(self.scope.ctx_raw(), s)? };
         Ok(self.handle(self.scope.value(raw)))
     }
 
@@ -107,14 +122,7 @@ impl<'ctx> Env<'ctx> {
     }
 
     pub fn get_string(&self, v: Local<'ctx, Value>) -> Result<String, String> {
-        let mut cstr_buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
-        let result_ptr =
-            unsafe { mquickjs_ffi::JS_ToCString(self.scope.ctx_raw(), v.as_raw(), &mut cstr_buf) };
-        if result_ptr.is_null() {
-            return Err("Failed to convert Value to string".to_string());
-        }
-        Ok(unsafe { std::ffi::CStr::from_ptr(result_ptr) }
-            .to_string_lossy()
-            .into_owned())
+        unsafe { crate::handles::string::to_rust_string_raw(self.scope.ctx_raw(), v.as_raw()) }
+            .ok_or_else(|| "Failed to convert Value to string".to_string())
     }
 }
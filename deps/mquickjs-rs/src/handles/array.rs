@@ -1,52 +1,17 @@
 use crate::env::Env;
 use crate::handles::any::Any;
+use crate::handles::js_call::{call_raw, is_exception, take_exception_message, TempRoots};
 use crate::handles::local::{Array, Local, Object, Value};
 use crate::handles::scope::Scope;
+use crate::handles::value_kind::ValueKind;
 use crate::mquickjs_ffi;
 
 impl<'ctx> Local<'ctx, Value> {
     pub fn is_array(&self, scope: &Scope<'ctx>) -> bool {
-        // mquickjs does not export JS_IsArray; use Array.isArray(x).
-        // We implement it via: globalThis.Array.isArray(value)
-        let ctx = scope.ctx_raw();
-        unsafe {
-            let global = mquickjs_ffi::JS_GetGlobalObject(ctx);
-            if mquickjs_ffi::js_value_special_tag(global) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32)
-            {
-                return false;
-            }
-
-            let array_ctor =
-                mquickjs_ffi::JS_GetPropertyStr(ctx, global, b"Array\0".as_ptr() as *const _);
-            if mquickjs_ffi::js_value_special_tag(array_ctor)
-                == (mquickjs_ffi::JS_TAG_EXCEPTION as u32)
-            {
-                return false;
-            }
-
-            let is_array =
-                mquickjs_ffi::JS_GetPropertyStr(ctx, array_ctor, b"isArray\0".as_ptr() as *const _);
-            if mquickjs_ffi::js_value_special_tag(is_array)
-                == (mquickjs_ffi::JS_TAG_EXCEPTION as u32)
-            {
-                return false;
-            }
-
-            // call: Array.isArray(value)
-            mquickjs_ffi::JS_PushArg(ctx, self.as_raw());
-            mquickjs_ffi::JS_PushArg(ctx, is_array);
-            mquickjs_ffi::JS_PushArg(ctx, array_ctor);
-            let ret = mquickjs_ffi::JS_Call(ctx, 1);
-            if mquickjs_ffi::js_value_special_tag(ret) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
-                return false;
-            }
-
-            let mut out = 0i32;
-            if mquickjs_ffi::JS_ToInt32(ctx, &mut out, ret) != 0 {
-                return false;
-            }
-            out != 0
-        }
+        // mquickjs does not export JS_IsArray, but every array is a JS_CLASS_ARRAY object,
+        // so a class id check is exact and avoids a round-trip through `Array.isArray`.
+        (unsafe { mquickjs_ffi::JS_GetClassID(scope.ctx_raw(), self.as_raw()) })
+            == mquickjs_ffi::JSObjectClassEnum_JS_CLASS_ARRAY as i32
     }
 
     pub fn try_into_array(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Array>, String> {
@@ -58,51 +23,97 @@ impl<'ctx> Local<'ctx, Value> {
     }
 }
 
+/// Above this many values `extend_from_slice` roots its input and pushes in chunks, so a
+/// single call never needs more than this many JS stack slots.
+const PUSH_CHUNK: usize = 64;
+
 impl<'ctx> Local<'ctx, Array> {
     fn as_object(&self) -> Local<'ctx, Object> {
         Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id())
     }
 
-    pub fn len(&self, env: &Env<'ctx>) -> Result<u32, String> {
-        // length is on array prototype and is a getter.
-        let length = self.as_object().get_property(env.scope(), "length")?;
-        env.get_number(length).map(|n| n as u32)
-    }
-
-    pub fn get<'hs>(&self, env: &'hs mut Env<'ctx>, index: u32) -> Result<Any<'hs, 'ctx>, String> {
+    fn raw_len(&self, scope: &Scope<'ctx>) -> Result<u32, String> {
+        let ctx = scope.ctx_raw();
         let raw = unsafe {
-            mquickjs_ffi::JS_GetPropertyUint32(env.scope().ctx_raw(), self.as_raw(), index)
+            mquickjs_ffi::JS_GetPropertyStr(ctx, self.as_raw(), b"length\0".as_ptr() as *const _)
         };
-        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+        if is_exception(raw) {
+            return Err("Exception during array length".to_string());
+        }
+        // Array lengths are small integers in practice; take the immediate-int fast path
+        // before falling back to a full number conversion.
+        if (raw as u32) & 1 == mquickjs_ffi::JS_TAG_INT as u32 {
+            let mut out = 0i32;
+            if unsafe { mquickjs_ffi::JS_ToInt32(ctx, &mut out, raw) } == 0 {
+                return Ok(out as u32);
+            }
+        }
+        let mut out = 0.0f64;
+        if unsafe { mquickjs_ffi::JS_ToNumber(ctx, &mut out, raw) } != 0 {
+            return Err("Failed to convert array length".to_string());
+        }
+        Ok(out as u32)
+    }
+
+    fn raw_get(&self, scope: &Scope<'ctx>, index: u32) -> Result<mquickjs_ffi::JSValue, String> {
+        let raw =
+            unsafe { mquickjs_ffi::JS_GetPropertyUint32(scope.ctx_raw(), self.as_raw(), index) };
+        if is_exception(raw) {
             return Err("Exception during array get".to_string());
         }
-        Ok(Any::from_value(env.handle(env.scope().value(raw))))
+        Ok(raw)
     }
 
-    pub fn set(
+    fn raw_set(
         &self,
-        env: &Env<'ctx>,
+        scope: &Scope<'ctx>,
         index: u32,
-        value: Local<'ctx, Value>,
+        value: mquickjs_ffi::JSValue,
     ) -> Result<(), String> {
         let r = unsafe {
-            mquickjs_ffi::JS_SetPropertyUint32(
-                env.scope().ctx_raw(),
-                self.as_raw(),
-                index,
-                value.as_raw(),
-            )
+            mquickjs_ffi::JS_SetPropertyUint32(scope.ctx_raw(), self.as_raw(), index, value)
         };
-        if mquickjs_ffi::js_value_special_tag(r) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+        if is_exception(r) {
             return Err("Exception during array set".to_string());
         }
         Ok(())
     }
 
+    fn set_len(&self, scope: &Scope<'ctx>, len: u32) -> Result<(), String> {
+        let ctx = scope.ctx_raw();
+        let new_len = unsafe { mquickjs_ffi::JS_NewUint32(ctx, len) };
+        if is_exception(new_len) {
+            return Err("Exception during JS_NewUint32".to_string());
+        }
+        self.as_object()
+            .set_property(scope, "length", scope.value(new_len))
+            .map_err(|_| "Exception during array set length".to_string())
+    }
+
+    pub fn len(&self, env: &Env<'ctx>) -> Result<u32, String> {
+        self.raw_len(env.scope())
+    }
+
+    pub fn is_empty(&self, env: &Env<'ctx>) -> Result<bool, String> {
+        Ok(self.len(env)? == 0)
+    }
+
+    pub fn get<'hs>(&self, env: &'hs mut Env<'ctx>, index: u32) -> Result<Any<'hs, 'ctx>, String> {
+        let raw = self.raw_get(env.scope(), index)?;
+        Ok(Any::from_value(env.handle(env.scope().value(raw))))
+    }
+
+    pub fn set(
+        &self,
+        env: &Env<'ctx>,
+        index: u32,
+        value: Local<'ctx, Value>,
+    ) -> Result<(), String> {
+        self.raw_set(env.scope(), index, value.as_raw())
+    }
+
     pub fn push(&self, env: &Env<'ctx>, value: Local<'ctx, Value>) -> Result<u32, String> {
-        let index = self.len(env)?;
-        self.set(env, index, value)?;
-        Ok(index + 1)
+        self.extend_from_slice(env, &[value])
     }
 
     pub fn pop<'hs>(&self, env: &'hs mut Env<'ctx>) -> Result<Any<'hs, 'ctx>, String> {
@@ -113,30 +124,198 @@ impl<'ctx> Local<'ctx, Array> {
             ));
         }
 
-        let ctx = env.scope().ctx_raw();
         let last_index = len - 1;
 
         // Fetch value first (needs &mut Env to create a rooted Any).
         let v = self.get(env, last_index)?;
 
         // Shrink by setting `length`.
-        let new_len = unsafe { mquickjs_ffi::JS_NewUint32(ctx, last_index) };
-        if mquickjs_ffi::js_value_special_tag(new_len) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
-            return Err("Exception during JS_NewUint32".to_string());
+        self.set_len(env.scope(), last_index)?;
+
+        Ok(v)
+    }
+
+    /// Visit every element in index order; the length is read once up front.
+    ///
+    /// The callback receives unrooted `Local`s; pin them via `Env::handle` if the callback
+    /// allocates and needs them to survive a GC.
+    pub fn for_each(
+        &self,
+        env: &Env<'ctx>,
+        mut f: impl FnMut(u32, Local<'ctx, Value>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let scope = env.scope();
+        let len = self.raw_len(scope)?;
+        for i in 0..len {
+            f(i, scope.value(self.raw_get(scope, i)?))?;
         }
+        Ok(())
+    }
 
-        let r = unsafe {
-            mquickjs_ffi::JS_SetPropertyStr(
-                ctx,
-                self.as_raw(),
-                b"length\0".as_ptr() as *const _,
-                new_len,
-            )
+    /// Every element, read with `JS_GetPropertyUint32` (mquickjs exports no bulk accessor).
+    ///
+    /// A read may run a getter and allocate, so the array and the elements read so far stay
+    /// rooted until the last read. The values are only valid until the next allocation.
+    fn elements(&self, scope: &Scope<'ctx>) -> Result<Vec<mquickjs_ffi::JSValue>, String> {
+        let ctx = scope.ctx_raw();
+        let len = self.raw_len(scope)?;
+        let mut roots = TempRoots::new(ctx);
+        let arr = roots.push(self.as_raw());
+        for i in 0..len {
+            let v = unsafe { mquickjs_ffi::JS_GetPropertyUint32(ctx, roots.get(arr), i) };
+            if is_exception(v) {
+                return Err(take_exception_message(ctx, "array get"));
+            }
+            roots.push(v);
+        }
+        Ok(roots.values().split_off(arr + 1))
+    }
+
+    /// Snapshot all elements.
+    ///
+    /// Like any `Local`, the returned values are unrooted: they are only valid until the next
+    /// allocation (the GC moves objects, reachable or not). Pin the ones you keep via
+    /// `Env::handle`, or use `for_each` / `get`.
+    pub fn to_vec(&self, env: &Env<'ctx>) -> Result<Vec<Local<'ctx, Value>>, String> {
+        let scope = env.scope();
+        Ok(self
+            .elements(scope)?
+            .into_iter()
+            .map(|v| scope.value(v))
+            .collect())
+    }
+
+    /// Collect a numeric array. Fails on the first non-number element (no coercion).
+    pub fn to_f64_vec(&self, env: &Env<'ctx>) -> Result<Vec<f64>, String> {
+        let scope = env.scope();
+        // Reading numbers does not allocate, so the copied elements stay valid throughout.
+        let elements = self.elements(scope)?;
+        let mut out = Vec::with_capacity(elements.len());
+        for (i, v) in elements.into_iter().enumerate() {
+            let v = scope.value(v);
+            match v.as_f64(scope) {
+                Some(n) => out.push(n),
+                None => {
+                    return Err(format!(
+                        "array element {i}: expected number, got {}",
+                        v.kind(scope).name()
+                    ));
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Collect a string array. Fails on the first non-string element (no coercion).
+    pub fn to_string_vec(&self, env: &Env<'ctx>) -> Result<Vec<String>, String> {
+        let scope = env.scope();
+        let ctx = scope.ctx_raw();
+        let len = self.raw_len(scope)?;
+        // String conversion may allocate; re-read the array from its root for every element.
+        let mut roots = TempRoots::new(ctx);
+        let arr = roots.push(self.as_raw());
+        let mut out = Vec::with_capacity(len as usize);
+        for i in 0..len {
+            let v = unsafe { mquickjs_ffi::JS_GetPropertyUint32(ctx, roots.get(arr), i) };
+            if is_exception(v) {
+                return Err(take_exception_message(ctx, "array get"));
+            }
+            let v = scope.value(v);
+            if v.kind(scope) != ValueKind::String {
+                return Err(format!(
+                    "array element {i}: expected string, got {}",
+                    v.kind(scope).name()
+                ));
+            }
+            out.push(env.get_string(v)?);
+        }
+        Ok(out)
+    }
+
+    /// Append `values` at the end with `Array.prototype.push` (one call per `PUSH_CHUNK`
+    /// values). Returns the new length.
+    pub fn extend_from_slice(
+        &self,
+        env: &Env<'ctx>,
+        values: &[Local<'ctx, Value>],
+    ) -> Result<u32, String> {
+        let scope = env.scope();
+        let ctx = scope.ctx_raw();
+        if values.is_empty() {
+            return self.raw_len(scope);
+        }
+
+        // Growing the array allocates: keep the array, `push` and every value rooted.
+        let mut roots = TempRoots::new(ctx);
+        let arr = roots.push(self.as_raw());
+        let push = unsafe {
+            mquickjs_ffi::JS_GetPropertyStr(ctx, roots.get(arr), b"push\0".as_ptr() as *const _)
         };
-        if mquickjs_ffi::js_value_special_tag(r) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
-            return Err("Exception during array pop (set length)".to_string());
+        if is_exception(push) {
+            return Err(take_exception_message(ctx, "array push"));
+        }
+        let push = roots.push(push);
+        let first = push + 1;
+        for v in values {
+            roots.push(v.as_raw());
         }
 
-        Ok(v)
+        let mut new_len = mquickjs_ffi::JS_UNDEFINED;
+        for start in (0..values.len()).step_by(PUSH_CHUNK) {
+            let end = (start + PUSH_CHUNK).min(values.len());
+            let chunk: Vec<_> = (first + start..first + end).map(|i| roots.get(i)).collect();
+            new_len = call_raw(ctx, roots.get(push), roots.get(arr), &chunk, "array push")?;
+        }
+
+        let mut out = 0.0f64;
+        if unsafe { mquickjs_ffi::JS_ToNumber(ctx, &mut out, new_len) } != 0 {
+            return Err("Failed to convert array length".to_string());
+        }
+        Ok(out as u32)
+    }
+
+    /// `Array.prototype.splice` semantics with clamped `start`/`delete_count`.
+    ///
+    /// Removes `delete_count` elements at `start`, inserts `items` in their place and
+    /// returns the removed elements, all in one call to the builtin. The removed `Local`s are
+    /// unrooted: pin them via `Env::handle` before allocating.
+    pub fn splice(
+        &self,
+        env: &Env<'ctx>,
+        start: u32,
+        delete_count: u32,
+        items: &[Local<'ctx, Value>],
+    ) -> Result<Vec<Local<'ctx, Value>>, String> {
+        let scope = env.scope();
+        let ctx = scope.ctx_raw();
+
+        // The builtin clamps `start`/`delete_count` itself.
+        let mut roots = TempRoots::new(ctx);
+        let arr = roots.push(self.as_raw());
+        for v in items {
+            roots.push(v.as_raw());
+        }
+        let start_v = roots.push(unsafe { mquickjs_ffi::JS_NewUint32(ctx, start) });
+        let count_v = roots.push(unsafe { mquickjs_ffi::JS_NewUint32(ctx, delete_count) });
+        let splice = unsafe {
+            mquickjs_ffi::JS_GetPropertyStr(ctx, roots.get(arr), b"splice\0".as_ptr() as *const _)
+        };
+        if is_exception(splice) {
+            return Err(take_exception_message(ctx, "array splice"));
+        }
+
+        let mut args = vec![roots.get(start_v), roots.get(count_v)];
+        args.extend((0..items.len()).map(|k| roots.get(arr + 1 + k)));
+        let removed = call_raw(ctx, splice, roots.get(arr), &args, "array splice")?;
+        drop(roots);
+        let removed = scope
+            .value(removed)
+            .try_into_array(scope)
+            .map_err(|_| "array splice did not return an array".to_string())?;
+        Ok(removed
+            .elements(scope)?
+            .into_iter()
+            .map(|v| scope.value(v))
+            .collect())
     }
 }
@@ -79,3 +79,135 @@ fn array_pop_shrinks() {
     let len = arr_local.len(&env).unwrap();
     assert_eq!(len, 1);
 }
+
+#[test]
+fn array_bulk_extend_and_collect() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let arr = env.array().unwrap();
+    let arr_local = scope.value(arr.as_raw()).try_into_array(&scope).unwrap();
+    assert!(scope.value(arr_local.as_raw()).is_array(&scope));
+
+    let vals = [
+        env.int_local(1).unwrap(),
+        env.int_local(2).unwrap(),
+        env.int_local(3).unwrap(),
+    ];
+    let new_len = arr_local.extend_from_slice(&env, &vals).unwrap();
+    assert_eq!(new_len, 3);
+
+    assert_eq!(arr_local.to_f64_vec(&env).unwrap(), vec![1.0, 2.0, 3.0]);
+    assert_eq!(arr_local.to_vec(&env).unwrap().len(), 3);
+
+    let mut sum = 0.0;
+    arr_local
+        .for_each(&env, |_, v| {
+            sum += env.get_number(v)?;
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(sum, 6.0);
+
+    // strings are not coerced into numbers (and vice versa)
+    let s = env.str("x").unwrap();
+    let s_raw = s.as_raw();
+    arr_local.push(&env, scope.value(s_raw)).unwrap();
+    assert!(arr_local.to_f64_vec(&env).is_err());
+    assert!(arr_local.to_string_vec(&env).is_err());
+}
+
+#[test]
+fn array_splice_grows_and_shrinks() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let arr = env.array().unwrap();
+    let arr_local = scope.value(arr.as_raw()).try_into_array(&scope).unwrap();
+
+    let vals: Vec<_> = (0..5).map(|i| env.int_local(i).unwrap()).collect();
+    arr_local.extend_from_slice(&env, &vals).unwrap();
+
+    // [0,1,2,3,4] -> remove 1,2,3 -> [0,4]
+    let removed = arr_local.splice(&env, 1, 3, &[]).unwrap();
+    assert_eq!(removed.len(), 3);
+    assert_eq!(arr_local.to_f64_vec(&env).unwrap(), vec![0.0, 4.0]);
+
+    // [0,4] -> insert 7,8,9 at 1 -> [0,7,8,9,4]
+    let items: Vec<_> = (7..10).map(|i| env.int_local(i).unwrap()).collect();
+    let removed = arr_local.splice(&env, 1, 0, &items).unwrap();
+    assert!(removed.is_empty());
+    assert_eq!(
+        arr_local.to_f64_vec(&env).unwrap(),
+        vec![0.0, 7.0, 8.0, 9.0, 4.0]
+    );
+
+    // out-of-range start/count are clamped
+    let removed = arr_local.splice(&env, 4, 100, &[]).unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(arr_local.len(&env).unwrap(), 4);
+}
+
+#[test]
+fn array_extend_spans_push_chunks() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.eval("var strs = []; for (var i = 0; i < 100; i++) strs.push('s' + i);")
+        .unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let arr = env.array().unwrap();
+    let arr_local = scope.value(arr.as_raw()).try_into_array(&scope).unwrap();
+
+    // More values than one `push` call takes; every chunk must land in order.
+    let vals: Vec<_> = (0..200).map(|i| env.int_local(i).unwrap()).collect();
+    assert_eq!(arr_local.extend_from_slice(&env, &vals).unwrap(), 200);
+    assert_eq!(arr_local.extend_from_slice(&env, &[]).unwrap(), 200);
+    let nums = arr_local.to_f64_vec(&env).unwrap();
+    assert_eq!(nums.len(), 200);
+    assert_eq!((nums[0], nums[199]), (0.0, 199.0));
+
+    let removed = arr_local.splice(&env, 198, 10, &[]).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert_eq!(env.get_number(removed[1]).unwrap(), 199.0);
+    assert_eq!(arr_local.len(&env).unwrap(), 198);
+
+    let global = env.global().unwrap().as_local();
+    let strs: crate::handles::local::Local<crate::handles::local::Array> =
+        global.get_path(&env, "strs").unwrap();
+    let strings = strs.to_string_vec(&env).unwrap();
+    assert_eq!(strings.len(), 100);
+    assert_eq!((strings[0].as_str(), strings[99].as_str()), ("s0", "s99"));
+}
+
+#[test]
+fn array_to_string_vec_survives_gc_between_elements() {
+    // A small heap, so converting the strings collects (and moves the array) along the way.
+    let mut ctx = Context::new(64 * 1024).unwrap();
+    ctx.eval("var words = []; for (var i = 0; i < 300; i++) words.push('word' + i);")
+        .unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let words = env
+        .global()
+        .unwrap()
+        .as_local()
+        .get_property(&scope, "words")
+        .unwrap()
+        .try_into_array(&scope)
+        .unwrap();
+    let got = words.to_string_vec(&env).unwrap();
+    let want: Vec<String> = (0..300).map(|i| format!("word{i}")).collect();
+    assert_eq!(got, want);
+}
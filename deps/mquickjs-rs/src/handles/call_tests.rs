@@ -0,0 +1,168 @@
+use crate::handles::local::{Function, Local, Object, Value};
+use crate::Context;
+use crate::Env;
+
+fn setup(ctx: &mut Context) {
+    ctx.eval(
+        r#"
+        var app = {
+            handlers: {
+                onTick: function (a, b) { return a + b; },
+            },
+            name: "demo",
+        };
+        function Point(x, y) { this.x = x; this.y = y; }
+        Point.prototype.sum = function () { return this.x + this.y; };
+        function fail() { throw new Error("boom"); }
+        "#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn call_with_tuple_args_and_typed_result() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    setup(&mut ctx);
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    let global = env.global().unwrap().as_local();
+    let on_tick: Local<Function> = global.get_path(&env, "app.handlers.onTick").unwrap();
+
+    let n: i32 = on_tick.call_with(&env, (), (2, 3)).unwrap();
+    assert_eq!(n, 5);
+    let s: String = on_tick.call_with(&env, (), ("a", "b")).unwrap();
+    assert_eq!(s, "ab");
+    let f: f64 = on_tick.call_with(&env, (), (1.5, 2)).unwrap();
+    assert_eq!(f, 3.5);
+
+    // Strict result conversion: a string is not a number.
+    let r: Result<i32, String> = on_tick.call_with(&env, (), ("1", "2"));
+    assert!(r.unwrap_err().contains("expected number"));
+
+    let name: String = global.get_path(&env, "app.name").unwrap();
+    assert_eq!(name, "demo");
+    let missing: Option<i32> = global.get_path(&env, "app.nothing").unwrap();
+    assert_eq!(missing, None);
+    let err = global
+        .get_path::<Local<Value>>(&env, "app.nothing.deeper")
+        .unwrap_err();
+    assert!(err.contains("app.nothing"));
+}
+
+#[test]
+fn construct_and_call_method() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    setup(&mut ctx);
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    let global = env.global().unwrap().as_local();
+    let point: Local<Function> = global.get_path(&env, "Point").unwrap();
+    let p: Local<Object> = point.construct(&env, (3, 4)).unwrap();
+
+    let x: i32 = p.get_path(&env, "x").unwrap();
+    assert_eq!(x, 3);
+    let sum: i32 = p.call_method(&env, "sum", ()).unwrap();
+    assert_eq!(sum, 7);
+
+    assert!(p.call_method::<()>(&env, "nope", ()).is_err());
+}
+
+#[test]
+fn get_path_and_construct_survive_gc_on_the_way() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    setup(&mut ctx);
+    ctx.eval(
+        r#"
+        var lazy = {
+            get inner() {
+                // Enough garbage to force collections inside the walk.
+                for (var i = 0; i < 50000; i++) [i, {}];
+                return { label: "after gc" };
+            },
+        };
+        "#,
+    )
+    .unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    let global = env.global().unwrap().as_local();
+    let label: String = global.get_path(&env, "lazy.inner.label").unwrap();
+    assert_eq!(label, "after gc");
+
+    // The `new` helper is compiled once and stays rooted across collections.
+    for i in 0..20 {
+        let point: Local<Function> = global.get_path(&env, "Point").unwrap();
+        let p: Local<Object> = point.construct(&env, (i, 1)).unwrap();
+        let sum: i32 = p.call_method(&env, "sum", ()).unwrap();
+        assert_eq!(sum, i + 1);
+        unsafe { crate::mquickjs_ffi::JS_GC(scope.ctx_raw()) };
+    }
+}
+
+#[test]
+fn call_with_propagates_exceptions() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    setup(&mut ctx);
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    let global = env.global().unwrap().as_local();
+    let err = global.call_method::<()>(&env, "fail", ()).unwrap_err();
+    assert!(err.contains("boom"));
+}
+
+#[test]
+fn vec_and_ridl_struct_conversions_stay_rooted() {
+    use crate::ridl_runtime::{StructReader, StructWriter};
+    use crate::IntoJs;
+
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.eval(
+        r#"
+        function describe(v) { return JSON.stringify(v); }
+        var opts = {
+            get fatal() { for (var i = 0; i < 50000; i++) [i, {}]; return true; },
+            label: "x",
+        };
+        "#,
+    )
+    .unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let describe: Local<Function> = global.get_path(&env, "describe").unwrap();
+
+    let names: Vec<String> = (0..200).map(|i| format!("name-{i}")).collect();
+    let arr = names.into_js(&scope).unwrap();
+    let json: String = describe.call_with(&env, (), (arr,)).unwrap();
+    assert!(json.starts_with("[\"name-0\",\"name-1\","));
+    assert!(json.ends_with("\"name-199\"]"));
+
+    let w = StructWriter::new(&scope).unwrap();
+    w.field("size", 3.5).unwrap();
+    w.field("name", "a".repeat(1000)).unwrap();
+    w.field("missing", None::<bool>).unwrap();
+    let json: String = describe.call_with(&env, (), (w.finish(),)).unwrap();
+    assert!(json.starts_with("{\"size\":3.5,\"name\":\"aaa"));
+    assert!(json.ends_with("\"missing\":null}"));
+
+    // A getter that collects garbage between two field reads.
+    let opts: Local<Value> = global.get_path(&env, "opts").unwrap();
+    let r = StructReader::new(&scope, "Opts", opts).unwrap();
+    assert_eq!(r.field::<Option<bool>>("fatal").unwrap(), Some(true));
+    assert_eq!(r.field::<String>("label").unwrap(), "x");
+    assert_eq!(r.field::<Option<bool>>("ignoreBOM").unwrap(), None);
+    let err = r.field::<bool>("ignoreBOM").unwrap_err();
+    assert_eq!(err, "Opts.ignoreBOM: expected bool, got undefined");
+
+    let not_obj = scope.value(crate::mquickjs_ffi::JS_NULL);
+    assert!(StructReader::new(&scope, "Opts", not_obj).is_err());
+}
@@ -0,0 +1,298 @@
+//! Rust <-> JS value conversion for the typed call helpers (`call_with`, `call_method`,
+//! `construct`, `get_path`).
+//!
+//! Conversions are strict in the same way as the `ValueKind` accessors: `FromJs` never
+//! coerces (a string is not a number), and integer targets accept only integral numbers
+//! that fit.
+
+use crate::handles::handle::Handle;
+use crate::handles::js_call::TempRoots;
+use crate::handles::local::{Array, Function, JsString, Local, Object, Value};
+use crate::handles::scope::Scope;
+use crate::handles::string::new_string_raw;
+use crate::handles::value_kind::ValueKind;
+use crate::mquickjs_ffi;
+
+/// A Rust value that can be turned into a JS value.
+pub trait IntoJs<'ctx> {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String>;
+}
+
+/// A Rust value that can be read back from a JS value.
+pub trait FromJs<'ctx>: Sized {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String>;
+}
+
+/// An argument list: tuples of `IntoJs` values (up to 8), or a slice of `Local`s.
+pub trait JsArgs<'ctx> {
+    fn into_js_args(self, scope: &Scope<'ctx>) -> Result<Vec<Local<'ctx, Value>>, String>;
+}
+
+fn check_ctx<'ctx, T>(scope: &Scope<'ctx>, v: &Local<'ctx, T>) -> Result<(), String> {
+    if v.ctx_id() != scope.context_id() {
+        return Err("cross-context value".to_string());
+    }
+    Ok(())
+}
+
+fn type_error<'ctx>(scope: &Scope<'ctx>, expected: &str, v: &Local<'ctx, Value>) -> String {
+    format!("expected {expected}, got {}", v.kind(scope).name())
+}
+
+// ---- IntoJs ----
+
+/// `()` is `undefined` (e.g. as the `this` of a plain function call).
+impl<'ctx> IntoJs<'ctx> for () {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        Ok(scope.value(mquickjs_ffi::JS_UNDEFINED))
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for bool {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        Ok(scope.value(mquickjs_ffi::js_mkbool(self)))
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for i32 {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        let raw = unsafe { mquickjs_ffi::JS_NewInt32(scope.ctx_raw(), self) };
+        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+            return Err("Exception during JS_NewInt32".to_string());
+        }
+        Ok(scope.value(raw))
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for u32 {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        let raw = unsafe { mquickjs_ffi::JS_NewUint32(scope.ctx_raw(), self) };
+        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+            return Err("Exception during JS_NewUint32".to_string());
+        }
+        Ok(scope.value(raw))
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for f64 {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        let raw = unsafe { mquickjs_ffi::JS_NewFloat64(scope.ctx_raw(), self) };
+        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+            return Err("Exception during JS_NewFloat64".to_string());
+        }
+        Ok(scope.value(raw))
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for &str {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        Ok(scope.value(unsafe { new_string_raw(scope.ctx_raw(), self)? }))
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for &String {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        self.as_str().into_js(scope)
+    }
+}
+
+impl<'ctx> IntoJs<'ctx> for String {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        self.as_str().into_js(scope)
+    }
+}
+
+/// `None` is `null`.
+impl<'ctx, T: IntoJs<'ctx>> IntoJs<'ctx> for Option<T> {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        match self {
+            Some(v) => v.into_js(scope),
+            None => Ok(scope.value(mquickjs_ffi::JS_NULL)),
+        }
+    }
+}
+
+impl<'ctx, T> IntoJs<'ctx> for Local<'ctx, T> {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        check_ctx(scope, &self)?;
+        Ok(scope.value(self.as_raw()))
+    }
+}
+
+impl<'ctx, T> IntoJs<'ctx> for Handle<'_, 'ctx, T> {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        self.as_local().into_js(scope)
+    }
+}
+
+/// A new JS array; it stays rooted while the elements are converted.
+impl<'ctx, T: IntoJs<'ctx>> IntoJs<'ctx> for Vec<T> {
+    fn into_js(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Value>, String> {
+        let raw = unsafe { mquickjs_ffi::JS_NewArray(scope.ctx_raw(), 0) };
+        if mquickjs_ffi::js_value_special_tag(raw) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+            return Err("Exception during JS_NewArray".to_string());
+        }
+        let mut roots = TempRoots::new(scope.ctx_raw());
+        let arr = roots.push(raw);
+        for (i, item) in self.into_iter().enumerate() {
+            let v = item.into_js(scope)?;
+            let a = scope.value(roots.get(arr)).try_into_object(scope)?;
+            a.set_index(scope, i as u32, v)?;
+        }
+        Ok(scope.value(roots.get(arr)))
+    }
+}
+
+// ---- FromJs ----
+
+/// Discards the value.
+impl<'ctx> FromJs<'ctx> for () {
+    fn from_js(_scope: &Scope<'ctx>, _v: Local<'ctx, Value>) -> Result<Self, String> {
+        Ok(())
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for bool {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.as_bool().ok_or_else(|| type_error(scope, "bool", &v))
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for f64 {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.as_f64(scope)
+            .ok_or_else(|| type_error(scope, "number", &v))
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for i32 {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        if let Some(n) = v.as_i32(scope) {
+            return Ok(n);
+        }
+        let n = f64::from_js(scope, v)?;
+        if n.fract() == 0.0 && n >= i32::MIN as f64 && n <= i32::MAX as f64 {
+            Ok(n as i32)
+        } else {
+            Err(format!("expected i32, got {n}"))
+        }
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for u32 {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        let n = f64::from_js(scope, v)?;
+        if n.fract() == 0.0 && n >= 0.0 && n <= u32::MAX as f64 {
+            Ok(n as u32)
+        } else {
+            Err(format!("expected u32, got {n}"))
+        }
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for String {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.try_into_string(scope)?.to_rust_string(scope)
+    }
+}
+
+/// `null` / `undefined` are `None`.
+impl<'ctx, T: FromJs<'ctx>> FromJs<'ctx> for Option<T> {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        if v.kind(scope).is_nullish() {
+            return Ok(None);
+        }
+        T::from_js(scope, v).map(Some)
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for Local<'ctx, Value> {
+    fn from_js(_scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        Ok(v)
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for Local<'ctx, Object> {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.as_object(scope)
+            .ok_or_else(|| type_error(scope, "object", &v))
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for Local<'ctx, Function> {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.as_function(scope)
+            .ok_or_else(|| type_error(scope, "function", &v))
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for Local<'ctx, Array> {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.as_array(scope)
+            .ok_or_else(|| type_error(scope, "array", &v))
+    }
+}
+
+impl<'ctx> FromJs<'ctx> for Local<'ctx, JsString> {
+    fn from_js(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> Result<Self, String> {
+        v.try_into_string(scope)
+    }
+}
+
+// ---- JsArgs ----
+
+impl<'ctx> JsArgs<'ctx> for &[Local<'ctx, Value>] {
+    fn into_js_args(self, scope: &Scope<'ctx>) -> Result<Vec<Local<'ctx, Value>>, String> {
+        for v in self {
+            check_ctx(scope, v)?;
+        }
+        Ok(self.to_vec())
+    }
+}
+
+impl<'ctx> JsArgs<'ctx> for Vec<Local<'ctx, Value>> {
+    fn into_js_args(self, scope: &Scope<'ctx>) -> Result<Vec<Local<'ctx, Value>>, String> {
+        self.as_slice().into_js_args(scope)
+    }
+}
+
+macro_rules! impl_js_args_tuple {
+    ($($name:ident),*) => {
+        impl<'ctx, $($name: IntoJs<'ctx>),*> JsArgs<'ctx> for ($($name,)*) {
+            #[allow(non_snake_case, unused_variables, unused_mut)]
+            fn into_js_args(self, scope: &Scope<'ctx>) -> Result<Vec<Local<'ctx, Value>>, String> {
+                let ($($name,)*) = self;
+                // Keep earlier arguments rooted while later ones allocate.
+                let mut roots = TempRoots::new(scope.ctx_raw());
+                $(roots.push($name.into_js(scope)?.as_raw());)*
+                Ok(roots.values().into_iter().map(|raw| scope.value(raw)).collect())
+            }
+        }
+    };
+}
+
+impl_js_args_tuple!();
+impl_js_args_tuple!(A);
+impl_js_args_tuple!(A, B);
+impl_js_args_tuple!(A, B, C);
+impl_js_args_tuple!(A, B, C, D);
+impl_js_args_tuple!(A, B, C, D, E);
+impl_js_args_tuple!(A, B, C, D, E, F);
+impl_js_args_tuple!(A, B, C, D, E, F, G);
+impl_js_args_tuple!(A, B, C, D, E, F, G, H);
+
+/// Whether `v` is callable, for error messages in the call helpers.
+pub(crate) fn expect_function<'ctx>(
+    scope: &Scope<'ctx>,
+    v: Local<'ctx, Value>,
+    what: &str,
+) -> Result<Local<'ctx, Function>, String> {
+    if v.kind(scope) == ValueKind::Function {
+        Ok(Local::from_raw_for_same_ctx(v.as_raw()).with_ctx_id(v.ctx_id()))
+    } else {
+        Err(format!(
+            "{what} is not a function (got {})",
+            v.kind(scope).name()
+        ))
+    }
+}
@@ -1,5 +1,6 @@
-use std::ffi::CStr;
-
+use crate::env::Env;
+use crate::handles::convert::{FromJs, IntoJs, JsArgs};
+use crate::handles::js_call::{cached_helper, call_raw, TempRoots};
 use crate::handles::local::{Function, Local, Value};
 use crate::handles::scope::Scope;
 use crate::mquickjs_ffi;
@@ -43,12 +44,9 @@ impl<'ctx> Local<'ctx, Function> {
         {
             let exception = unsafe { mquickjs_ffi::JS_GetException(scope.ctx()) };
 
-            let mut cstr_buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
-            let error_ptr =
-                unsafe { mquickjs_ffi::JS_ToCString(scope.ctx(), exception, &mut cstr_buf) };
-
-            if !error_ptr.is_null() {
-                let error_str = unsafe { CStr::from_ptr(error_ptr).to_string_lossy().into_owned() };
+            if let Some(error_str) =
+                unsafe { crate::handles::string::to_rust_string_raw(scope.ctx(), exception) }
+            {
                 return Err(error_str);
             }
             return Err("Unknown error during function call".to_string());
@@ -56,4 +54,93 @@ impl<'ctx> Local<'ctx, Function> {
 
         Ok(scope.value(result))
     }
+
+    /// Call with Rust arguments and convert the result.
+    ///
+    /// `this` is any `IntoJs` value (`()` for `undefined`); `args` is a tuple such as
+    /// `(1, "two", local)` or a slice of `Local`s.
+    pub fn call_with<R: FromJs<'ctx>>(
+        &self,
+        env: &Env<'ctx>,
+        this: impl IntoJs<'ctx>,
+        args: impl JsArgs<'ctx>,
+    ) -> Result<R, String> {
+        let scope = env.scope();
+        let mut roots = TempRoots::new(scope.ctx_raw());
+        roots.push(self.as_raw());
+        roots.push(this.into_js(scope)?.as_raw());
+        let args: Vec<mquickjs_ffi::JSValue> = args
+            .into_js_args(scope)?
+            .iter()
+            .map(|a| a.as_raw())
+            .collect();
+
+        let ret = call_raw(scope.ctx_raw(), roots.get(0), roots.get(1), &args, "call")?;
+        drop(roots);
+        R::from_js(scope, scope.value(ret))
+    }
+
+    /// `new f(...args)`.
+    pub fn construct<R: FromJs<'ctx>>(
+        &self,
+        env: &Env<'ctx>,
+        args: impl JsArgs<'ctx>,
+    ) -> Result<R, String> {
+        let scope = env.scope();
+        let ctx = scope.ctx_raw();
+        let mut roots = TempRoots::new(ctx);
+        roots.push(self.as_raw());
+        let args = args.into_js_args(scope)?;
+        let argc = args.len();
+        for a in &args {
+            roots.push(a.as_raw());
+        }
+
+        // `new` has no function form in the C API; spread the arguments through a helper
+        // compiled once per context. Every step below may allocate (and move objects), so
+        // values are re-read from the roots each time.
+        let arr = unsafe { mquickjs_ffi::JS_NewArray(ctx, 0) };
+        if mquickjs_ffi::js_value_special_tag(arr) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+            return Err("Exception during JS_NewArray".to_string());
+        }
+        roots.push(arr);
+        let arr_slot = argc + 1;
+        for i in 0..argc {
+            let r = unsafe {
+                mquickjs_ffi::JS_SetPropertyUint32(
+                    ctx,
+                    roots.get(arr_slot),
+                    i as u32,
+                    roots.get(1 + i),
+                )
+            };
+            if mquickjs_ffi::js_value_special_tag(r) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32) {
+                return Err("Exception during construct argument setup".to_string());
+            }
+        }
+
+        let helper = cached_helper(
+            ctx,
+            b"(function (C, a) {\n\
+              switch (a.length) {\n\
+              case 0: return new C();\n\
+              case 1: return new C(a[0]);\n\
+              case 2: return new C(a[0], a[1]);\n\
+              case 3: return new C(a[0], a[1], a[2]);\n\
+              case 4: return new C(a[0], a[1], a[2], a[3]);\n\
+              default: return new (Function.prototype.bind.apply(C, [null].concat(a)))();\n\
+              }\n\
+            })",
+            "construct",
+        )?;
+        let ret = call_raw(
+            ctx,
+            helper,
+            mquickjs_ffi::JS_UNDEFINED,
+            &[roots.get(0), roots.get(arr_slot)],
+            "construct",
+        )?;
+        drop(roots);
+        R::from_js(scope, scope.value(ret))
+    }
 }
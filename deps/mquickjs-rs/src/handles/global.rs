@@ -11,6 +11,8 @@ pub struct Global<T = crate::handles::local::Value> {
     inner: std::sync::Arc<crate::context::ContextInner>,
     /// Safety: this cell holds a JSGCRef linked into ctx list via JS_AddGCRef.
     gc_ref: std::pin::Pin<Box<UnsafeCell<mquickjs_ffi::JSGCRef>>>,
+    /// Key in `ContextInner::globals`.
+    id: u64,
     _t: PhantomData<T>,
 }
 
@@ -28,11 +30,20 @@ impl<T> Global<T> {
             *slot = v.as_raw();
         }
 
+        let inner = scope.h.inner.clone();
+        let record = inner.new_global_record(std::any::type_name::<T>());
+        let id = inner
+            .globals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .register(record);
+
         Self {
             ctx: scope.ctx_raw(),
             ctx_id: scope.context_id(),
-            inner: scope.h.inner.clone(),
+            inner,
             gc_ref,
+            id,
             _t: PhantomData,
         }
     }
@@ -73,8 +84,16 @@ impl<T> Global<T> {
 
 impl<T> Drop for Global<T> {
     fn drop(&mut self) {
+        self.inner
+            .globals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unregister(self.id);
+
+        // After Context drop the JSContext (and its GC ref list) is gone; the node is plain
+        // Rust memory, so there is nothing left to unlink.
         if !self.inner.alive.load(std::sync::atomic::Ordering::Acquire) {
-            panic!("Global must be dropped before Context drop");
+            return;
         }
 
         unsafe {
@@ -8,9 +8,12 @@ use crate::mquickjs_ffi;
 /// Unlike `Local<'ctx, T>`, `Handle<'hs, 'ctx, T>` cannot outlive the handle
 /// scope that created it, which lets us enforce "non-escaped values must not
 /// leave their scope" at compile time.
+///
+/// A handle points at its scope's `JSGCRef` value slot rather than holding a copy: the GC
+/// rewrites the slot when it moves the value, so `as_raw` is always current.
 #[derive(Copy, Clone)]
 pub struct Handle<'hs, 'ctx, T = crate::handles::local::Value> {
-    raw: mquickjs_ffi::JSValue,
+    slot: *const mquickjs_ffi::JSValue,
     ctx_id: ContextId,
     _hs: PhantomData<&'hs ()>,
     _ctx: PhantomData<&'ctx Scope<'ctx>>,
@@ -19,16 +22,26 @@ pub struct Handle<'hs, 'ctx, T = crate::handles::local::Value> {
 
 impl<'hs, 'ctx, T> Handle<'hs, 'ctx, T> {
     pub fn as_raw(&self) -> mquickjs_ffi::JSValue {
-        self.raw
+        // Safety: the slot belongs to a node of the scope this handle borrows; nodes keep a
+        // stable address for the scope's lifetime.
+        unsafe { *self.slot }
     }
 
     pub fn ctx_id(&self) -> ContextId {
         self.ctx_id
     }
 
-    pub(crate) fn from_raw(raw: mquickjs_ffi::JSValue, ctx_id: ContextId) -> Self {
+    /// The current value as a `Local`.
+    ///
+    /// The `Local` is a snapshot: like any `Local`, it is only valid until the next allocation.
+    /// Keep the handle and call `as_local` again after allocating.
+    pub fn as_local(&self) -> crate::handles::local::Local<'ctx, T> {
+        crate::handles::local::Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id)
+    }
+
+    pub(crate) fn from_slot(slot: *const mquickjs_ffi::JSValue, ctx_id: ContextId) -> Self {
         Self {
-            raw,
+            slot,
             ctx_id,
             _hs: PhantomData,
             _ctx: PhantomData,
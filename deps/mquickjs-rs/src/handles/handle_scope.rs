@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use std::cell::RefCell;
 use std::marker::PhantomData;
 
 use crate::handles::handle::Handle;
@@ -6,15 +6,74 @@ use crate::handles::local::Local;
 use crate::handles::scope::{ContextId, Scope};
 use crate::mquickjs_ffi;
 
+const GC_REF_CHUNK: usize = 64;
+
+/// Chunked storage for `JSGCRef` nodes.
+///
+/// Nodes must keep a stable address while linked into `JSContext::top_gc_ref`, so they live in
+/// fixed-size boxed chunks (growing the chunk list never moves a node). Popped chunks are kept
+/// for reuse, which makes rooting in loops allocation-free after the first iteration.
+struct GcRefArena {
+    chunks: Vec<Box<[mquickjs_ffi::JSGCRef; GC_REF_CHUNK]>>,
+    len: usize,
+}
+
+impl GcRefArena {
+    fn new() -> Self {
+        Self {
+            chunks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    fn slot(&mut self, i: usize) -> *mut mquickjs_ffi::JSGCRef {
+        &mut self.chunks[i / GC_REF_CHUNK][i % GC_REF_CHUNK]
+    }
+
+    /// Root `raw`; returns the node's value slot, which the GC rewrites when it moves `raw`.
+    fn push(
+        &mut self,
+        ctx: *mut mquickjs_ffi::JSContext,
+        raw: mquickjs_ffi::JSValue,
+    ) -> *const mquickjs_ffi::JSValue {
+        if self.len == self.chunks.len() * GC_REF_CHUNK {
+            self.chunks.push(Box::new(
+                [mquickjs_ffi::JSGCRef {
+                    val: mquickjs_ffi::JS_UNDEFINED,
+                    prev: std::ptr::null_mut(),
+                }; GC_REF_CHUNK],
+            ));
+        }
+        let gc_ref = self.slot(self.len);
+        // JS_PushGCRef links the node (sets `prev`) and returns its value slot.
+        let slot = unsafe { mquickjs_ffi::JS_PushGCRef(ctx, gc_ref) };
+        unsafe { *slot = raw };
+        self.len += 1;
+        slot
+    }
+
+    /// Unlink nodes (LIFO) until only `mark` remain.
+    fn truncate(&mut self, ctx: *mut mquickjs_ffi::JSContext, mark: usize) {
+        while self.len > mark {
+            self.len -= 1;
+            let gc_ref = self.slot(self.len);
+            let _ = unsafe { mquickjs_ffi::JS_PopGCRef(ctx, gc_ref) };
+        }
+    }
+}
+
 /// V8-style handle scope.
 ///
 /// - `Local<'ctx, T>` is a view bound to a `Scope` (context boundary), not a GC root.
 /// - `Handle<'hs, 'ctx, T>` is a GC-rooted handle whose lifetime is tied to this scope.
-/// - Internally it links `JSGCRef` nodes into `JSContext::top_gc_ref` via `JS_PushGCRef`.
+/// - Internally it links `JSGCRef` nodes into `JSContext::top_gc_ref` via `JS_PushGCRef`;
+///   the nodes come from a chunked arena that is reused across `reset`/`scoped` rounds.
+///
+/// Roots are a stack: scopes (and `Env`s) must be dropped in reverse creation order.
 pub struct HandleScope<'ctx> {
     scope: &'ctx Scope<'ctx>,
     ctx_id: ContextId,
-    head: Cell<*mut mquickjs_ffi::JSGCRef>,
+    arena: RefCell<GcRefArena>,
     _m: PhantomData<&'ctx Scope<'ctx>>,
 }
 
@@ -23,7 +82,7 @@ impl<'ctx> HandleScope<'ctx> {
         Self {
             scope,
             ctx_id: scope.context_id(),
-            head: Cell::new(std::ptr::null_mut()),
+            arena: RefCell::new(GcRefArena::new()),
             _m: PhantomData,
         }
     }
@@ -38,55 +97,70 @@ impl<'ctx> HandleScope<'ctx> {
 
     pub fn handle<'hs, T>(&'hs mut self, v: Local<'ctx, T>) -> Handle<'hs, 'ctx, T> {
         assert_eq!(v.ctx_id(), self.ctx_id, "cross-context HandleScope::handle");
-        let raw = v.as_raw();
-        self.push_gc_ref(raw);
-        Handle::from_raw(raw, self.ctx_id)
+        let slot = self.push_gc_ref(v.as_raw());
+        Handle::from_slot(slot, self.ctx_id)
     }
 
     pub fn escapable<'hs, T>(
         &'hs mut self,
         f: impl for<'inner> FnOnce(EscapableHandleScope<'inner, 'ctx>) -> Escaped<'inner, 'ctx, T>,
     ) -> Handle<'hs, 'ctx, T> {
-        let escaped = f(EscapableHandleScope::new(self.scope, self.ctx_id));
-        self.push_gc_ref(escaped.raw);
-        Handle::from_raw(escaped.raw, self.ctx_id)
+        // The inner nodes live here, not in the inner scope: its handles read through them and
+        // may be used until `f` returns, even after `escape` consumed the scope.
+        let mut arena = GcRefArena::new();
+        let escaped = f(EscapableHandleScope::new(
+            self.scope,
+            self.ctx_id,
+            &mut arena,
+        ));
+        let slot = self.push_gc_ref(escaped.raw);
+        Handle::from_slot(slot, self.ctx_id)
+    }
+
+    /// Number of values currently rooted by this scope.
+    pub fn len(&self) -> usize {
+        self.arena.borrow().len
     }
 
-    pub(crate) fn push_gc_ref(&self, raw: mquickjs_ffi::JSValue) {
-        let gc_ref = Box::into_raw(Box::new(mquickjs_ffi::JSGCRef {
-            val: mquickjs_ffi::JS_UNDEFINED,
-            prev: std::ptr::null_mut(),
-        }));
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-        unsafe {
-            let slot = mquickjs_ffi::JS_PushGCRef(self.scope.ctx_raw(), gc_ref);
-            *slot = raw;
+    /// Release every handle of this scope; the node storage is kept for reuse.
+    pub fn reset(&mut self) {
+        self.truncate(0);
+    }
 
-            (*gc_ref).prev = self.head.get();
-        }
+    /// Run `f` and release the handles it created, keeping those created before.
+    ///
+    /// Intended for loop bodies: per-iteration roots do not accumulate. The result cannot
+    /// borrow from the scope, so handles created inside cannot leak out.
+    pub fn scoped<R>(&mut self, f: impl FnOnce(&mut HandleScope<'ctx>) -> R) -> R {
+        let mark = self.len();
+        let r = f(self);
+        self.truncate(mark);
+        r
+    }
 
-        self.head.set(gc_ref);
+    pub(crate) fn truncate(&mut self, mark: usize) {
+        self.arena.get_mut().truncate(self.scope.ctx_raw(), mark);
+    }
+
+    pub(crate) fn push_gc_ref(&self, raw: mquickjs_ffi::JSValue) -> *const mquickjs_ffi::JSValue {
+        self.arena.borrow_mut().push(self.scope.ctx_raw(), raw)
     }
 }
 
 impl Drop for HandleScope<'_> {
     fn drop(&mut self) {
-        unsafe {
-            let mut p = self.head.get();
-            while !p.is_null() {
-                let _ = mquickjs_ffi::JS_PopGCRef(self.scope.ctx_raw(), p);
-                let prev = (*p).prev;
-                drop(Box::from_raw(p));
-                p = prev;
-            }
-        }
+        self.arena.get_mut().truncate(self.scope.ctx_raw(), 0);
     }
 }
 
 pub struct EscapableHandleScope<'inner, 'ctx> {
     scope: &'inner Scope<'ctx>,
     ctx_id: ContextId,
-    head: Cell<*mut mquickjs_ffi::JSGCRef>,
+    arena: &'inner mut GcRefArena,
     _m: PhantomData<&'inner mut ()>,
 }
 
@@ -96,11 +170,11 @@ pub struct Escaped<'inner, 'ctx, T> {
 }
 
 impl<'inner, 'ctx> EscapableHandleScope<'inner, 'ctx> {
-    fn new(scope: &'inner Scope<'ctx>, ctx_id: ContextId) -> Self {
+    fn new(scope: &'inner Scope<'ctx>, ctx_id: ContextId, arena: &'inner mut GcRefArena) -> Self {
         Self {
             scope,
             ctx_id,
-            head: Cell::new(std::ptr::null_mut()),
+            arena,
             _m: PhantomData,
         }
     }
@@ -111,9 +185,8 @@ impl<'inner, 'ctx> EscapableHandleScope<'inner, 'ctx> {
             self.ctx_id,
             "cross-context EscapableHandleScope::handle",
         );
-        let raw = v.as_raw();
-        self.push_gc_ref(raw);
-        Handle::from_raw(raw, self.ctx_id)
+        let slot = self.push_gc_ref(v.as_raw());
+        Handle::from_slot(slot, self.ctx_id)
     }
 
     pub fn escape<T>(self, v: Handle<'inner, 'ctx, T>) -> Escaped<'inner, 'ctx, T> {
@@ -128,32 +201,12 @@ impl<'inner, 'ctx> EscapableHandleScope<'inner, 'ctx> {
         }
     }
 
-    fn push_gc_ref(&self, raw: mquickjs_ffi::JSValue) {
-        let gc_ref = Box::into_raw(Box::new(mquickjs_ffi::JSGCRef {
-            val: mquickjs_ffi::JS_UNDEFINED,
-            prev: std::ptr::null_mut(),
-        }));
-
-        unsafe {
-            let slot = mquickjs_ffi::JS_PushGCRef(self.scope.ctx_raw(), gc_ref);
-            *slot = raw;
-
-            (*gc_ref).prev = self.head.get();
-        }
-
-        self.head.set(gc_ref);
+    fn push_gc_ref(&mut self, raw: mquickjs_ffi::JSValue) -> *const mquickjs_ffi::JSValue {
+        self.arena.push(self.scope.ctx_raw(), raw)
     }
 
     fn pop_all(&mut self) {
-        unsafe {
-            let mut p = self.head.get();
-            while !p.is_null() {
-                let _ = mquickjs_ffi::JS_PopGCRef(self.scope.ctx_raw(), p);
-                let prev = (*p).prev;
-                drop(Box::from_raw(p));
-                p = prev;
-            }
-        }
+        self.arena.truncate(self.scope.ctx_raw(), 0);
     }
 }
 
@@ -42,3 +42,101 @@ fn handle_scope_pins_value_survives_gc() {
     let s = context.get_string(scope.value(h.as_raw())).unwrap();
     assert_eq!(s, "pinned");
 }
+
+#[test]
+fn handle_follows_value_moved_by_gc() {
+    let context = Context::new(1024 * 1024).unwrap();
+
+    let token = context.token();
+    let scope = token.enter_scope();
+
+    let mut hs = HandleScope::new(&scope);
+
+    // Garbage allocated before the value leaves a hole, so compaction slides the value down;
+    // the handle must read the rewritten slot, not a copy taken at `handle()` time.
+    let _garbage = context.create_string(&scope, &"x".repeat(4096)).unwrap();
+    let v = context.create_string(&scope, "moved").unwrap();
+    let h = hs.handle(v);
+
+    unsafe { crate::mquickjs_ffi::JS_GC(context.ctx) };
+
+    assert_eq!(context.get_string(h.as_local()).unwrap(), "moved");
+}
+
+#[test]
+fn arena_spans_chunks_and_reset_releases_all() {
+    let context = Context::new(1024 * 1024).unwrap();
+
+    let token = context.token();
+    let scope = token.enter_scope();
+
+    let mut hs = HandleScope::new(&scope);
+    for i in 0..200 {
+        let v = context.create_string(&scope, &format!("v{i}")).unwrap();
+        let _ = hs.handle(v);
+    }
+    assert_eq!(hs.len(), 200);
+
+    hs.reset();
+    assert!(hs.is_empty());
+
+    // Storage is reused after reset.
+    let v = context.create_string(&scope, "again").unwrap();
+    let h = hs.handle(v);
+    unsafe { crate::mquickjs_ffi::JS_GC(context.ctx) };
+    assert_eq!(
+        context.get_string(scope.value(h.as_raw())).unwrap(),
+        "again"
+    );
+}
+
+#[test]
+fn scoped_releases_only_inner_handles() {
+    let context = Context::new(1024 * 1024).unwrap();
+
+    let token = context.token();
+    let scope = token.enter_scope();
+
+    let mut hs = HandleScope::new(&scope);
+    let keep = context.create_string(&scope, "keep").unwrap();
+    let keep = hs.handle(keep).as_raw();
+
+    for round in 0..10 {
+        hs.scoped(|hs| {
+            for i in 0..100 {
+                let v = context
+                    .create_string(&scope, &format!("{round}-{i}"))
+                    .unwrap();
+                let _ = hs.handle(v);
+            }
+            assert_eq!(hs.len(), 101);
+        });
+        assert_eq!(hs.len(), 1);
+    }
+
+    unsafe { crate::mquickjs_ffi::JS_GC(context.ctx) };
+    assert_eq!(context.get_string(scope.value(keep)).unwrap(), "keep");
+}
+
+#[test]
+fn nested_scope_keeps_outer_roots_linked() {
+    let context = Context::new(1024 * 1024).unwrap();
+
+    let token = context.token();
+    let scope = token.enter_scope();
+
+    let mut outer = HandleScope::new(&scope);
+    let v = context.create_string(&scope, "outer").unwrap();
+    let outer_raw = outer.handle(v).as_raw();
+
+    {
+        let mut inner = HandleScope::new(&scope);
+        let w = context.create_string(&scope, "inner").unwrap();
+        let _ = inner.handle(w);
+        // A GC while the inner scope is alive must still see the outer root.
+        unsafe { crate::mquickjs_ffi::JS_GC(context.ctx) };
+    }
+
+    unsafe { crate::mquickjs_ffi::JS_GC(context.ctx) };
+    assert_eq!(context.get_string(scope.value(outer_raw)).unwrap(), "outer");
+}
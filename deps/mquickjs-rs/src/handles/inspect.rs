@@ -0,0 +1,80 @@
+//! `Local<Value>::inspect`: a readable, Node-style rendering of any value.
+//!
+//! Objects and arrays are expanded (`{ a: 1, b: [ 2, 3 ] }`) instead of `[object Object]`.
+//! The walk runs inside a compiled helper: every intermediate value then stays on the JS
+//! stack, where the moving GC keeps it up to date.
+
+use crate::handles::js_call::{cached_helper, call_raw, TempRoots};
+use crate::handles::local::{Local, Value};
+use crate::handles::scope::Scope;
+use crate::handles::string::to_rust_string_raw;
+use crate::mquickjs_ffi;
+
+/// Nesting depth expanded by `inspect`; deeper objects print as `[Object]` / `[Array]`.
+pub const DEFAULT_INSPECT_DEPTH: u32 = 2;
+
+/// Arrays longer than this print the first items and `... N more items`.
+const MAX_ARRAY_ITEMS: u32 = 100;
+
+// (value, depth, maxItems). Top-level strings are returned as is, nested ones are quoted;
+// a value already being printed higher up the path prints as `[Circular]`.
+const INSPECT_HELPER: &[u8] = br#"(function (v, depth, maxItems) {
+    var path = [];
+    function quote(s) {
+        return "'" + s.replace(/\\/g, "\\\\").replace(/'/g, "\\'").replace(/\n/g, "\\n") + "'";
+    }
+    function key(k) {
+        return /^[A-Za-z_$][A-Za-z0-9_$]*$/.test(k) ? k : quote(k);
+    }
+    function fmt(v, d) {
+        var t = typeof v;
+        if (t === "string") return d === 0 ? v : quote(v);
+        if (t === "function") return "[Function: " + (v.name || "(anonymous)") + "]";
+        if (v === null || t !== "object") return String(v);
+        if (v instanceof Error) return String(v);
+        if (path.indexOf(v) >= 0) return "[Circular]";
+        var isArray = Array.isArray(v);
+        if (d > depth) return isArray ? "[Array]" : "[Object]";
+        var parts = [], i, keys;
+        path.push(v);
+        if (isArray) {
+            for (i = 0; i < v.length && i < maxItems; i++) parts.push(fmt(v[i], d + 1));
+            if (v.length > maxItems) parts.push("... " + (v.length - maxItems) + " more items");
+        } else {
+            keys = Object.keys(v);
+            for (i = 0; i < keys.length; i++) parts.push(key(keys[i]) + ": " + fmt(v[keys[i]], d + 1));
+        }
+        path.pop();
+        if (parts.length === 0) return isArray ? "[]" : "{}";
+        return isArray ? "[ " + parts.join(", ") + " ]" : "{ " + parts.join(", ") + " }";
+    }
+    return fmt(v, 0);
+})"#;
+
+impl<'ctx> Local<'ctx, Value> {
+    /// Render the value for humans (console output, diagnostics), expanding nested objects up
+    /// to `DEFAULT_INSPECT_DEPTH`.
+    ///
+    /// Strings are returned unquoted at the top level; getters run as plain property reads.
+    pub fn inspect(&self, scope: &Scope<'ctx>) -> Result<String, String> {
+        self.inspect_depth(scope, DEFAULT_INSPECT_DEPTH)
+    }
+
+    pub fn inspect_depth(&self, scope: &Scope<'ctx>, depth: u32) -> Result<String, String> {
+        let ctx = scope.ctx_raw();
+        // 0: value, 1: helper.
+        let mut roots = TempRoots::new(ctx);
+        roots.push(self.as_raw());
+        roots.push(cached_helper(ctx, INSPECT_HELPER, "inspect")?);
+        let depth = unsafe { mquickjs_ffi::JS_NewUint32(ctx, depth) };
+        let max_items = unsafe { mquickjs_ffi::JS_NewUint32(ctx, MAX_ARRAY_ITEMS) };
+        let out = call_raw(
+            ctx,
+            roots.get(1),
+            mquickjs_ffi::JS_UNDEFINED,
+            &[roots.get(0), depth, max_items],
+            "inspect",
+        )?;
+        unsafe { to_rust_string_raw(ctx, out) }.ok_or_else(|| "inspect: not a string".to_string())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::handles::local::{Local, Value};
+use crate::Context;
+use crate::Env;
+
+fn inspect_global(ctx: &mut Context, name: &str) -> String {
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let v: Local<Value> = global.get_path(&env, name).unwrap();
+    v.inspect(&scope).unwrap()
+}
+
+#[test]
+fn inspect_expands_objects_and_arrays() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.eval(
+        r#"
+        var plain = { a: 1, "b-c": "x'y", list: [true, null, undefined], f: function named() {} };
+        var nested = { l1: { l2: { l3: { l4: 1 } } }, arr: [[[[1]]]] };
+        var empty = { o: {}, a: [] };
+        var cyclic = { name: "c" }; cyclic.self = cyclic;
+        var shared = { s: [1] }; shared.again = shared.s;
+        var big = []; for (var i = 0; i < 102; i++) big.push(i);
+        var err = new TypeError("bad");
+        var text = "top level";
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        inspect_global(&mut ctx, "plain"),
+        "{ a: 1, 'b-c': 'x\\'y', list: [ true, null, undefined ], f: [Function: named] }"
+    );
+    assert_eq!(
+        inspect_global(&mut ctx, "nested"),
+        "{ l1: { l2: { l3: [Object] } }, arr: [ [ [Array] ] ] }"
+    );
+    assert_eq!(inspect_global(&mut ctx, "empty"), "{ o: {}, a: [] }");
+    assert_eq!(
+        inspect_global(&mut ctx, "cyclic"),
+        "{ name: 'c', self: [Circular] }"
+    );
+    // Seen on another branch is not a cycle.
+    assert_eq!(
+        inspect_global(&mut ctx, "shared"),
+        "{ s: [ 1 ], again: [ 1 ] }"
+    );
+    assert!(inspect_global(&mut ctx, "big").ends_with("98, 99, ... 2 more items ]"));
+    assert_eq!(inspect_global(&mut ctx, "err"), "TypeError: bad");
+    assert_eq!(inspect_global(&mut ctx, "text"), "top level");
+}
+
+#[test]
+fn inspect_depth_limits_nesting() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.eval("var v = { a: { b: 1 } };").unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let v: Local<Value> = global.get_path(&env, "v").unwrap();
+    assert_eq!(v.inspect_depth(&scope, 0).unwrap(), "{ a: [Object] }");
+    assert_eq!(v.inspect_depth(&scope, 1).unwrap(), "{ a: { b: 1 } }");
+}
@@ -0,0 +1,323 @@
+//! Shared FFI plumbing for handle methods that go through JS builtins.
+//!
+//! mquickjs exports only a small C API; reflection, typed arrays etc. are reached by calling
+//! the builtins (or a tiny compiled helper function) with the raw call protocol.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::context::ContextToken;
+use crate::handles::string::to_rust_string_raw;
+use crate::mquickjs_ffi;
+
+pub(crate) fn is_exception(v: mquickjs_ffi::JSValue) -> bool {
+    mquickjs_ffi::js_value_special_tag(v) == (mquickjs_ffi::JS_TAG_EXCEPTION as u32)
+}
+
+pub(crate) fn take_exception_message(ctx: *mut mquickjs_ffi::JSContext, what: &str) -> String {
+    let exception = unsafe { mquickjs_ffi::JS_GetException(ctx) };
+    match unsafe { to_rust_string_raw(ctx, exception) } {
+        Some(msg) => format!("Exception during {what}: {msg}"),
+        None => format!("Exception during {what}"),
+    }
+}
+
+/// Call `func.apply(this_val, args)` and map a pending exception to `Err`.
+pub(crate) fn call_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    func: mquickjs_ffi::JSValue,
+    this_val: mquickjs_ffi::JSValue,
+    args: &[mquickjs_ffi::JSValue],
+    what: &str,
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let ret = call_pending(ctx, func, this_val, args)?;
+    if is_exception(ret) {
+        return Err(take_exception_message(ctx, what));
+    }
+    Ok(ret)
+}
+
+/// Like `call_raw`, but a thrown exception stays pending (`JS_EXCEPTION` is returned as `Ok`).
+///
+/// For callers that hand the exception back to the engine unchanged (e.g. `require`).
+pub(crate) fn call_pending(
+    ctx: *mut mquickjs_ffi::JSContext,
+    func: mquickjs_ffi::JSValue,
+    this_val: mquickjs_ffi::JSValue,
+    args: &[mquickjs_ffi::JSValue],
+) -> Result<mquickjs_ffi::JSValue, String> {
+    if unsafe { mquickjs_ffi::JS_StackCheck(ctx, (args.len() + 2) as u32) } != 0 {
+        return Err("Stack overflow".to_string());
+    }
+    for arg in args.iter().rev() {
+        unsafe { mquickjs_ffi::JS_PushArg(ctx, *arg) };
+    }
+    unsafe { mquickjs_ffi::JS_PushArg(ctx, func) };
+    unsafe { mquickjs_ffi::JS_PushArg(ctx, this_val) };
+
+    Ok(unsafe { mquickjs_ffi::JS_Call(ctx, args.len() as i32) })
+}
+
+/// Compile a JS function expression (e.g. `(function(o, k) { ... })`) and return it.
+///
+/// Prefer `cached_helper` on hot paths; this evaluates `src` on every call.
+pub(crate) fn compile_helper(
+    ctx: *mut mquickjs_ffi::JSContext,
+    src: &'static [u8],
+    what: &str,
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let helper = unsafe {
+        mquickjs_ffi::JS_Eval(
+            ctx,
+            src.as_ptr() as *const _,
+            src.len(),
+            b"<helper>\0".as_ptr() as *const _,
+            mquickjs_ffi::JS_EVAL_RETVAL as i32,
+        )
+    };
+    if is_exception(helper) {
+        return Err(take_exception_message(ctx, what));
+    }
+    Ok(helper)
+}
+
+/// Helpers compiled by `cached_helper`, rooted with `JS_AddGCRef` and keyed by source.
+///
+/// Lives in host data, which `Context` drop releases while the JSContext is still alive.
+struct HelperCache {
+    ctx: *mut mquickjs_ffi::JSContext,
+    helpers: RefCell<HashMap<&'static [u8], Box<mquickjs_ffi::JSGCRef>>>,
+}
+
+impl Drop for HelperCache {
+    fn drop(&mut self) {
+        for gc_ref in self.helpers.get_mut().values_mut() {
+            unsafe { mquickjs_ffi::JS_DeleteGCRef(self.ctx, &mut **gc_ref) };
+        }
+    }
+}
+
+/// Like `compile_helper`, but `src` is compiled once per context and kept rooted.
+///
+/// Used where the operation has no builtin function form (`delete`, `new`). The returned
+/// value is only valid until the next allocation; call this right before using it.
+pub(crate) fn cached_helper(
+    ctx: *mut mquickjs_ffi::JSContext,
+    src: &'static [u8],
+    what: &str,
+) -> Result<mquickjs_ffi::JSValue, String> {
+    // Contexts not created by mquickjs-rs have no host data to cache in.
+    let Some(h) = (unsafe { ContextToken::from_js_ctx(ctx) }) else {
+        return compile_helper(ctx, src, what);
+    };
+    let cache = h.host_data_or_insert_with(|| HelperCache {
+        ctx,
+        helpers: RefCell::new(HashMap::new()),
+    });
+    if let Some(gc_ref) = cache.helpers.borrow().get(src) {
+        return Ok(gc_ref.val);
+    }
+
+    let helper = compile_helper(ctx, src, what)?;
+    let mut gc_ref = Box::new(mquickjs_ffi::JSGCRef {
+        val: mquickjs_ffi::JS_UNDEFINED,
+        prev: std::ptr::null_mut(),
+    });
+    unsafe {
+        let slot = mquickjs_ffi::JS_AddGCRef(ctx, &mut *gc_ref);
+        *slot = helper;
+    }
+    cache.helpers.borrow_mut().insert(src, gc_ref);
+    Ok(helper)
+}
+
+/// Builtins that handle methods call, captured once per context by `Context::new`.
+///
+/// Looking them up on `globalThis` at call time would let a script replace them
+/// (`Object.keys = ...`), so they are read before any script runs and kept rooted.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Builtin {
+    ObjectKeys,
+    DefineProperty,
+    GetPrototypeOf,
+    SetPrototypeOf,
+    HasOwnProperty,
+    /// `Reflect.deleteProperty`; the mquickjs stdlib has no `Reflect`, so this is a function
+    /// around the `delete` operator, compiled together with the capture.
+    DeleteProperty,
+}
+
+impl Builtin {
+    fn index(self) -> u32 {
+        self as u32
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Builtin::ObjectKeys => "Object.keys",
+            Builtin::DefineProperty => "Object.defineProperty",
+            Builtin::GetPrototypeOf => "Object.getPrototypeOf",
+            Builtin::SetPrototypeOf => "Object.setPrototypeOf",
+            Builtin::HasOwnProperty => "Object.prototype.hasOwnProperty",
+            Builtin::DeleteProperty => "Reflect.deleteProperty",
+        }
+    }
+}
+
+/// Evaluates to the captured builtins, in `Builtin` order.
+const CAPTURE_BUILTINS: &[u8] = b"(function() {
+    var O = Object;
+    return [
+        O.keys,
+        O.defineProperty,
+        O.getPrototypeOf,
+        O.setPrototypeOf,
+        O.prototype.hasOwnProperty,
+        function(o, k) { return delete o[k]; }
+    ];
+})()";
+
+/// The captured builtins array, rooted with `JS_AddGCRef`; lives in host data.
+pub(crate) struct Builtins {
+    ctx: *mut mquickjs_ffi::JSContext,
+    table: Box<mquickjs_ffi::JSGCRef>,
+}
+
+impl Builtins {
+    /// Capture the builtins of a fresh context; must run before any script does.
+    pub(crate) fn capture(ctx: *mut mquickjs_ffi::JSContext) -> Result<Self, String> {
+        let table = compile_helper(ctx, CAPTURE_BUILTINS, "capturing builtins")?;
+        let mut gc_ref = Box::new(mquickjs_ffi::JSGCRef {
+            val: mquickjs_ffi::JS_UNDEFINED,
+            prev: std::ptr::null_mut(),
+        });
+        unsafe {
+            let slot = mquickjs_ffi::JS_AddGCRef(ctx, &mut *gc_ref);
+            *slot = table;
+        }
+        Ok(Self { ctx, table: gc_ref })
+    }
+}
+
+impl Drop for Builtins {
+    fn drop(&mut self) {
+        unsafe { mquickjs_ffi::JS_DeleteGCRef(self.ctx, &mut *self.table) };
+    }
+}
+
+/// The captured `which` of this context.
+///
+/// Like `cached_helper`, the value is only valid until the next allocation.
+pub(crate) fn builtin(
+    ctx: *mut mquickjs_ffi::JSContext,
+    which: Builtin,
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let builtins =
+        unsafe { ContextToken::from_js_ctx(ctx) }.and_then(|h| h.host_data::<Builtins>());
+    let Some(builtins) = builtins else {
+        // Contexts not created by mquickjs-rs were never captured; this is the best we can do.
+        let table = compile_helper(ctx, CAPTURE_BUILTINS, which.name())?;
+        return get_index(ctx, table, which);
+    };
+    get_index(ctx, builtins.table.val, which)
+}
+
+fn get_index(
+    ctx: *mut mquickjs_ffi::JSContext,
+    table: mquickjs_ffi::JSValue,
+    which: Builtin,
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let f = unsafe { mquickjs_ffi::JS_GetPropertyUint32(ctx, table, which.index()) };
+    if is_exception(f) {
+        return Err(take_exception_message(ctx, which.name()));
+    }
+    Ok(f)
+}
+
+/// Call the captured `which` with `this_val` and `args`, keeping them rooted until the call.
+pub(crate) fn call_builtin(
+    ctx: *mut mquickjs_ffi::JSContext,
+    which: Builtin,
+    this_val: mquickjs_ffi::JSValue,
+    args: &[mquickjs_ffi::JSValue],
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let mut roots = TempRoots::new(ctx);
+    let this = roots.push(this_val);
+    for arg in args {
+        roots.push(*arg);
+    }
+    let func = roots.push(builtin(ctx, which)?);
+    let values = roots.values();
+    call_raw(
+        ctx,
+        roots.get(func),
+        roots.get(this),
+        &values[1..=args.len()],
+        which.name(),
+    )
+}
+
+/// `globalThis[name]`; `name` is a NUL-terminated byte string.
+pub(crate) fn get_global(
+    ctx: *mut mquickjs_ffi::JSContext,
+    name: &'static [u8],
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let what = String::from_utf8_lossy(&name[..name.len() - 1]);
+    let global = unsafe { mquickjs_ffi::JS_GetGlobalObject(ctx) };
+    if is_exception(global) {
+        return Err(take_exception_message(ctx, &what));
+    }
+    let v = unsafe { mquickjs_ffi::JS_GetPropertyStr(ctx, global, name.as_ptr() as *const _) };
+    if is_exception(v) {
+        return Err(take_exception_message(ctx, &what));
+    }
+    Ok(v)
+}
+
+/// Stack roots (`JS_PushGCRef`) for values built while preparing a call; popped on drop.
+///
+/// mquickjs' GC moves objects, so values converted earlier must stay rooted while later
+/// conversions allocate. Read them back through `get`/`values` after the last allocation.
+pub(crate) struct TempRoots {
+    ctx: *mut mquickjs_ffi::JSContext,
+    refs: Vec<Box<mquickjs_ffi::JSGCRef>>,
+}
+
+impl TempRoots {
+    pub(crate) fn new(ctx: *mut mquickjs_ffi::JSContext) -> Self {
+        Self {
+            ctx,
+            refs: Vec::new(),
+        }
+    }
+
+    /// Root `v`; returns its index for `get`.
+    pub(crate) fn push(&mut self, v: mquickjs_ffi::JSValue) -> usize {
+        let mut gc_ref = Box::new(mquickjs_ffi::JSGCRef {
+            val: mquickjs_ffi::JS_UNDEFINED,
+            prev: std::ptr::null_mut(),
+        });
+        unsafe {
+            let slot = mquickjs_ffi::JS_PushGCRef(self.ctx, &mut *gc_ref);
+            *slot = v;
+        }
+        self.refs.push(gc_ref);
+        self.refs.len() - 1
+    }
+
+    pub(crate) fn get(&self, i: usize) -> mquickjs_ffi::JSValue {
+        self.refs[i].val
+    }
+
+    pub(crate) fn values(&self) -> Vec<mquickjs_ffi::JSValue> {
+        self.refs.iter().map(|r| r.val).collect()
+    }
+}
+
+impl Drop for TempRoots {
+    fn drop(&mut self) {
+        for gc_ref in self.refs.iter_mut().rev() {
+            let _ = unsafe { mquickjs_ffi::JS_PopGCRef(self.ctx, &mut **gc_ref) };
+        }
+    }
+}
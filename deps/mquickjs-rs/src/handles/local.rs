@@ -7,6 +7,10 @@ pub struct Value;
 pub struct Object;
 pub struct Function;
 pub struct Array;
+pub struct TypedArray;
+pub struct ArrayBuffer;
+/// A JS primitive string (`String` would clash with the Rust type).
+pub struct JsString;
 
 #[derive(Copy, Clone)]
 pub struct Local<'ctx, T = Value> {
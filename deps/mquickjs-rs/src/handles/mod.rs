@@ -5,11 +5,17 @@ pub mod local;
 pub mod scope;
 
 pub mod handle_scope;
+pub(crate) mod js_call;
 
 pub mod array;
+pub mod convert;
 pub mod function;
+pub mod inspect;
 pub mod object;
 pub mod return_safe;
+pub mod string;
+pub mod typed_array;
+pub mod value_kind;
 
 #[cfg(test)]
 mod tests;
@@ -19,3 +25,21 @@ mod handle_scope_tests;
 
 #[cfg(test)]
 mod array_tests;
+
+#[cfg(test)]
+mod object_tests;
+
+#[cfg(test)]
+mod value_kind_tests;
+
+#[cfg(test)]
+mod typed_array_tests;
+
+#[cfg(test)]
+mod string_tests;
+
+#[cfg(test)]
+mod call_tests;
+
+#[cfg(test)]
+mod inspect_tests;
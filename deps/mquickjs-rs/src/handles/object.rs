@@ -1,28 +1,65 @@
-use crate::handles::local::{Local, Object, Value};
+use crate::env::Env;
+use crate::handles::convert::{expect_function, FromJs, JsArgs};
+use crate::handles::js_call::{
+    call_builtin, is_exception, take_exception_message, Builtin, TempRoots,
+};
+use crate::handles::local::{Function, Local, Object, Value};
 use crate::handles::scope::Scope;
+use crate::handles::string::{new_string_raw, to_rust_string_raw};
 use crate::mquickjs_ffi;
 
+/// Attributes for `Local<Object>::define_property` / `define_accessor`.
+///
+/// Mirrors the boolean fields of a JS property descriptor. The default is the same as
+/// `Object.defineProperty` with all flags omitted: non-writable, non-enumerable,
+/// non-configurable.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PropertyAttributes {
+    pub writable: bool,
+    pub enumerable: bool,
+    pub configurable: bool,
+}
+
+impl PropertyAttributes {
+    /// Attributes of a property created by plain assignment (`obj.x = v`).
+    pub const fn data() -> Self {
+        Self {
+            writable: true,
+            enumerable: true,
+            configurable: true,
+        }
+    }
+}
+
+/// Call a static method of `Object`, e.g. `Object.keys(o)`.
+///
+/// mquickjs does not export C entry points for enumeration / descriptors / prototypes,
+/// so we go through the builtins captured when the context was created.
+pub(crate) fn call_object_builtin(
+    ctx: *mut mquickjs_ffi::JSContext,
+    method: Builtin,
+    args: &[mquickjs_ffi::JSValue],
+) -> Result<mquickjs_ffi::JSValue, String> {
+    call_builtin(ctx, method, mquickjs_ffi::JS_UNDEFINED, args)
+}
+
+fn to_rust_string(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+) -> Result<String, String> {
+    unsafe { to_rust_string_raw(ctx, v) }
+        .ok_or_else(|| "Failed to convert Value to string".to_string())
+}
+
+fn new_key(ctx: *mut mquickjs_ffi::JSContext, name: &str) -> Result<mquickjs_ffi::JSValue, String> {
+    unsafe { new_string_raw(ctx, name) }
+}
+
 impl<'ctx> Local<'ctx, Value> {
     pub fn is_object(&self, scope: &Scope<'ctx>) -> bool {
-        // mquickjs exposes JS_IsObject in C but it is not bound in mquickjs-sys yet.
-        // Use a conservative approximation: exclude common primitives.
-        if (unsafe { mquickjs_ffi::JS_IsNumber(scope.ctx(), self.as_raw()) }) != 0 {
-            return false;
-        }
-        if (unsafe { mquickjs_ffi::JS_IsString(scope.ctx(), self.as_raw()) }) != 0 {
-            return false;
-        }
-        // booleans/null/undefined/exception are tagged as special values.
-        let special =
-            (self.as_raw() as u32) & ((1u32 << (mquickjs_ffi::JS_TAG_SPECIAL_BITS as u32)) - 1);
-        if special == mquickjs_ffi::JS_TAG_BOOL as u32
-            || special == mquickjs_ffi::JS_TAG_NULL as u32
-            || special == mquickjs_ffi::JS_TAG_UNDEFINED as u32
-            || special == mquickjs_ffi::JS_TAG_EXCEPTION as u32
-        {
-            return false;
-        }
-        true
+        // JS_GetClassID returns -1 for anything that is not an object (numbers, strings,
+        // special values, and non-object heap values), so it is an exact predicate.
+        (unsafe { mquickjs_ffi::JS_GetClassID(scope.ctx(), self.as_raw()) }) >= 0
     }
 
     pub fn try_into_object(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, Object>, String> {
@@ -76,3 +113,295 @@ impl<'ctx> Local<'ctx, Object> {
         Ok(())
     }
 }
+
+impl<'ctx> Local<'ctx, Object> {
+    /// `obj[index]` for integer-indexed properties (arrays, typed arrays, array-likes).
+    pub fn get_index(&self, scope: &Scope<'ctx>, index: u32) -> Result<Local<'ctx, Value>, String> {
+        let raw = unsafe { mquickjs_ffi::JS_GetPropertyUint32(scope.ctx(), self.as_raw(), index) };
+        if is_exception(raw) {
+            return Err(take_exception_message(scope.ctx(), "get_index"));
+        }
+        Ok(scope.value(raw))
+    }
+
+    /// `obj[index] = value`.
+    pub fn set_index(
+        &self,
+        scope: &Scope<'ctx>,
+        index: u32,
+        value: Local<'ctx, Value>,
+    ) -> Result<(), String> {
+        let r = unsafe {
+            mquickjs_ffi::JS_SetPropertyUint32(scope.ctx(), self.as_raw(), index, value.as_raw())
+        };
+        if is_exception(r) {
+            return Err(take_exception_message(scope.ctx(), "set_index"));
+        }
+        Ok(())
+    }
+
+    /// Own enumerable string keys, in the same order as `Object.keys(obj)`.
+    pub fn keys(&self, scope: &Scope<'ctx>) -> Result<Vec<String>, String> {
+        let ctx = scope.ctx();
+        let mut roots = TempRoots::new(ctx);
+        let keys_arr = roots.push(call_object_builtin(
+            ctx,
+            Builtin::ObjectKeys,
+            &[self.as_raw()],
+        )?);
+
+        let len_val = unsafe {
+            mquickjs_ffi::JS_GetPropertyStr(
+                ctx,
+                roots.get(keys_arr),
+                b"length\0".as_ptr() as *const _,
+            )
+        };
+        if is_exception(len_val) {
+            return Err(take_exception_message(ctx, "keys (length)"));
+        }
+        let mut len = 0i32;
+        if unsafe { mquickjs_ffi::JS_ToInt32(ctx, &mut len, len_val) } != 0 {
+            return Err("Failed to read keys length".to_string());
+        }
+
+        let mut out = Vec::with_capacity(len.max(0) as usize);
+        for i in 0..len.max(0) as u32 {
+            // Converting a key may allocate; re-read the array from its root every time.
+            let key = unsafe { mquickjs_ffi::JS_GetPropertyUint32(ctx, roots.get(keys_arr), i) };
+            if is_exception(key) {
+                return Err(take_exception_message(ctx, "keys"));
+            }
+            out.push(to_rust_string(ctx, key)?);
+        }
+        Ok(out)
+    }
+
+    /// `Object.prototype.hasOwnProperty.call(obj, name)`.
+    pub fn has_own(&self, scope: &Scope<'ctx>, name: &str) -> Result<bool, String> {
+        let ctx = scope.ctx();
+        let mut roots = TempRoots::new(ctx);
+        let this = roots.push(self.as_raw());
+        let key = new_key(ctx, name)?;
+        let r = call_builtin(ctx, Builtin::HasOwnProperty, roots.get(this), &[key])?;
+        Ok(r == mquickjs_ffi::JS_TRUE)
+    }
+
+    /// `name in obj`: own property or anywhere on the prototype chain.
+    pub fn has(&self, scope: &Scope<'ctx>, name: &str) -> Result<bool, String> {
+        let mut cur = Some(*self);
+        while let Some(o) = cur {
+            if o.has_own(scope, name)? {
+                return Ok(true);
+            }
+            cur = o.get_prototype(scope)?;
+        }
+        Ok(false)
+    }
+
+    /// `delete obj[name]`. Returns `false` when the property exists but is not configurable.
+    pub fn delete(&self, scope: &Scope<'ctx>, name: &str) -> Result<bool, String> {
+        let ctx = scope.ctx();
+        let mut roots = TempRoots::new(ctx);
+        let this = roots.push(self.as_raw());
+        let key = new_key(ctx, name)?;
+        let r = call_builtin(
+            ctx,
+            Builtin::DeleteProperty,
+            mquickjs_ffi::JS_UNDEFINED,
+            &[roots.get(this), key],
+        )?;
+        Ok(r == mquickjs_ffi::JS_TRUE)
+    }
+
+    /// `Object.defineProperty(obj, name, { value, writable, enumerable, configurable })`.
+    pub fn define_property(
+        &self,
+        scope: &Scope<'ctx>,
+        name: &str,
+        value: Local<'ctx, Value>,
+        attrs: PropertyAttributes,
+    ) -> Result<(), String> {
+        let ctx = scope.ctx();
+        let mut roots = TempRoots::new(ctx);
+        let this = roots.push(self.as_raw());
+        let value = roots.push(value.as_raw());
+        let desc = new_descriptor(ctx, &mut roots, attrs, true)?;
+        set_desc_field(ctx, roots.get(desc), b"value\0", roots.get(value))?;
+        define_with_descriptor(ctx, &mut roots, this, name, desc)
+    }
+
+    /// `Object.defineProperty(obj, name, { get, set, enumerable, configurable })`.
+    ///
+    /// `attrs.writable` is ignored: accessor descriptors have no writable flag.
+    pub fn define_accessor(
+        &self,
+        scope: &Scope<'ctx>,
+        name: &str,
+        getter: Option<Local<'ctx, Function>>,
+        setter: Option<Local<'ctx, Function>>,
+        attrs: PropertyAttributes,
+    ) -> Result<(), String> {
+        if getter.is_none() && setter.is_none() {
+            return Err("define_accessor requires a getter or a setter".to_string());
+        }
+        let ctx = scope.ctx();
+        let mut roots = TempRoots::new(ctx);
+        let this = roots.push(self.as_raw());
+        let get = getter.map(|g| roots.push(g.as_raw()));
+        let set = setter.map(|s| roots.push(s.as_raw()));
+        let desc = new_descriptor(ctx, &mut roots, attrs, false)?;
+        if let Some(g) = get {
+            set_desc_field(ctx, roots.get(desc), b"get\0", roots.get(g))?;
+        }
+        if let Some(s) = set {
+            set_desc_field(ctx, roots.get(desc), b"set\0", roots.get(s))?;
+        }
+        define_with_descriptor(ctx, &mut roots, this, name, desc)
+    }
+
+    /// `Object.getPrototypeOf(obj)`; `None` when the prototype is `null`.
+    pub fn get_prototype(
+        &self,
+        scope: &Scope<'ctx>,
+    ) -> Result<Option<Local<'ctx, Object>>, String> {
+        let raw = call_object_builtin(scope.ctx(), Builtin::GetPrototypeOf, &[self.as_raw()])?;
+        if raw == mquickjs_ffi::JS_NULL {
+            return Ok(None);
+        }
+        scope.value(raw).try_into_object(scope).map(Some)
+    }
+
+    /// `Object.setPrototypeOf(obj, proto)`; `None` sets a `null` prototype.
+    pub fn set_prototype(
+        &self,
+        scope: &Scope<'ctx>,
+        proto: Option<Local<'ctx, Object>>,
+    ) -> Result<(), String> {
+        let proto_raw = proto.map_or(mquickjs_ffi::JS_NULL, |p| p.as_raw());
+        call_object_builtin(
+            scope.ctx(),
+            Builtin::SetPrototypeOf,
+            &[self.as_raw(), proto_raw],
+        )?;
+        Ok(())
+    }
+}
+
+/// Build a descriptor object with the flags of `attrs`; returns its index in `roots`.
+fn new_descriptor(
+    ctx: *mut mquickjs_ffi::JSContext,
+    roots: &mut TempRoots,
+    attrs: PropertyAttributes,
+    is_data: bool,
+) -> Result<usize, String> {
+    let desc = unsafe { mquickjs_ffi::JS_NewObject(ctx) };
+    if is_exception(desc) {
+        return Err("Exception during JS_NewObject".to_string());
+    }
+    let desc = roots.push(desc);
+    if is_data {
+        set_desc_field(
+            ctx,
+            roots.get(desc),
+            b"writable\0",
+            mquickjs_ffi::js_mkbool(attrs.writable),
+        )?;
+    }
+    set_desc_field(
+        ctx,
+        roots.get(desc),
+        b"enumerable\0",
+        mquickjs_ffi::js_mkbool(attrs.enumerable),
+    )?;
+    set_desc_field(
+        ctx,
+        roots.get(desc),
+        b"configurable\0",
+        mquickjs_ffi::js_mkbool(attrs.configurable),
+    )?;
+    Ok(desc)
+}
+
+/// `Object.defineProperty(roots[obj], name, roots[desc])`.
+fn define_with_descriptor(
+    ctx: *mut mquickjs_ffi::JSContext,
+    roots: &mut TempRoots,
+    obj: usize,
+    name: &str,
+    desc: usize,
+) -> Result<(), String> {
+    let key = roots.push(new_key(ctx, name)?);
+    call_object_builtin(
+        ctx,
+        Builtin::DefineProperty,
+        &[roots.get(obj), roots.get(key), roots.get(desc)],
+    )?;
+    Ok(())
+}
+
+fn set_desc_field(
+    ctx: *mut mquickjs_ffi::JSContext,
+    desc: mquickjs_ffi::JSValue,
+    field: &'static [u8],
+    value: mquickjs_ffi::JSValue,
+) -> Result<(), String> {
+    let r =
+        unsafe { mquickjs_ffi::JS_SetPropertyStr(ctx, desc, field.as_ptr() as *const _, value) };
+    if is_exception(r) {
+        return Err(take_exception_message(ctx, "define_property"));
+    }
+    Ok(())
+}
+
+impl<'ctx> Local<'ctx, Object> {
+    /// `obj[name](...args)` with `this = obj`.
+    pub fn call_method<R: FromJs<'ctx>>(
+        &self,
+        env: &Env<'ctx>,
+        name: &str,
+        args: impl JsArgs<'ctx>,
+    ) -> Result<R, String> {
+        let scope = env.scope();
+        let func = expect_function(scope, self.get_property(scope, name)?, name)?;
+        func.call_with(env, *self, args)
+    }
+
+    /// Walk a dotted path (`"app.handlers.onTick"`) from this object and convert the leaf.
+    ///
+    /// Every intermediate segment must be an object; a missing leaf is `undefined`
+    /// (use `Option<T>` to accept it).
+    pub fn get_path<R: FromJs<'ctx>>(&self, env: &Env<'ctx>, path: &str) -> Result<R, String> {
+        let scope = env.scope();
+        // Getters on the path run JS and the leaf conversion may allocate; keep every
+        // intermediate rooted and re-read it from its slot.
+        let mut roots = TempRoots::new(scope.ctx_raw());
+        let mut cur = roots.push(self.as_raw());
+        let mut segments = path.split('.').peekable();
+        let mut walked = String::new();
+        while let Some(seg) = segments.next() {
+            if seg.is_empty() {
+                return Err(format!("invalid path: {path:?}"));
+            }
+            let obj: Local<'ctx, Object> =
+                Local::from_raw_for_same_ctx(roots.get(cur)).with_ctx_id(self.ctx_id());
+            let slot = roots.push(obj.get_property(scope, seg)?.as_raw());
+            if !walked.is_empty() {
+                walked.push('.');
+            }
+            walked.push_str(seg);
+            let v = scope.value(roots.get(slot));
+            if segments.peek().is_none() {
+                return R::from_js(scope, v).map_err(|e| format!("{walked}: {e}"));
+            }
+            if v.as_object(scope).is_none() {
+                return Err(format!(
+                    "{walked} is {}, not an object",
+                    v.kind(scope).name()
+                ));
+            }
+            cur = slot;
+        }
+        Err(format!("invalid path: {path:?}"))
+    }
+}
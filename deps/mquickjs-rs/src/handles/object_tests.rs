@@ -0,0 +1,169 @@
+use crate::handles::object::PropertyAttributes;
+use crate::Context;
+use crate::Env;
+
+#[test]
+fn reflection_ignores_script_overrides() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.eval(
+        "Object.keys = function() { return ['forged']; };
+         Object.getPrototypeOf = function() { return null; };
+         Object.defineProperty = function() { throw new Error('forged'); };
+         Object.prototype.hasOwnProperty = function() { return true; };
+         var o = { a: 1 };",
+    )
+    .unwrap();
+
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    let obj = env.global().unwrap().as_local();
+    let o = obj
+        .get_property(&scope, "o")
+        .unwrap()
+        .try_into_object(&scope)
+        .unwrap();
+    assert_eq!(o.keys(&scope).unwrap(), vec!["a"]);
+    assert!(o.has_own(&scope, "a").unwrap());
+    assert!(!o.has_own(&scope, "missing").unwrap());
+    assert!(o.get_prototype(&scope).unwrap().is_some());
+
+    let v = env.int_local(2).unwrap();
+    o.define_property(&scope, "b", v, PropertyAttributes::data())
+        .unwrap();
+    assert!(o.delete(&scope, "a").unwrap());
+    assert_eq!(o.keys(&scope).unwrap(), vec!["b"]);
+}
+
+#[test]
+fn is_object_is_exact() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let obj = env.obj().unwrap();
+    assert!(scope.value(obj.as_raw()).is_object(&scope));
+
+    let arr = env.array().unwrap();
+    assert!(scope.value(arr.as_raw()).is_object(&scope));
+
+    let s = env.str("x").unwrap();
+    assert!(!scope.value(s.as_raw()).is_object(&scope));
+
+    let n = env.int_local(1).unwrap();
+    assert!(!n.is_object(&scope));
+    assert!(!scope.value(crate::mquickjs_ffi::JS_NULL).is_object(&scope));
+    assert!(!scope
+        .value(crate::mquickjs_ffi::JS_UNDEFINED)
+        .is_object(&scope));
+    assert!(!scope.value(crate::mquickjs_ffi::JS_TRUE).is_object(&scope));
+}
+
+#[test]
+fn keys_has_delete() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let obj = env.obj().unwrap();
+    let obj = scope.value(obj.as_raw()).try_into_object(&scope).unwrap();
+
+    let one = env.int_local(1).unwrap();
+    obj.set_property(&scope, "a", one).unwrap();
+    let two = env.int_local(2).unwrap();
+    obj.set_property(&scope, "b", two).unwrap();
+
+    assert_eq!(obj.keys(&scope).unwrap(), vec!["a", "b"]);
+    assert!(obj.has_own(&scope, "a").unwrap());
+    assert!(!obj.has_own(&scope, "toString").unwrap());
+    assert!(obj.has(&scope, "toString").unwrap());
+
+    assert!(obj.delete(&scope, "a").unwrap());
+    assert!(!obj.has(&scope, "a").unwrap());
+    assert_eq!(obj.keys(&scope).unwrap(), vec!["b"]);
+}
+
+#[test]
+fn define_property_respects_flags() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let obj = env.obj().unwrap();
+    let obj = scope.value(obj.as_raw()).try_into_object(&scope).unwrap();
+
+    let v = env.int_local(7).unwrap();
+    obj.define_property(&scope, "hidden", v, PropertyAttributes::default())
+        .unwrap();
+
+    // non-enumerable: not listed, but still readable
+    assert!(obj.keys(&scope).unwrap().is_empty());
+    let got = obj.get_property(&scope, "hidden").unwrap();
+    assert_eq!(env.get_number(got).unwrap(), 7.0);
+
+    // non-configurable: delete fails
+    assert!(!obj.delete(&scope, "hidden").unwrap());
+}
+
+#[test]
+fn prototype_and_index_access() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let proto = env.obj().unwrap();
+    let proto = scope.value(proto.as_raw()).try_into_object(&scope).unwrap();
+    let v = env.int_local(3).unwrap();
+    proto.set_property(&scope, "inherited", v).unwrap();
+
+    let obj = env.obj().unwrap();
+    let obj = scope.value(obj.as_raw()).try_into_object(&scope).unwrap();
+    obj.set_prototype(&scope, Some(proto)).unwrap();
+
+    let got = obj.get_prototype(&scope).unwrap().unwrap();
+    assert_eq!(got.as_raw(), proto.as_raw());
+    assert!(obj.has(&scope, "inherited").unwrap());
+    assert!(!obj.has_own(&scope, "inherited").unwrap());
+
+    obj.set_prototype(&scope, None).unwrap();
+    assert!(obj.get_prototype(&scope).unwrap().is_none());
+    assert!(!obj.has(&scope, "inherited").unwrap());
+
+    let x = env.str("x").unwrap();
+    let x_raw = x.as_raw();
+    obj.set_index(&scope, 0, scope.value(x_raw)).unwrap();
+    let v0 = obj.get_index(&scope, 0).unwrap();
+    assert_eq!(env.get_string(v0).unwrap(), "x");
+    assert_eq!(obj.keys(&scope).unwrap(), vec!["0"]);
+}
+
+#[test]
+fn captured_builtins_survive_gc() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    // The captured builtins are reused by every call; a GC in between must neither free nor
+    // stale them.
+    for i in 0..3 {
+        let obj = env.obj().unwrap();
+        let obj = scope.value(obj.as_raw()).try_into_object(&scope).unwrap();
+        let v = env.int_local(i).unwrap();
+        obj.set_property(&scope, "k", v).unwrap();
+        assert!(obj.delete(&scope, "k").unwrap());
+        assert!(!obj.has_own(&scope, "k").unwrap());
+        unsafe { crate::mquickjs_ffi::JS_GC(ctx.ctx) };
+    }
+    assert_eq!(ctx.globals_count(), 0);
+}
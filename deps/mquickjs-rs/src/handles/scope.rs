@@ -53,6 +53,11 @@ impl<'ctx> Scope<'ctx> {
         ContextId(std::sync::Arc::as_ptr(&self.h.inner) as usize as u64)
     }
 
+    /// The token this scope was entered from (e.g. for `ContextToken::host_data`).
+    pub fn token(&self) -> &'ctx ContextToken {
+        self.h
+    }
+
     pub fn from_handle(h: &'ctx ContextToken) -> Self {
         let id = ContextId(std::sync::Arc::as_ptr(&h.inner) as usize as u64);
         TLS_CURRENT.with(|s| {
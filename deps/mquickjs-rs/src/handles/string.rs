@@ -0,0 +1,247 @@
+//! JS string access.
+//!
+//! mquickjs stores strings as WTF-8: UTF-8 that may additionally contain lone surrogates
+//! (encoded as 3-byte sequences). `JS_ToCStringLen` returns a pointer into the engine-owned
+//! string, or into the caller's `JSCStringBuf` for very short strings. Nothing is allocated
+//! for the caller, which is why mquickjs has no `JS_FreeCString`; the flip side is that the
+//! pointer is only valid until the next GC, so everything here copies immediately except the
+//! explicitly `unsafe` [`Local::<JsString>::as_str`] view.
+
+use std::borrow::Cow;
+
+use crate::env::Env;
+use crate::handles::handle::Handle;
+use crate::handles::js_call::is_exception;
+use crate::handles::local::{JsString, Local, Value};
+use crate::handles::scope::Scope;
+use crate::handles::value_kind::ValueKind;
+use crate::mquickjs_ffi;
+
+/// Backing storage for [`Local::<JsString>::as_str`] (short strings are copied into it).
+pub struct JsStringBuf(mquickjs_ffi::JSCStringBuf);
+
+impl JsStringBuf {
+    pub fn new() -> Self {
+        Self(mquickjs_ffi::JSCStringBuf { buf: [0; 5] })
+    }
+}
+
+impl Default for JsStringBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `ToString(v)` as borrowed WTF-8 bytes (interior NULs included).
+///
+/// # Safety
+/// `ctx` must be live and `v` belong to it; the slice must be dropped before the next GC.
+unsafe fn raw_bytes<'b>(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+    buf: &'b mut mquickjs_ffi::JSCStringBuf,
+) -> Option<&'b [u8]> {
+    let mut len = 0usize;
+    let ptr = unsafe { mquickjs_ffi::JS_ToCStringLen(ctx, &mut len, v, buf) };
+    if ptr.is_null() {
+        return None;
+    }
+    Some(unsafe { std::slice::from_raw_parts(ptr as *const u8, len) })
+}
+
+/// Decode WTF-8 into UTF-16 code units; lone surrogates are preserved as-is.
+pub(crate) fn wtf8_to_utf16(bytes: &[u8]) -> Vec<u16> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let cont = |i: usize| bytes.get(i).map_or(0, |b| (*b & 0x3f) as u32);
+    let mut i = 0;
+    while i < bytes.len() {
+        let b0 = bytes[i] as u32;
+        let (cp, n) = if b0 < 0x80 {
+            (b0, 1)
+        } else if b0 >> 5 == 0x6 {
+            (((b0 & 0x1f) << 6) | cont(i + 1), 2)
+        } else if b0 >> 4 == 0xe {
+            (((b0 & 0x0f) << 12) | (cont(i + 1) << 6) | cont(i + 2), 3)
+        } else if b0 >> 3 == 0x1e {
+            (
+                ((b0 & 0x07) << 18) | (cont(i + 1) << 12) | (cont(i + 2) << 6) | cont(i + 3),
+                4,
+            )
+        } else {
+            (0xfffd, 1)
+        };
+        if cp >= 0x10000 {
+            let c = cp - 0x10000;
+            out.push(0xd800 | (c >> 10) as u16);
+            out.push(0xdc00 | (c & 0x3ff) as u16);
+        } else {
+            out.push(cp as u16);
+        }
+        i += n;
+    }
+    out
+}
+
+/// Encode UTF-16 code units as WTF-8; lone surrogates become 3-byte sequences.
+pub(crate) fn utf16_to_wtf8(units: &[u16]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(units.len());
+    for r in char::decode_utf16(units.iter().copied()) {
+        match r {
+            Ok(c) => {
+                let mut tmp = [0u8; 4];
+                out.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+            }
+            Err(e) => {
+                let u = e.unpaired_surrogate();
+                out.push(0xe0 | (u >> 12) as u8);
+                out.push(0x80 | ((u >> 6) & 0x3f) as u8);
+                out.push(0x80 | (u & 0x3f) as u8);
+            }
+        }
+    }
+    out
+}
+
+/// WTF-8 to `String`; lone surrogates become U+FFFD.
+fn wtf8_to_string_lossy(bytes: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Cow::Borrowed(s),
+        Err(_) => Cow::Owned(String::from_utf16_lossy(&wtf8_to_utf16(bytes))),
+    }
+}
+
+/// `ToString(v)` copied into a Rust `String` (lone surrogates become U+FFFD).
+///
+/// Unlike `CStr::from_ptr`, the full length is used, so interior NULs survive.
+///
+/// # Safety
+/// `ctx` must be a live JSContext and `v` a value that belongs to it.
+pub unsafe fn to_rust_string_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+) -> Option<String> {
+    let mut buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
+    let bytes = unsafe { raw_bytes(ctx, v, &mut buf) }?;
+    Some(wtf8_to_string_lossy(bytes).into_owned())
+}
+
+/// Create a JS string from raw WTF-8 bytes (may contain NULs).
+///
+/// # Safety
+/// `ctx` must be a live JSContext.
+pub(crate) unsafe fn new_string_wtf8_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    bytes: &[u8],
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let raw =
+        unsafe { mquickjs_ffi::JS_NewStringLen(ctx, bytes.as_ptr() as *const _, bytes.len()) };
+    if is_exception(raw) {
+        return Err("Exception during JS_NewStringLen".to_string());
+    }
+    Ok(raw)
+}
+
+/// Create a JS string from `s`; interior NULs are kept.
+///
+/// # Safety
+/// `ctx` must be a live JSContext.
+pub unsafe fn new_string_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    s: &str,
+) -> Result<mquickjs_ffi::JSValue, String> {
+    unsafe { new_string_wtf8_raw(ctx, s.as_bytes()) }
+}
+
+impl<'ctx> Local<'ctx, Value> {
+    pub fn is_string(&self, scope: &Scope<'ctx>) -> bool {
+        self.kind(scope) == ValueKind::String
+    }
+
+    pub fn try_into_string(self, scope: &Scope<'ctx>) -> Result<Local<'ctx, JsString>, String> {
+        if self.is_string(scope) {
+            Ok(Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id()))
+        } else {
+            Err(format!("expected string, got {}", self.kind(scope).name()))
+        }
+    }
+}
+
+impl<'ctx> Local<'ctx, JsString> {
+    fn with_bytes<R>(&self, scope: &Scope<'ctx>, f: impl FnOnce(&[u8]) -> R) -> Result<R, String> {
+        let mut buf = mquickjs_ffi::JSCStringBuf { buf: [0; 5] };
+        let bytes = unsafe { raw_bytes(scope.ctx_raw(), self.as_raw(), &mut buf) }
+            .ok_or_else(|| "Failed to read string contents".to_string())?;
+        Ok(f(bytes))
+    }
+
+    /// Copy into a Rust `String`. Lone surrogates become U+FFFD; use [`Self::to_utf16`] or
+    /// [`Self::to_wtf8`] when the exact contents matter.
+    pub fn to_rust_string(&self, scope: &Scope<'ctx>) -> Result<String, String> {
+        self.with_bytes(scope, |b| wtf8_to_string_lossy(b).into_owned())
+    }
+
+    /// Exact contents as UTF-16 code units (what JS indexes by).
+    pub fn to_utf16(&self, scope: &Scope<'ctx>) -> Result<Vec<u16>, String> {
+        self.with_bytes(scope, wtf8_to_utf16)
+    }
+
+    /// Exact contents in the engine's WTF-8 encoding.
+    pub fn to_wtf8(&self, scope: &Scope<'ctx>) -> Result<Vec<u8>, String> {
+        self.with_bytes(scope, |b| b.to_vec())
+    }
+
+    /// `str.length`, i.e. the length in UTF-16 code units.
+    pub fn len_utf16(&self, scope: &Scope<'ctx>) -> Result<u32, String> {
+        let ctx = scope.ctx_raw();
+        let raw = unsafe {
+            mquickjs_ffi::JS_GetPropertyStr(ctx, self.as_raw(), b"length\0".as_ptr() as *const _)
+        };
+        if is_exception(raw) {
+            return Err("Exception during string length".to_string());
+        }
+        let mut out = 0i32;
+        if unsafe { mquickjs_ffi::JS_ToInt32(ctx, &mut out, raw) } != 0 {
+            return Err("Failed to convert string length".to_string());
+        }
+        Ok(out as u32)
+    }
+
+    pub fn is_empty(&self, scope: &Scope<'ctx>) -> Result<bool, String> {
+        Ok(self.len_utf16(scope)? == 0)
+    }
+
+    /// Borrow the contents without copying when they are valid UTF-8 (no lone surrogates);
+    /// otherwise a lossy owned copy is returned.
+    ///
+    /// # Safety
+    /// The borrowed form points into GC-managed memory: no JS value may be allocated
+    /// (which could trigger a GC) while the returned `Cow` is alive.
+    pub unsafe fn as_str<'b>(
+        &self,
+        scope: &Scope<'ctx>,
+        buf: &'b mut JsStringBuf,
+    ) -> Result<Cow<'b, str>, String> {
+        let bytes = unsafe { raw_bytes(scope.ctx_raw(), self.as_raw(), &mut buf.0) }
+            .ok_or_else(|| "Failed to read string contents".to_string())?;
+        Ok(wtf8_to_string_lossy(bytes))
+    }
+}
+
+impl<'ctx> Env<'ctx> {
+    /// A JS string holding `s`; interior NULs are kept.
+    pub fn string<'hs>(&'hs mut self, s: &str) -> Result<Handle<'hs, 'ctx, JsString>, String> {
+        let scope = self.scope();
+        let raw = unsafe { new_string_raw(scope.ctx_raw(), s)? };
+        Ok(self.handle(scope.value(raw).try_into_string(scope)?))
+    }
+
+    /// A JS string from UTF-16 code units; lone surrogates are preserved.
+    pub fn string_from_utf16<'hs>(
+        &'hs mut self,
+        units: &[u16],
+    ) -> Result<Handle<'hs, 'ctx, JsString>, String> {
+        let scope = self.scope();
+        let raw = unsafe { new_string_wtf8_raw(scope.ctx_raw(), &utf16_to_wtf8(units))? };
+        Ok(self.handle(scope.value(raw).try_into_string(scope)?))
+    }
+}
@@ -0,0 +1,96 @@
+use std::ffi::CString;
+
+use crate::handles::string::{utf16_to_wtf8, wtf8_to_utf16};
+use crate::mquickjs_ffi;
+use crate::Context;
+use crate::Env;
+use crate::JsStringBuf;
+
+fn eval_raw(ctx: &Context, code: &str) -> mquickjs_ffi::JSValue {
+    let c_code = CString::new(code).unwrap();
+    let filename = CString::new("eval.js").unwrap();
+    unsafe {
+        mquickjs_ffi::JS_Eval(
+            ctx.ctx,
+            c_code.as_ptr(),
+            code.len(),
+            filename.as_ptr(),
+            mquickjs_ffi::JS_EVAL_RETVAL as i32,
+        )
+    }
+}
+
+#[test]
+fn wtf8_utf16_round_trip_keeps_lone_surrogates() {
+    let units: Vec<u16> = vec![0x61, 0xd800, 0x62, 0xd83d, 0xde00, 0xdc00];
+    let bytes = utf16_to_wtf8(&units);
+    // 'a' + ED A0 80 + 'b' + F0 9F 98 80 + ED B0 80
+    assert_eq!(
+        bytes,
+        vec![0x61, 0xed, 0xa0, 0x80, 0x62, 0xf0, 0x9f, 0x98, 0x80, 0xed, 0xb0, 0x80]
+    );
+    assert_eq!(wtf8_to_utf16(&bytes), units);
+    assert_eq!(
+        wtf8_to_utf16("é😀".as_bytes()),
+        "é😀".encode_utf16().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn string_to_rust_and_length() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let v = scope.value(eval_raw(&ctx, "'h\u{e9}llo \u{1F600}'"));
+    assert!(v.is_string(&scope));
+    let s = v.try_into_string(&scope).unwrap();
+    assert_eq!(s.to_rust_string(&scope).unwrap(), "h\u{e9}llo \u{1F600}");
+    // The emoji is a surrogate pair: two UTF-16 units.
+    assert_eq!(s.len_utf16(&scope).unwrap(), 8);
+    assert!(!s.is_empty(&scope).unwrap());
+
+    let mut buf = JsStringBuf::new();
+    let view = unsafe { s.as_str(&scope, &mut buf) }.unwrap();
+    assert_eq!(&*view, "h\u{e9}llo \u{1F600}");
+
+    let n = scope.value(eval_raw(&ctx, "42"));
+    assert!(!n.is_string(&scope));
+    assert!(n.try_into_string(&scope).is_err());
+}
+
+#[test]
+fn lone_surrogates_are_lossless_via_utf16() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let v = scope.value(eval_raw(&ctx, "'a' + String.fromCharCode(0xd800) + 'b'"));
+    let s = v.try_into_string(&scope).unwrap();
+    assert_eq!(s.len_utf16(&scope).unwrap(), 3);
+    assert_eq!(s.to_utf16(&scope).unwrap(), vec![0x61, 0xd800, 0x62]);
+    assert_eq!(s.to_rust_string(&scope).unwrap(), "a\u{fffd}b");
+
+    let mut env = Env::new(&scope);
+    let back = env.string_from_utf16(&[0x61, 0xdc00]).unwrap();
+    let back = scope.value(back.as_raw()).try_into_string(&scope).unwrap();
+    assert_eq!(back.len_utf16(&scope).unwrap(), 2);
+    assert_eq!(back.to_utf16(&scope).unwrap(), vec![0x61, 0xdc00]);
+}
+
+#[test]
+fn interior_nul_survives_round_trip() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+    let h = env.string("a\0b").unwrap();
+    let s = scope.value(h.as_raw()).try_into_string(&scope).unwrap();
+    assert_eq!(s.len_utf16(&scope).unwrap(), 3);
+    assert_eq!(s.to_rust_string(&scope).unwrap(), "a\0b");
+    assert_eq!(env.get_string(scope.value(h.as_raw())).unwrap(), "a\0b");
+
+    let v = env.str("x\0y").unwrap();
+    assert_eq!(ctx.get_string(scope.value(v.as_raw())).unwrap(), "x\0y");
+}
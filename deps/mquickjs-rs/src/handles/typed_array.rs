@@ -0,0 +1,390 @@
+//! Typed arrays and `ArrayBuffer`.
+//!
+//! Kinds are classified by class id. mquickjs keeps the storage private (there is no exported
+//! data-pointer accessor), so contents are copied element-wise through a `Uint8Array` view
+//! with the property API. `with_bytes` lends the copied bytes as a slice.
+
+use crate::env::Env;
+use crate::handles::handle::Handle;
+use crate::handles::js_call::{
+    cached_helper, call_raw, is_exception, take_exception_message, TempRoots,
+};
+use crate::handles::local::{ArrayBuffer, Local, TypedArray, Value};
+use crate::handles::scope::Scope;
+use crate::mquickjs_ffi;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TypedArrayKind {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+}
+
+impl TypedArrayKind {
+    pub const ALL: [TypedArrayKind; 9] = [
+        TypedArrayKind::Int8,
+        TypedArrayKind::Uint8,
+        TypedArrayKind::Uint8Clamped,
+        TypedArrayKind::Int16,
+        TypedArrayKind::Uint16,
+        TypedArrayKind::Int32,
+        TypedArrayKind::Uint32,
+        TypedArrayKind::Float32,
+        TypedArrayKind::Float64,
+    ];
+
+    /// Global constructor name, e.g. `"Float32Array"`.
+    pub fn ctor_name(&self) -> &'static str {
+        let name = self.ctor_name_nul();
+        std::str::from_utf8(&name[..name.len() - 1]).unwrap()
+    }
+
+    fn ctor_name_nul(&self) -> &'static [u8] {
+        match self {
+            TypedArrayKind::Int8 => b"Int8Array\0",
+            TypedArrayKind::Uint8 => b"Uint8Array\0",
+            TypedArrayKind::Uint8Clamped => b"Uint8ClampedArray\0",
+            TypedArrayKind::Int16 => b"Int16Array\0",
+            TypedArrayKind::Uint16 => b"Uint16Array\0",
+            TypedArrayKind::Int32 => b"Int32Array\0",
+            TypedArrayKind::Uint32 => b"Uint32Array\0",
+            TypedArrayKind::Float32 => b"Float32Array\0",
+            TypedArrayKind::Float64 => b"Float64Array\0",
+        }
+    }
+
+    /// Bytes per element.
+    pub fn element_size(&self) -> usize {
+        match self {
+            TypedArrayKind::Int8 | TypedArrayKind::Uint8 | TypedArrayKind::Uint8Clamped => 1,
+            TypedArrayKind::Int16 | TypedArrayKind::Uint16 => 2,
+            TypedArrayKind::Int32 | TypedArrayKind::Uint32 | TypedArrayKind::Float32 => 4,
+            TypedArrayKind::Float64 => 8,
+        }
+    }
+}
+
+impl TypedArrayKind {
+    fn class_id(&self) -> i32 {
+        (match self {
+            TypedArrayKind::Int8 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_INT8_ARRAY,
+            TypedArrayKind::Uint8 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_UINT8_ARRAY,
+            TypedArrayKind::Uint8Clamped => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_UINT8C_ARRAY,
+            TypedArrayKind::Int16 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_INT16_ARRAY,
+            TypedArrayKind::Uint16 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_UINT16_ARRAY,
+            TypedArrayKind::Int32 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_INT32_ARRAY,
+            TypedArrayKind::Uint32 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_UINT32_ARRAY,
+            TypedArrayKind::Float32 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_FLOAT32_ARRAY,
+            TypedArrayKind::Float64 => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_FLOAT64_ARRAY,
+        }) as i32
+    }
+
+    /// Decode one element from its platform-order bytes (`bytes.len() == element_size()`).
+    fn decode(&self, bytes: &[u8]) -> f64 {
+        match self {
+            TypedArrayKind::Int8 => bytes[0] as i8 as f64,
+            TypedArrayKind::Uint8 | TypedArrayKind::Uint8Clamped => bytes[0] as f64,
+            TypedArrayKind::Int16 => i16::from_ne_bytes([bytes[0], bytes[1]]) as f64,
+            TypedArrayKind::Uint16 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f64,
+            TypedArrayKind::Int32 => i32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            TypedArrayKind::Uint32 => u32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            TypedArrayKind::Float32 => f32::from_ne_bytes(bytes.try_into().unwrap()) as f64,
+            TypedArrayKind::Float64 => f64::from_ne_bytes(bytes.try_into().unwrap()),
+        }
+    }
+}
+
+fn typed_array_kind_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+) -> Option<TypedArrayKind> {
+    // Every typed array kind has its own class, so one class id read classifies exactly.
+    let class_id = unsafe { mquickjs_ffi::JS_GetClassID(ctx, v) };
+    TypedArrayKind::ALL
+        .into_iter()
+        .find(|k| k.class_id() == class_id)
+}
+
+fn is_array_buffer_raw(ctx: *mut mquickjs_ffi::JSContext, v: mquickjs_ffi::JSValue) -> bool {
+    (unsafe { mquickjs_ffi::JS_GetClassID(ctx, v) })
+        == mquickjs_ffi::JSObjectClassEnum_JS_CLASS_ARRAY_BUFFER as i32
+}
+
+fn get_u32_prop(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+    name: &'static [u8],
+) -> Result<u32, String> {
+    let raw = unsafe { mquickjs_ffi::JS_GetPropertyStr(ctx, v, name.as_ptr() as *const _) };
+    if is_exception(raw) {
+        return Err(take_exception_message(ctx, "typed array property read"));
+    }
+    let mut out = 0.0f64;
+    if unsafe { mquickjs_ffi::JS_ToNumber(ctx, &mut out, raw) } != 0 {
+        return Err("Failed to convert typed array length".to_string());
+    }
+    Ok(out as u32)
+}
+
+/// A `Uint8Array` over the bytes of a typed array (any kind) or `ArrayBuffer`; reads and
+/// writes through it reach the original storage. Returns its index in `roots`.
+fn byte_view(
+    ctx: *mut mquickjs_ffi::JSContext,
+    roots: &mut TempRoots,
+    v: mquickjs_ffi::JSValue,
+) -> Result<usize, String> {
+    let is_buffer = is_array_buffer_raw(ctx, v);
+    match typed_array_kind_raw(ctx, v) {
+        Some(TypedArrayKind::Uint8) => return Ok(roots.push(v)),
+        Some(_) => {}
+        None if is_buffer => {}
+        None => return Err("expected Uint8Array, typed array or ArrayBuffer".to_string()),
+    }
+    let v = roots.push(v);
+    let helper = cached_helper(
+        ctx,
+        b"(function (v, buf) { return buf ? new Uint8Array(v) : new Uint8Array(v.buffer, v.byteOffset, v.byteLength); })",
+        "Uint8Array view",
+    )?;
+    let view = call_raw(
+        ctx,
+        helper,
+        mquickjs_ffi::JS_UNDEFINED,
+        &[roots.get(v), mquickjs_ffi::js_mkbool(is_buffer)],
+        "Uint8Array view",
+    )?;
+    Ok(roots.push(view))
+}
+
+/// Byte length of a typed array (any kind) or `ArrayBuffer`.
+fn byte_length_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+) -> Result<u32, String> {
+    if !is_array_buffer_raw(ctx, v) && typed_array_kind_raw(ctx, v).is_none() {
+        return Err("expected Uint8Array, typed array or ArrayBuffer".to_string());
+    }
+    get_u32_prop(ctx, v, b"byteLength\0")
+}
+
+/// Copy the bytes of a typed array (any element kind) or an `ArrayBuffer`.
+///
+/// # Safety
+/// `ctx` must be a live JSContext and `v` a value that belongs to it.
+pub unsafe fn bytes_from_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+) -> Result<Vec<u8>, String> {
+    let mut roots = TempRoots::new(ctx);
+    let view = byte_view(ctx, &mut roots, v)?;
+    let len = get_u32_prop(ctx, roots.get(view), b"length\0")?;
+    let mut out = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let raw = unsafe { mquickjs_ffi::JS_GetPropertyUint32(ctx, roots.get(view), i) };
+        if is_exception(raw) {
+            return Err(take_exception_message(ctx, "typed array read"));
+        }
+        let mut b = 0i32;
+        if unsafe { mquickjs_ffi::JS_ToInt32(ctx, &mut b, raw) } != 0 {
+            return Err("Failed to convert typed array element".to_string());
+        }
+        out.push(b as u8);
+    }
+    Ok(out)
+}
+
+/// Overwrite the first `bytes.len()` bytes of a typed array or `ArrayBuffer`.
+fn write_bytes_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    v: mquickjs_ffi::JSValue,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let mut roots = TempRoots::new(ctx);
+    let view = byte_view(ctx, &mut roots, v)?;
+    let byte_len = get_u32_prop(ctx, roots.get(view), b"length\0")? as usize;
+    if bytes.len() > byte_len {
+        return Err(format!(
+            "{} bytes do not fit into a typed array of {byte_len} bytes",
+            bytes.len()
+        ));
+    }
+    for (i, b) in bytes.iter().enumerate() {
+        let b = unsafe { mquickjs_ffi::JS_NewInt32(ctx, *b as i32) };
+        let r = unsafe { mquickjs_ffi::JS_SetPropertyUint32(ctx, roots.get(view), i as u32, b) };
+        if is_exception(r) {
+            return Err(take_exception_message(ctx, "typed array write"));
+        }
+    }
+    Ok(())
+}
+
+/// Create a `Uint8Array` holding a copy of `bytes`.
+///
+/// # Safety
+/// `ctx` must be a live JSContext.
+pub unsafe fn new_uint8_array_raw(
+    ctx: *mut mquickjs_ffi::JSContext,
+    bytes: &[u8],
+) -> Result<mquickjs_ffi::JSValue, String> {
+    let len = u32::try_from(bytes.len()).map_err(|_| "byte buffer too large".to_string())?;
+    let mut roots = TempRoots::new(ctx);
+    let n = roots.push(unsafe { mquickjs_ffi::JS_NewUint32(ctx, len) });
+    let helper = cached_helper(
+        ctx,
+        b"(function (n) { return new Uint8Array(n); })",
+        "new Uint8Array",
+    )?;
+    let arr = call_raw(
+        ctx,
+        helper,
+        mquickjs_ffi::JS_UNDEFINED,
+        &[roots.get(n)],
+        "new Uint8Array",
+    )?;
+    let arr = roots.push(arr);
+    write_bytes_raw(ctx, roots.get(arr), bytes)?;
+    Ok(roots.get(arr))
+}
+
+impl<'ctx> Local<'ctx, Value> {
+    /// Element kind when this value is a typed array (`Uint8Array`, `Float32Array`, ...).
+    pub fn typed_array_kind(&self, scope: &Scope<'ctx>) -> Option<TypedArrayKind> {
+        typed_array_kind_raw(scope.ctx(), self.as_raw())
+    }
+
+    pub fn is_typed_array(&self, scope: &Scope<'ctx>) -> bool {
+        self.typed_array_kind(scope).is_some()
+    }
+
+    pub fn is_array_buffer(&self, scope: &Scope<'ctx>) -> bool {
+        is_array_buffer_raw(scope.ctx(), self.as_raw())
+    }
+
+    pub fn try_into_typed_array(
+        self,
+        scope: &Scope<'ctx>,
+    ) -> Result<Local<'ctx, TypedArray>, String> {
+        if self.is_typed_array(scope) {
+            Ok(Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id()))
+        } else {
+            Err("Value is not a typed array".to_string())
+        }
+    }
+
+    pub fn try_into_array_buffer(
+        self,
+        scope: &Scope<'ctx>,
+    ) -> Result<Local<'ctx, ArrayBuffer>, String> {
+        if self.is_array_buffer(scope) {
+            Ok(Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id()))
+        } else {
+            Err("Value is not an ArrayBuffer".to_string())
+        }
+    }
+}
+
+impl<'ctx> Local<'ctx, TypedArray> {
+    pub fn kind(&self, scope: &Scope<'ctx>) -> Result<TypedArrayKind, String> {
+        typed_array_kind_raw(scope.ctx(), self.as_raw())
+            .ok_or_else(|| "Value is no longer a typed array".to_string())
+    }
+
+    /// Number of elements.
+    pub fn len(&self, scope: &Scope<'ctx>) -> Result<u32, String> {
+        Ok(self.byte_length(scope)? / self.kind(scope)?.element_size() as u32)
+    }
+
+    pub fn is_empty(&self, scope: &Scope<'ctx>) -> Result<bool, String> {
+        Ok(self.len(scope)? == 0)
+    }
+
+    pub fn byte_length(&self, scope: &Scope<'ctx>) -> Result<u32, String> {
+        byte_length_raw(scope.ctx(), self.as_raw())
+    }
+
+    /// Raw bytes in platform byte order (copied).
+    pub fn to_bytes(&self, scope: &Scope<'ctx>) -> Result<Vec<u8>, String> {
+        unsafe { bytes_from_raw(scope.ctx(), self.as_raw()) }
+    }
+
+    /// Decode every element as a number, whatever the element kind.
+    pub fn to_f64_vec(&self, scope: &Scope<'ctx>) -> Result<Vec<f64>, String> {
+        let kind = self.kind(scope)?;
+        let bytes = self.to_bytes(scope)?;
+        Ok(bytes
+            .chunks_exact(kind.element_size())
+            .map(|e| kind.decode(e))
+            .collect())
+    }
+
+    /// Lend the contents as `&[u8]` for the duration of `f` (see module docs: this is a copy).
+    pub fn with_bytes<R>(
+        &self,
+        scope: &Scope<'ctx>,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> Result<R, String> {
+        let bytes = self.to_bytes(scope)?;
+        Ok(f(&bytes))
+    }
+
+    /// Overwrite the first `bytes.len()` bytes of the array's storage (platform byte order,
+    /// whatever the element kind); the rest is left as is.
+    pub fn write_bytes(&self, scope: &Scope<'ctx>, bytes: &[u8]) -> Result<(), String> {
+        write_bytes_raw(scope.ctx(), self.as_raw(), bytes)
+    }
+}
+
+impl<'ctx> Local<'ctx, ArrayBuffer> {
+    pub fn byte_length(&self, scope: &Scope<'ctx>) -> Result<u32, String> {
+        byte_length_raw(scope.ctx(), self.as_raw())
+    }
+
+    pub fn to_bytes(&self, scope: &Scope<'ctx>) -> Result<Vec<u8>, String> {
+        unsafe { bytes_from_raw(scope.ctx(), self.as_raw()) }
+    }
+
+    /// Lend the contents as `&[u8]` for the duration of `f` (a copy, like
+    /// `Local<TypedArray>::with_bytes`).
+    pub fn with_bytes<R>(
+        &self,
+        scope: &Scope<'ctx>,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> Result<R, String> {
+        let bytes = self.to_bytes(scope)?;
+        Ok(f(&bytes))
+    }
+}
+
+impl<'ctx> Env<'ctx> {
+    /// `new Uint8Array(bytes)` (copied).
+    pub fn uint8_array<'hs>(
+        &'hs mut self,
+        bytes: &[u8],
+    ) -> Result<Handle<'hs, 'ctx, TypedArray>, String> {
+        let scope = self.scope();
+        let raw = unsafe { new_uint8_array_raw(scope.ctx_raw(), bytes)? };
+        Ok(self.handle(scope.value(raw).try_into_typed_array(scope)?))
+    }
+
+    /// An `ArrayBuffer` holding a copy of `bytes`.
+    pub fn array_buffer<'hs>(
+        &'hs mut self,
+        bytes: &[u8],
+    ) -> Result<Handle<'hs, 'ctx, ArrayBuffer>, String> {
+        let scope = self.scope();
+        let ctx = scope.ctx_raw();
+        let arr = unsafe { new_uint8_array_raw(ctx, bytes)? };
+        let buffer =
+            unsafe { mquickjs_ffi::JS_GetPropertyStr(ctx, arr, b"buffer\0".as_ptr() as *const _) };
+        if is_exception(buffer) {
+            return Err(take_exception_message(ctx, "Uint8Array buffer"));
+        }
+        Ok(self.handle(scope.value(buffer).try_into_array_buffer(scope)?))
+    }
+}
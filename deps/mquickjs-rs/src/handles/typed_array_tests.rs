@@ -0,0 +1,139 @@
+use std::ffi::CString;
+
+use crate::handles::typed_array::TypedArrayKind;
+use crate::mquickjs_ffi;
+use crate::Context;
+use crate::Env;
+
+fn eval_raw(ctx: &Context, code: &str) -> mquickjs_ffi::JSValue {
+    let c_code = CString::new(code).unwrap();
+    let filename = CString::new("eval.js").unwrap();
+    unsafe {
+        mquickjs_ffi::JS_Eval(
+            ctx.ctx,
+            c_code.as_ptr(),
+            code.len(),
+            filename.as_ptr(),
+            mquickjs_ffi::JS_EVAL_RETVAL as i32,
+        )
+    }
+}
+
+#[test]
+fn uint8_array_round_trip() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let arr = env.uint8_array(&[1, 2, 255]).unwrap();
+    let arr = scope.value(arr.as_raw());
+    assert_eq!(arr.typed_array_kind(&scope), Some(TypedArrayKind::Uint8));
+
+    let arr = arr.try_into_typed_array(&scope).unwrap();
+    assert_eq!(arr.len(&scope).unwrap(), 3);
+    assert_eq!(arr.to_bytes(&scope).unwrap(), vec![1, 2, 255]);
+    let sum = arr
+        .with_bytes(&scope, |b| b.iter().map(|&x| x as u32).sum::<u32>())
+        .unwrap();
+    assert_eq!(sum, 258);
+
+    let buf = env.array_buffer(&[9, 8]).unwrap();
+    let buf = scope.value(buf.as_raw());
+    assert!(buf.is_array_buffer(&scope));
+    assert!(!buf.is_typed_array(&scope));
+    let buf = buf.try_into_array_buffer(&scope).unwrap();
+    assert_eq!(buf.byte_length(&scope).unwrap(), 2);
+    assert_eq!(buf.to_bytes(&scope).unwrap(), vec![9, 8]);
+}
+
+#[test]
+fn detects_and_decodes_other_kinds() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let f32s = scope.value(eval_raw(&ctx, "new Float32Array([0.5, -2])"));
+    assert_eq!(f32s.typed_array_kind(&scope), Some(TypedArrayKind::Float32));
+    let f32s = f32s.try_into_typed_array(&scope).unwrap();
+    assert_eq!(f32s.to_f64_vec(&scope).unwrap(), vec![0.5, -2.0]);
+    assert_eq!(f32s.byte_length(&scope).unwrap(), 8);
+    assert_eq!(
+        f32s.to_bytes(&scope).unwrap(),
+        [0.5f32.to_ne_bytes(), (-2.0f32).to_ne_bytes()].concat()
+    );
+
+    let plain = scope.value(eval_raw(&ctx, "[1, 2]"));
+    assert_eq!(plain.typed_array_kind(&scope), None);
+    assert!(plain.try_into_typed_array(&scope).is_err());
+}
+
+#[test]
+fn write_bytes_fills_storage_in_place() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let u8s = scope.value(eval_raw(&ctx, "globalThis.u8s = new Uint8Array(3)"));
+    let u8s = u8s.try_into_typed_array(&scope).unwrap();
+    u8s.write_bytes(&scope, &[7, 8]).unwrap();
+    assert_eq!(u8s.to_bytes(&scope).unwrap(), vec![7, 8, 0]);
+    assert!(u8s.write_bytes(&scope, &[0; 4]).is_err());
+
+    // Element kinds other than u8 are written through their buffer, honoring byteOffset.
+    let u16s = scope.value(eval_raw(&ctx, "new Uint16Array(new ArrayBuffer(8), 2, 2)"));
+    let u16s = u16s.try_into_typed_array(&scope).unwrap();
+    let bytes = [1u16.to_ne_bytes(), 513u16.to_ne_bytes()].concat();
+    u16s.write_bytes(&scope, &bytes).unwrap();
+    assert_eq!(u16s.to_f64_vec(&scope).unwrap(), vec![1.0, 513.0]);
+
+    let same = scope.value(eval_raw(&ctx, "u8s[1]"));
+    assert_eq!(same.as_i32(&scope), Some(8));
+}
+
+#[test]
+fn views_read_and_write_the_shared_storage() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    // A view with a byte offset reads exactly its own window of the buffer.
+    let view = scope.value(eval_raw(
+        &ctx,
+        "globalThis.buf = new Uint8Array([1, 2, 3, 4, 5, 6]).buffer; new Uint8Array(buf, 2, 3)",
+    ));
+    assert_eq!(view.typed_array_kind(&scope), Some(TypedArrayKind::Uint8));
+    let view = view.try_into_typed_array(&scope).unwrap();
+    assert_eq!(view.len(&scope).unwrap(), 3);
+    assert_eq!(
+        view.with_bytes(&scope, |b| b.to_vec()).unwrap(),
+        vec![3, 4, 5]
+    );
+
+    // Writes land in the buffer every other view sees.
+    view.write_bytes(&scope, &[9]).unwrap();
+    let seen = scope.value(eval_raw(&ctx, "new Uint8Array(buf)[2]"));
+    assert_eq!(seen.as_i32(&scope), Some(9));
+
+    let clamped = scope.value(eval_raw(&ctx, "new Uint8ClampedArray(0)"));
+    assert_eq!(
+        clamped.typed_array_kind(&scope),
+        Some(TypedArrayKind::Uint8Clamped)
+    );
+    let clamped = clamped.try_into_typed_array(&scope).unwrap();
+    assert!(clamped.is_empty(&scope).unwrap());
+    assert!(clamped.to_bytes(&scope).unwrap().is_empty());
+
+    let i16s = scope.value(eval_raw(&ctx, "new Int16Array([-1, 300])"));
+    let i16s = i16s.try_into_typed_array(&scope).unwrap();
+    assert_eq!(i16s.to_f64_vec(&scope).unwrap(), vec![-1.0, 300.0]);
+
+    let empty = scope.value(eval_raw(&ctx, "new ArrayBuffer(0)"));
+    let empty = empty.try_into_array_buffer(&scope).unwrap();
+    assert_eq!(empty.byte_length(&scope).unwrap(), 0);
+    assert!(scope
+        .value(eval_raw(&ctx, "({})"))
+        .typed_array_kind(&scope)
+        .is_none());
+}
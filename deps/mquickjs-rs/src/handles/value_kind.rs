@@ -0,0 +1,198 @@
+use crate::handles::local::{Array, Function, Local, Object, Value};
+use crate::handles::scope::Scope;
+use crate::mquickjs_ffi;
+
+/// Coarse runtime type of a `JSValue`, computed from the tag bits plus the object class id.
+///
+/// Order of precedence: special tags first, then functions (including short functions,
+/// which are tagged specials), strings, numbers, and finally object class ids.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ValueKind {
+    Undefined,
+    Null,
+    Bool,
+    /// Immediate 31-bit integer.
+    Int,
+    /// Any other number (short or boxed float64).
+    Float,
+    String,
+    Array,
+    Function,
+    /// Plain or builtin object that has no more specific kind.
+    Object,
+    /// Instance of a RIDL-generated class; carries the absolute QuickJS class id
+    /// (compare against `mquickjs_rs::ridl_js_class_id::JS_CLASS_*`).
+    RidlClass(i32),
+    Error,
+    /// `JS_EXCEPTION` sentinel: an exception is pending on the context.
+    Exception,
+}
+
+impl ValueKind {
+    /// Classify a raw value.
+    ///
+    /// # Safety
+    /// `ctx` must be a live JSContext and `v` a value that belongs to it.
+    pub unsafe fn of_raw(ctx: *mut mquickjs_ffi::JSContext, v: mquickjs_ffi::JSValue) -> Self {
+        let tag = mquickjs_ffi::js_value_special_tag(v);
+        if tag == mquickjs_ffi::JS_TAG_EXCEPTION as u32 {
+            return ValueKind::Exception;
+        }
+        if tag == mquickjs_ffi::JS_TAG_UNDEFINED as u32
+            || tag == mquickjs_ffi::JS_TAG_UNINITIALIZED as u32
+        {
+            return ValueKind::Undefined;
+        }
+        if tag == mquickjs_ffi::JS_TAG_NULL as u32 {
+            return ValueKind::Null;
+        }
+        if tag == mquickjs_ffi::JS_TAG_BOOL as u32 {
+            return ValueKind::Bool;
+        }
+
+        if unsafe { mquickjs_ffi::JS_IsFunction(ctx, v) } != 0 {
+            return ValueKind::Function;
+        }
+        if unsafe { mquickjs_ffi::JS_IsString(ctx, v) } != 0 {
+            return ValueKind::String;
+        }
+        if unsafe { mquickjs_ffi::JS_IsNumber(ctx, v) } != 0 {
+            return if (v as u32) & 1 == mquickjs_ffi::JS_TAG_INT as u32 {
+                ValueKind::Int
+            } else {
+                ValueKind::Float
+            };
+        }
+
+        let class_id = unsafe { mquickjs_ffi::JS_GetClassID(ctx, v) };
+        if class_id == mquickjs_ffi::JSObjectClassEnum_JS_CLASS_ARRAY as i32 {
+            return ValueKind::Array;
+        }
+        if class_id >= mquickjs_ffi::JSObjectClassEnum_JS_CLASS_USER as i32 {
+            return ValueKind::RidlClass(class_id);
+        }
+        if unsafe { mquickjs_ffi::JS_IsError(ctx, v) } != 0 {
+            return ValueKind::Error;
+        }
+        ValueKind::Object
+    }
+
+    /// Short lowercase name, for error messages (`"expected string, got null"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueKind::Undefined => "undefined",
+            ValueKind::Null => "null",
+            ValueKind::Bool => "bool",
+            ValueKind::Int => "int",
+            ValueKind::Float => "float",
+            ValueKind::String => "string",
+            ValueKind::Array => "array",
+            ValueKind::Function => "function",
+            ValueKind::Object => "object",
+            ValueKind::RidlClass(_) => "class instance",
+            ValueKind::Error => "error",
+            ValueKind::Exception => "exception",
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, ValueKind::Int | ValueKind::Float)
+    }
+
+    /// `null` or `undefined`.
+    pub fn is_nullish(&self) -> bool {
+        matches!(self, ValueKind::Null | ValueKind::Undefined)
+    }
+
+    /// Every kind backed by a JS object (arrays, functions, errors, class instances).
+    pub fn is_object(&self) -> bool {
+        matches!(
+            self,
+            ValueKind::Array
+                | ValueKind::Function
+                | ValueKind::Object
+                | ValueKind::RidlClass(_)
+                | ValueKind::Error
+        )
+    }
+}
+
+impl<'ctx> Local<'ctx, Value> {
+    pub fn kind(&self, scope: &Scope<'ctx>) -> ValueKind {
+        unsafe { ValueKind::of_raw(scope.ctx(), self.as_raw()) }
+    }
+
+    pub fn is_undefined(&self) -> bool {
+        mquickjs_ffi::js_value_special_tag(self.as_raw()) == mquickjs_ffi::JS_TAG_UNDEFINED as u32
+    }
+
+    pub fn is_null(&self) -> bool {
+        mquickjs_ffi::js_value_special_tag(self.as_raw()) == mquickjs_ffi::JS_TAG_NULL as u32
+    }
+
+    /// Exact boolean (no truthiness coercion).
+    pub fn as_bool(&self) -> Option<bool> {
+        if mquickjs_ffi::js_is_bool(self.as_raw()) {
+            Some(self.as_raw() == mquickjs_ffi::JS_TRUE)
+        } else {
+            None
+        }
+    }
+
+    /// Immediate integers only; floats (even integral ones) return `None`.
+    pub fn as_i32(&self, scope: &Scope<'ctx>) -> Option<i32> {
+        if self.kind(scope) != ValueKind::Int {
+            return None;
+        }
+        let mut out = 0i32;
+        if unsafe { mquickjs_ffi::JS_ToInt32(scope.ctx(), &mut out, self.as_raw()) } != 0 {
+            return None;
+        }
+        Some(out)
+    }
+
+    /// Any number, widened to `f64`.
+    pub fn as_f64(&self, scope: &Scope<'ctx>) -> Option<f64> {
+        if !self.kind(scope).is_number() {
+            return None;
+        }
+        let mut out = 0.0f64;
+        if unsafe { mquickjs_ffi::JS_ToNumber(scope.ctx(), &mut out, self.as_raw()) } != 0 {
+            return None;
+        }
+        Some(out)
+    }
+
+    /// Strings only; other values are not stringified.
+    pub fn as_string(&self, scope: &Scope<'ctx>) -> Option<String> {
+        if self.kind(scope) != ValueKind::String {
+            return None;
+        }
+        unsafe { crate::handles::string::to_rust_string_raw(scope.ctx(), self.as_raw()) }
+    }
+
+    pub fn as_array(&self, scope: &Scope<'ctx>) -> Option<Local<'ctx, Array>> {
+        (self.kind(scope) == ValueKind::Array)
+            .then(|| Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id()))
+    }
+
+    pub fn as_function(&self, scope: &Scope<'ctx>) -> Option<Local<'ctx, Function>> {
+        (self.kind(scope) == ValueKind::Function)
+            .then(|| Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id()))
+    }
+
+    /// Any object-backed kind (see `ValueKind::is_object`).
+    pub fn as_object(&self, scope: &Scope<'ctx>) -> Option<Local<'ctx, Object>> {
+        self.kind(scope)
+            .is_object()
+            .then(|| Local::from_raw_for_same_ctx(self.as_raw()).with_ctx_id(self.ctx_id()))
+    }
+
+    /// Class id of a RIDL class instance, `None` for everything else.
+    pub fn ridl_class_id(&self, scope: &Scope<'ctx>) -> Option<i32> {
+        match self.kind(scope) {
+            ValueKind::RidlClass(id) => Some(id),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::ffi::CString;
+
+use crate::handles::value_kind::ValueKind;
+use crate::mquickjs_ffi;
+use crate::Context;
+use crate::Env;
+
+fn eval_raw(ctx: &Context, code: &str) -> mquickjs_ffi::JSValue {
+    let c_code = CString::new(code).unwrap();
+    let filename = CString::new("eval.js").unwrap();
+    unsafe {
+        mquickjs_ffi::JS_Eval(
+            ctx.ctx,
+            c_code.as_ptr(),
+            code.len(),
+            filename.as_ptr(),
+            mquickjs_ffi::JS_EVAL_RETVAL as i32,
+        )
+    }
+}
+
+#[test]
+fn kind_covers_numbers_and_objects() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let cases = [
+        ("1.5", ValueKind::Float),
+        ("-7", ValueKind::Int),
+        ("[1, 2]", ValueKind::Array),
+        ("({ a: 1 })", ValueKind::Object),
+        ("(function () {})", ValueKind::Function),
+        ("Math.max", ValueKind::Function),
+        ("new TypeError('x')", ValueKind::Error),
+        ("false", ValueKind::Bool),
+        ("''", ValueKind::String),
+    ];
+    for (code, expected) in cases {
+        let v = scope.value(eval_raw(&ctx, code));
+        assert_eq!(v.kind(&scope), expected, "kind of {code}");
+    }
+}
+
+#[test]
+fn as_accessors_do_not_coerce() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+
+    let mut env = Env::new(&scope);
+
+    let s = env.str("12").unwrap();
+    let s = scope.value(s.as_raw());
+    assert_eq!(s.as_f64(&scope), None);
+    assert_eq!(s.as_bool(), None);
+    assert!(s.as_object(&scope).is_none());
+
+    let f = scope.value(eval_raw(&ctx, "2.25"));
+    assert_eq!(f.as_f64(&scope), Some(2.25));
+    assert_eq!(f.as_i32(&scope), None);
+    assert_eq!(f.as_string(&scope), None);
+
+    let arr = scope.value(eval_raw(&ctx, "[3]"));
+    assert!(arr.as_array(&scope).is_some());
+    assert!(arr.as_object(&scope).is_some());
+    assert!(arr.as_function(&scope).is_none());
+    assert_eq!(arr.ridl_class_id(&scope), None);
+
+    let null = scope.value(mquickjs_ffi::JS_NULL);
+    assert!(null.kind(&scope).is_nullish());
+    assert!(!null.kind(&scope).is_object());
+}
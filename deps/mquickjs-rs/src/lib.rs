@@ -43,14 +43,27 @@ pub mod mquickjs_ffi {
 }
 
 pub use context::Context;
+pub use context_builder::ContextBuilder;
 pub use env::Env;
 pub use handles::any::Any;
+pub use handles::convert::{FromJs, IntoJs, JsArgs};
 pub use handles::global::Global;
 pub use handles::handle::Handle;
 pub use handles::handle_scope::{EscapableHandleScope, HandleScope};
 pub use handles::local::{Local, Value};
+pub use handles::object::PropertyAttributes;
 pub use handles::return_safe::{ReturnAny, ReturnSafe};
+pub use handles::string::JsStringBuf;
+pub use handles::typed_array::TypedArrayKind;
+pub use handles::value_kind::ValueKind;
+pub use module_loader::{
+    BundleModuleLoader, FsModuleLoader, ModuleLoader, ModuleSource, NativeModule, RequireCheck,
+};
 pub use handles::scope::Scope;
+#[cfg(feature = "ridl-extensions")]
+pub use permissions::Permissions;
+#[cfg(feature = "ridl-schema")]
+pub use schema::{Schema, ValidationError};
 
 pub mod ridl_js_class_id {
     include!(concat!(env!("OUT_DIR"), "/ridl_js_class_id.rs"));
@@ -58,14 +71,24 @@ pub mod ridl_js_class_id {
 
 pub mod context;
 
+pub mod context_builder;
+
 pub mod env;
 
 pub mod handles;
 
+pub mod module_loader;
+
 pub mod ridl_include;
 
 // Note: ridl_modules are generated/aggregated by the app crate build and included there.
 
+#[cfg(feature = "ridl-extensions")]
+pub mod permissions;
+
+#[cfg(feature = "ridl-schema")]
+pub mod schema;
+
 #[cfg(feature = "ridl-extensions")]
 pub mod ridl_runtime;
 
@@ -321,6 +344,8 @@ mod tests {
         let value = scope.value(str_val);
         // QuickJS has both string and string objects; for this test, rely on JS_ToCString.
         assert!(context.get_string(value).is_ok());
+        assert_eq!(value.kind(&scope), ValueKind::String);
+        assert_eq!(value.as_string(&scope).as_deref(), Some("test string"));
 
         // Test number
         let c_code = CString::new("42").unwrap();
@@ -336,6 +361,8 @@ mod tests {
         };
         let value = scope.value(num_val);
         assert!(context.get_number(value).is_ok());
+        assert_eq!(value.kind(&scope), ValueKind::Int);
+        assert_eq!(value.as_i32(&scope), Some(42));
 
         // Test boolean
         let c_code = CString::new("true").unwrap();
@@ -351,6 +378,8 @@ mod tests {
         };
         let value = scope.value(bool_val);
         assert!(context.get_boolean(value).is_ok());
+        assert_eq!(value.kind(&scope), ValueKind::Bool);
+        assert_eq!(value.as_bool(), Some(true));
 
         // Test null
         let c_code = CString::new("null").unwrap();
@@ -364,8 +393,9 @@ mod tests {
                 mquickjs_ffi::JS_EVAL_RETVAL as i32,
             )
         };
-        let _value = scope.value(null_val);
-        // no typed predicate helpers; keep this as a smoke check that eval produced a non-exception.
+        let value = scope.value(null_val);
+        assert_eq!(value.kind(&scope), ValueKind::Null);
+        assert!(value.is_null());
 
         // Test undefined
         let c_code = CString::new("undefined").unwrap();
@@ -379,7 +409,20 @@ mod tests {
                 mquickjs_ffi::JS_EVAL_RETVAL as i32,
             )
         };
-        let _value = scope.value(undef_val);
-        // no typed predicate helpers; keep this as a smoke check that eval produced a non-exception.
+        let value = scope.value(undef_val);
+        assert_eq!(value.kind(&scope), ValueKind::Undefined);
+        assert!(value.is_undefined());
     }
 }
+
+#[cfg(test)]
+mod context_builder_tests;
+
+#[cfg(test)]
+mod module_loader_tests;
+
+#[cfg(all(test, feature = "ridl-extensions"))]
+mod permissions_tests;
+
+#[cfg(all(test, feature = "ridl-schema"))]
+mod schema_tests;
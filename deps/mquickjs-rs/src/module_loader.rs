@@ -0,0 +1,651 @@
+//! Script modules for `require()`: plain JS files with CommonJS `module.exports` semantics.
+//!
+//! `require(spec)` (require.c) first looks `spec` up in the RIDL native module table. On a miss
+//! it calls back into Rust, where the context's `ModuleLoader` resolves `spec` to a module id and
+//! supplies the source. The source runs once per context as the body of
+//! `function (exports, require, module, __filename, __dirname)`; later requires of the same id
+//! return the cached `module.exports`. A module is cached before its body runs, so a require
+//! cycle sees the partially filled `exports` of the module still loading (as in Node.js).
+//!
+//! Native modules can be gated per context with `Permissions` and require checks
+//! (`Context::add_require_check`): a denial makes `require` throw instead of handing out the
+//! module.
+//!
+//! JS reaches Rust only through ROM C functions, so `require` inside a module needs the
+//! `ridl-extensions` build (where require.c is linked). `Env::require` loads a module from Rust
+//! in either build.
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use crate::context::{Context, ContextInner, ContextToken};
+use crate::env::Env;
+use crate::handles::handle::Handle;
+use crate::handles::js_call::{
+    call_pending, compile_helper, get_global, is_exception, take_exception_message, TempRoots,
+};
+use crate::handles::local::Value;
+use crate::handles::scope::Scope;
+use crate::handles::string::new_string_raw;
+use crate::mquickjs_ffi;
+
+/// Source of one script module, as returned by `ModuleLoader::load`.
+#[derive(Clone, Debug)]
+pub enum ModuleSource {
+    /// The module body as JS source (wrapped with `wrap_source` before evaluation).
+    Js(String),
+    /// mquickjs bytecode of `wrap_source(body)`, compiled with `JS_EVAL_RETVAL` by the same
+    /// engine build. It is relocated and run in place, so the buffer is kept until the
+    /// `Context` is dropped.
+    Bytecode(Vec<u8>),
+}
+
+/// Resolves `require` specs to module ids and supplies their source.
+pub trait ModuleLoader {
+    /// Canonical id for `spec`, or `None` if this loader has no such module.
+    ///
+    /// `referrer` is the id of the requiring module (`None` for top-level code and
+    /// `Env::require`). The id is the cache key, the module's `__filename` and the base for
+    /// its relative specs.
+    fn resolve(&self, spec: &str, referrer: Option<&str>) -> Option<String>;
+
+    /// Source for an id returned by `resolve`.
+    fn load(&self, id: &str) -> Result<ModuleSource, String>;
+}
+
+/// The function expression a module body is evaluated as.
+pub fn wrap_source(body: &str) -> String {
+    format!("(function (exports, require, module, __filename, __dirname) {{{body}\n}})")
+}
+
+fn is_relative(spec: &str) -> bool {
+    spec.starts_with("./") || spec.starts_with("../")
+}
+
+/// `/`-separated id for `spec`: relative specs are joined to the referrer's directory and
+/// `.`/`..` segments are folded. `None` if the result would climb above the root.
+pub fn resolve_id(spec: &str, referrer: Option<&str>) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    if let (true, Some(referrer)) = (is_relative(spec), referrer) {
+        parts.extend(referrer.split('/').filter(|s| !s.is_empty() && *s != "."));
+        // Drop the file name.
+        parts.pop();
+    }
+    for seg in spec.split('/') {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            s => parts.push(s),
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+/// CommonJS lookup order without `package.json`: `id`, `id.js`, `id/index.js`.
+fn candidates(id: &str) -> [String; 3] {
+    [id.to_string(), format!("{id}.js"), format!("{id}/index.js")]
+}
+
+/// In-memory modules keyed by `/`-separated path, e.g. filled from `include_str!` or from the
+/// files of an `include_dir!` bundle.
+#[derive(Clone, Debug, Default)]
+pub struct BundleModuleLoader {
+    modules: HashMap<String, ModuleSource>,
+}
+
+impl BundleModuleLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a JS module; `path` is normalized like a bare spec (`lib/foo.js`).
+    pub fn file(mut self, path: &str, source: impl Into<String>) -> Self {
+        self.insert(path, ModuleSource::Js(source.into()));
+        self
+    }
+
+    /// Add a precompiled module (see `ModuleSource::Bytecode`).
+    pub fn bytecode(mut self, path: &str, bytes: impl Into<Vec<u8>>) -> Self {
+        self.insert(path, ModuleSource::Bytecode(bytes.into()));
+        self
+    }
+
+    pub fn insert(&mut self, path: &str, source: ModuleSource) {
+        let id = resolve_id(path, None).unwrap_or_else(|| path.to_string());
+        self.modules.insert(id, source);
+    }
+}
+
+impl ModuleLoader for BundleModuleLoader {
+    fn resolve(&self, spec: &str, referrer: Option<&str>) -> Option<String> {
+        let id = resolve_id(spec, referrer)?;
+        candidates(&id)
+            .into_iter()
+            .find(|c| self.modules.contains_key(c))
+    }
+
+    fn load(&self, id: &str) -> Result<ModuleSource, String> {
+        self.modules
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("{id}: no such module"))
+    }
+}
+
+/// Modules on disk under one or more root directories.
+///
+/// Bare specs (`lib/foo`) are looked up in each root in order; relative specs resolve next to
+/// the requiring file, within its root. Absolute specs and specs that climb out of the root are
+/// not found (a lexical check: symlinks inside a root are followed). Ids are file paths, i.e.
+/// the root joined with the module path, so pass absolute roots for stable ids.
+#[derive(Clone, Debug)]
+pub struct FsModuleLoader {
+    roots: Vec<PathBuf>,
+}
+
+impl FsModuleLoader {
+    pub fn new<P: Into<PathBuf>>(roots: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            roots: roots.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+fn slash_path(p: &Path) -> Option<String> {
+    let parts = p
+        .components()
+        .map(|c| match c {
+            Component::Normal(s) => s.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+fn find_in(root: &Path, id: &str) -> Option<String> {
+    candidates(id)
+        .into_iter()
+        .map(|c| root.join(c))
+        .find(|p| p.is_file())
+        .and_then(|p| p.to_str().map(str::to_string))
+}
+
+impl ModuleLoader for FsModuleLoader {
+    fn resolve(&self, spec: &str, referrer: Option<&str>) -> Option<String> {
+        if Path::new(spec).is_absolute() {
+            return None;
+        }
+        if let (true, Some(referrer)) = (is_relative(spec), referrer) {
+            let referrer = Path::new(referrer);
+            let root = self.roots.iter().find(|r| referrer.starts_with(r))?;
+            let rel = slash_path(referrer.strip_prefix(root).ok()?)?;
+            return find_in(root, &resolve_id(spec, Some(&rel))?);
+        }
+        let id = resolve_id(spec, None)?;
+        self.roots.iter().find_map(|root| find_in(root, &id))
+    }
+
+    fn load(&self, id: &str) -> Result<ModuleSource, String> {
+        std::fs::read_to_string(id)
+            .map(ModuleSource::Js)
+            .map_err(|e| format!("{id}: {e}"))
+    }
+}
+
+// ---- native module checks ----
+
+/// A RIDL native module that `require` is about to instantiate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NativeModule<'a> {
+    /// Module name without version, e.g. `std.fs`.
+    pub base: &'a str,
+    /// Name with version as declared, e.g. `std.fs@1.0`.
+    pub full_name: &'a str,
+}
+
+/// Per-context capability check for native modules; `Err` denies the module with that reason.
+pub type RequireCheck = Rc<dyn Fn(&ContextToken, &NativeModule<'_>) -> Result<(), String>>;
+
+// ---- per-context state ----
+
+/// Loader and module cache of one context (`ContextInner::modules`).
+#[derive(Default)]
+pub(crate) struct ModuleState {
+    loader: Option<Rc<dyn ModuleLoader>>,
+    /// JS array of `module` objects, rooted with `JS_AddGCRef` once the first module loads.
+    cache: Option<Box<mquickjs_ffi::JSGCRef>>,
+    /// Module id -> index in `cache`.
+    slots: HashMap<String, u32>,
+    next_slot: u32,
+    /// Buffers of loaded bytecode modules; the engine runs them in place.
+    bytecode: Vec<Box<[u8]>>,
+    /// Run in order before a native module is instantiated; all must pass.
+    checks: Vec<RequireCheck>,
+}
+
+impl ModuleState {
+    /// Unlink the cache root; `Context` drop calls this before `JS_FreeContext`.
+    pub(crate) fn release(&mut self, ctx: *mut mquickjs_ffi::JSContext) {
+        if let Some(mut gc_ref) = self.cache.take() {
+            unsafe { mquickjs_ffi::JS_DeleteGCRef(ctx, &mut *gc_ref) };
+        }
+        self.slots.clear();
+        self.next_slot = 0;
+    }
+}
+
+impl Context {
+    /// Resolve non-native `require` specs with `loader`; replaces the previous loader and
+    /// clears the module cache.
+    pub fn set_module_loader(&self, loader: impl ModuleLoader + 'static) {
+        self.install_module_loader(Rc::new(loader));
+    }
+
+    pub(crate) fn install_module_loader(&self, loader: Rc<dyn ModuleLoader>) {
+        // Keep the hook referenced so the linker cannot drop it (require.c declares it weak).
+        #[cfg(feature = "ridl-extensions")]
+        let _ = std::hint::black_box(
+            mquickjs_rs_require_hook
+                as unsafe extern "C" fn(
+                    *mut mquickjs_ffi::JSContext,
+                    *const std::os::raw::c_char,
+                    mquickjs_ffi::JSValue,
+                    *mut mquickjs_ffi::JSValue,
+                ) -> std::os::raw::c_int,
+        );
+
+        let mut state = self.inner.modules.borrow_mut();
+        state.release(self.ctx);
+        state.loader = Some(loader);
+    }
+
+    /// Gate RIDL native modules: `check` runs each time `require` resolves one, and an `Err`
+    /// makes that `require` throw a TypeError. Checks accumulate; every one must pass.
+    ///
+    /// Script modules from the `ModuleLoader` are not checked (the loader decides what exists).
+    pub fn add_require_check(
+        &self,
+        check: impl Fn(&ContextToken, &NativeModule<'_>) -> Result<(), String> + 'static,
+    ) {
+        self.install_require_check(Rc::new(check));
+    }
+
+    pub(crate) fn install_require_check(&self, check: RequireCheck) {
+        // Same as the loader hook: require.c only references the check weakly.
+        #[cfg(feature = "ridl-extensions")]
+        let _ = std::hint::black_box(
+            mquickjs_rs_require_check
+                as unsafe extern "C" fn(
+                    *mut mquickjs_ffi::JSContext,
+                    *const std::os::raw::c_char,
+                    *const std::os::raw::c_char,
+                ) -> std::os::raw::c_int,
+        );
+
+        self.inner.modules.borrow_mut().checks.push(check);
+    }
+}
+
+impl<'ctx> Env<'ctx> {
+    /// Load a script module through the context's `ModuleLoader`; returns its `module.exports`.
+    ///
+    /// Only loader modules are reachable here; RIDL native modules go through JS `require`.
+    pub fn require<'hs>(&'hs mut self, spec: &str) -> Result<Handle<'hs, 'ctx, Value>, String> {
+        let scope = self.scope();
+        match require_script(scope, spec, None) {
+            Ok(Some(exports)) => Ok(self.handle(scope.value(exports))),
+            Ok(None) => Err(format!("require {spec} failed: module not found.")),
+            Err(RequireError::Thrown) => Err(take_exception_message(
+                scope.ctx_raw(),
+                &format!("require({spec})"),
+            )),
+            Err(RequireError::Message(msg)) => Err(msg),
+        }
+    }
+}
+
+// ---- loading ----
+
+pub(crate) enum RequireError {
+    /// A JS exception is pending (e.g. thrown by the module body); it is left for the caller.
+    Thrown,
+    Message(String),
+}
+
+fn check(v: mquickjs_ffi::JSValue) -> Result<mquickjs_ffi::JSValue, RequireError> {
+    if is_exception(v) {
+        Err(RequireError::Thrown)
+    } else {
+        Ok(v)
+    }
+}
+
+fn set_prop(
+    ctx: *mut mquickjs_ffi::JSContext,
+    obj: mquickjs_ffi::JSValue,
+    name: &'static [u8],
+    v: mquickjs_ffi::JSValue,
+) -> Result<(), RequireError> {
+    check(unsafe { mquickjs_ffi::JS_SetPropertyStr(ctx, obj, name.as_ptr() as *const _, v) })
+        .map(|_| ())
+}
+
+fn get_exports(
+    ctx: *mut mquickjs_ffi::JSContext,
+    module: mquickjs_ffi::JSValue,
+) -> Result<mquickjs_ffi::JSValue, RequireError> {
+    check(unsafe {
+        mquickjs_ffi::JS_GetPropertyStr(ctx, module, b"exports\0".as_ptr() as *const _)
+    })
+}
+
+/// Create (and root) the cache array on first use.
+fn ensure_cache(
+    ctx: *mut mquickjs_ffi::JSContext,
+    inner: &ContextInner,
+) -> Result<(), RequireError> {
+    if inner.modules.borrow().cache.is_some() {
+        return Ok(());
+    }
+    let arr = check(unsafe { mquickjs_ffi::JS_NewArray(ctx, 0) })?;
+    let mut gc_ref = Box::new(mquickjs_ffi::JSGCRef {
+        val: mquickjs_ffi::JS_UNDEFINED,
+        prev: std::ptr::null_mut(),
+    });
+    unsafe {
+        let slot = mquickjs_ffi::JS_AddGCRef(ctx, &mut *gc_ref);
+        *slot = arr;
+    }
+    inner.modules.borrow_mut().cache = Some(gc_ref);
+    Ok(())
+}
+
+fn cache_get(
+    ctx: *mut mquickjs_ffi::JSContext,
+    inner: &ContextInner,
+    id: &str,
+) -> Result<Option<mquickjs_ffi::JSValue>, RequireError> {
+    let state = inner.modules.borrow();
+    let (Some(cache), Some(&slot)) = (state.cache.as_ref(), state.slots.get(id)) else {
+        return Ok(None);
+    };
+    check(unsafe { mquickjs_ffi::JS_GetPropertyUint32(ctx, cache.val, slot) }).map(Some)
+}
+
+/// Store `module` under `id`; the cache array must exist (`ensure_cache`).
+fn cache_put(
+    ctx: *mut mquickjs_ffi::JSContext,
+    inner: &ContextInner,
+    id: &str,
+    module: mquickjs_ffi::JSValue,
+) -> Result<(), RequireError> {
+    let mut state = inner.modules.borrow_mut();
+    let slot = state.next_slot;
+    let Some(cache) = state.cache.as_ref() else {
+        return Err(RequireError::Message("module cache missing".to_string()));
+    };
+    check(unsafe { mquickjs_ffi::JS_SetPropertyUint32(ctx, cache.val, slot, module) })?;
+    state.slots.insert(id.to_string(), slot);
+    state.next_slot += 1;
+    Ok(())
+}
+
+/// Forget a module whose body threw, so the next `require` runs it again.
+fn cache_remove(ctx: *mut mquickjs_ffi::JSContext, inner: &ContextInner, id: &str) {
+    let mut state = inner.modules.borrow_mut();
+    let Some(slot) = state.slots.remove(id) else {
+        return;
+    };
+    if let Some(cache) = state.cache.as_ref() {
+        let _ = unsafe {
+            mquickjs_ffi::JS_SetPropertyUint32(ctx, cache.val, slot, mquickjs_ffi::JS_UNDEFINED)
+        };
+    }
+}
+
+/// Resolve `spec` with the context's loader and return the module's exports.
+///
+/// `Ok(None)` when there is no loader or it does not know `spec`.
+pub(crate) fn require_script(
+    scope: &Scope<'_>,
+    spec: &str,
+    referrer: Option<&str>,
+) -> Result<Option<mquickjs_ffi::JSValue>, RequireError> {
+    let ctx = scope.ctx_raw();
+    let inner = &*scope.h.inner;
+
+    // Clone the loader out: the module body may re-enter `require`.
+    let Some(loader) = inner.modules.borrow().loader.clone() else {
+        return Ok(None);
+    };
+    let Some(id) = loader.resolve(spec, referrer) else {
+        return Ok(None);
+    };
+
+    if let Some(module) = cache_get(ctx, inner, &id)? {
+        return get_exports(ctx, module).map(Some);
+    }
+
+    let source = loader
+        .load(&id)
+        .map_err(|e| RequireError::Message(format!("require {spec} failed: {e}")))?;
+    ensure_cache(ctx, inner)?;
+
+    // Everything below allocates and the GC moves objects: keep values on `roots` and read
+    // them back by index. 0: module, 1: exports, 2: id.
+    let mut roots = TempRoots::new(ctx);
+    roots.push(check(unsafe { mquickjs_ffi::JS_NewObject(ctx) })?);
+    roots.push(check(unsafe { mquickjs_ffi::JS_NewObject(ctx) })?);
+    roots.push(unsafe { new_string_raw(ctx, &id) }.map_err(RequireError::Message)?);
+    set_prop(ctx, roots.get(0), b"exports\0", roots.get(1))?;
+    set_prop(ctx, roots.get(0), b"id\0", roots.get(2))?;
+    set_prop(ctx, roots.get(0), b"loaded\0", mquickjs_ffi::JS_FALSE)?;
+
+    // Cache before running the body: a cycle back to `id` gets the partial exports.
+    cache_put(ctx, inner, &id, roots.get(0))?;
+    if let Err(e) = run_module(ctx, inner, &mut roots, &id, source) {
+        cache_remove(ctx, inner, &id);
+        return Err(e);
+    }
+    set_prop(ctx, roots.get(0), b"loaded\0", mquickjs_ffi::JS_TRUE)?;
+
+    // Re-read: the body may have replaced `module.exports`.
+    get_exports(ctx, roots.get(0)).map(Some)
+}
+
+/// `function require(s)` for one module: relative specs resolve against that module's id.
+const MODULE_REQUIRE_HELPER: &[u8] =
+    b"(function (r, f) { return function require(s) { return r(s, f); }; })";
+
+fn run_module(
+    ctx: *mut mquickjs_ffi::JSContext,
+    inner: &ContextInner,
+    roots: &mut TempRoots,
+    id: &str,
+    source: ModuleSource,
+) -> Result<(), RequireError> {
+    let func = match source {
+        ModuleSource::Js(body) => {
+            let code = CString::new(wrap_source(&body)).map_err(|_| {
+                RequireError::Message(format!("require {id} failed: source contains NUL"))
+            })?;
+            let filename = CString::new(id).map_err(|_| {
+                RequireError::Message(format!("require {id} failed: id contains NUL"))
+            })?;
+            check(unsafe {
+                mquickjs_ffi::JS_Eval(
+                    ctx,
+                    code.as_ptr(),
+                    code.as_bytes().len(),
+                    filename.as_ptr(),
+                    mquickjs_ffi::JS_EVAL_RETVAL as i32,
+                )
+            })?
+        }
+        ModuleSource::Bytecode(bytes) => load_bytecode(ctx, inner, bytes, id)?,
+    };
+    roots.push(func); // 3
+
+    let helper = compile_helper(ctx, MODULE_REQUIRE_HELPER, "module require")
+        .map_err(RequireError::Message)?;
+    roots.push(helper); // 4
+
+    // `undefined` without ridl-extensions: calling the module's `require` then throws.
+    let global_require = get_global(ctx, b"require\0").map_err(RequireError::Message)?;
+    roots.push(global_require); // 5
+    let require = call_pending(
+        ctx,
+        roots.get(4),
+        mquickjs_ffi::JS_UNDEFINED,
+        &[roots.get(5), roots.get(2)],
+    )
+    .map_err(RequireError::Message)?;
+    roots.push(check(require)?); // 6
+
+    let dirname = Path::new(id)
+        .parent()
+        .and_then(|p| p.to_str())
+        .unwrap_or("");
+    roots.push(unsafe { new_string_raw(ctx, dirname) }.map_err(RequireError::Message)?); // 7
+
+    // this = exports; (exports, require, module, __filename, __dirname)
+    let _script = inner.enter_script(id);
+    let r = call_pending(
+        ctx,
+        roots.get(3),
+        roots.get(1),
+        &[
+            roots.get(1),
+            roots.get(6),
+            roots.get(0),
+            roots.get(2),
+            roots.get(7),
+        ],
+    )
+    .map_err(RequireError::Message)?;
+    check(r).map(|_| ())
+}
+
+fn load_bytecode(
+    ctx: *mut mquickjs_ffi::JSContext,
+    inner: &ContextInner,
+    bytes: Vec<u8>,
+    id: &str,
+) -> Result<mquickjs_ffi::JSValue, RequireError> {
+    let mut buf = bytes.into_boxed_slice();
+    if unsafe { mquickjs_ffi::JS_IsBytecode(buf.as_ptr(), buf.len()) } == 0 {
+        return Err(RequireError::Message(format!(
+            "require {id} failed: not mquickjs bytecode"
+        )));
+    }
+    if unsafe { mquickjs_ffi::JS_RelocateBytecode(ctx, buf.as_mut_ptr(), buf.len() as u32) } != 0 {
+        return Err(RequireError::Message(format!(
+            "require {id} failed: cannot relocate bytecode"
+        )));
+    }
+    let main = unsafe { mquickjs_ffi::JS_LoadBytecode(ctx, buf.as_ptr()) };
+    // The loaded functions point into `buf` (the heap data does not move with the Box).
+    inner.modules.borrow_mut().bytecode.push(buf);
+    let main = check(main)?;
+    check(unsafe { mquickjs_ffi::JS_Run(ctx, main) })
+}
+
+/// Called by `require()` (require.c) when `spec` is not a RIDL native module.
+///
+/// `referrer` is the second `require` argument (the requiring module's id, passed by the
+/// per-module `require`). Returns 1 with `*out` set, 0 if no loader knows `spec`, and -1 with
+/// an exception pending.
+#[cfg(feature = "ridl-extensions")]
+#[no_mangle]
+pub unsafe extern "C" fn mquickjs_rs_require_hook(
+    ctx: *mut mquickjs_ffi::JSContext,
+    spec: *const std::os::raw::c_char,
+    referrer: mquickjs_ffi::JSValue,
+    out: *mut mquickjs_ffi::JSValue,
+) -> std::os::raw::c_int {
+    let Some(token) = (unsafe { crate::context::ContextToken::from_js_ctx(ctx) }) else {
+        return 0;
+    };
+    // Copy before anything allocates: `spec` points into a JS string.
+    let spec = unsafe { std::ffi::CStr::from_ptr(spec) }
+        .to_string_lossy()
+        .into_owned();
+    let scope = token.enter_scope();
+    let referrer = scope.value(referrer);
+    let referrer = if referrer.is_string(&scope) {
+        unsafe { crate::handles::string::to_rust_string_raw(ctx, referrer.as_raw()) }
+    } else {
+        None
+    };
+
+    match require_script(&scope, &spec, referrer.as_deref()) {
+        Ok(Some(exports)) => {
+            unsafe { *out = exports };
+            1
+        }
+        Ok(None) => 0,
+        Err(RequireError::Thrown) => -1,
+        Err(RequireError::Message(msg)) => {
+            unsafe { throw_type_error(ctx, msg) };
+            -1
+        }
+    }
+}
+
+#[cfg(feature = "ridl-extensions")]
+unsafe fn throw_type_error(ctx: *mut mquickjs_ffi::JSContext, msg: String) {
+    let msg = CString::new(msg).unwrap_or_else(|_| CString::new("require failed").unwrap());
+    unsafe {
+        mquickjs_ffi::JS_ThrowError(
+            ctx,
+            mquickjs_ffi::JSObjectClassEnum_JS_CLASS_TYPE_ERROR,
+            b"%s\0".as_ptr() as *const _,
+            msg.as_ptr(),
+        )
+    };
+}
+
+/// Capability check for native modules, called by require.c (declared weak there) once `spec`
+/// matched an entry of the RIDL require table and before the module object is created.
+///
+/// The context's `Permissions` are consulted first, then each `RequireCheck`. Returns 0 to
+/// allow and -1 with a TypeError pending when either denies the module.
+#[cfg(feature = "ridl-extensions")]
+#[no_mangle]
+pub unsafe extern "C" fn mquickjs_rs_require_check(
+    ctx: *mut mquickjs_ffi::JSContext,
+    module_base: *const std::os::raw::c_char,
+    module_full_name: *const std::os::raw::c_char,
+) -> std::os::raw::c_int {
+    let Some(token) = (unsafe { crate::context::ContextToken::from_js_ctx(ctx) }) else {
+        return 0;
+    };
+    // Both names point into the static require table.
+    let base = unsafe { std::ffi::CStr::from_ptr(module_base) }.to_string_lossy();
+    let full_name = unsafe { std::ffi::CStr::from_ptr(module_full_name) }.to_string_lossy();
+    let module = NativeModule {
+        base: &base,
+        full_name: &full_name,
+    };
+    let permissions = token.inner.permissions();
+    if let Some(Err(reason)) = permissions.map(|p| p.check_module(&module)) {
+        unsafe { throw_type_error(ctx, format!("require {full_name} failed: {reason}")) };
+        return -1;
+    }
+    // Clone the list so a check may itself add checks.
+    let checks = token.inner.modules.borrow().checks.clone();
+    for check in checks {
+        if let Err(reason) = check(&token, &module) {
+            unsafe { throw_type_error(ctx, format!("require {full_name} failed: {reason}")) };
+            return -1;
+        }
+    }
+    0
+}
@@ -0,0 +1,210 @@
+use crate::handles::local::{Function, Local, Object};
+use crate::module_loader::{resolve_id, BundleModuleLoader, FsModuleLoader, ModuleLoader};
+use crate::Context;
+use crate::Env;
+
+fn bundle() -> BundleModuleLoader {
+    BundleModuleLoader::new()
+        .file(
+            "lib/util.js",
+            r#"
+            globalThis.loads = (globalThis.loads || 0) + 1;
+            exports.add = function (a, b) { return a + b; };
+            exports.file = __filename;
+            exports.dir = __dirname;
+            "#,
+        )
+        .file("lib/answer/index.js", "module.exports = 42;")
+        .file("bad.js", "throw new Error('bad module');")
+}
+
+#[test]
+fn resolve_id_folds_segments() {
+    assert_eq!(resolve_id("lib/foo", None).as_deref(), Some("lib/foo"));
+    assert_eq!(resolve_id("./foo.js", None).as_deref(), Some("foo.js"));
+    assert_eq!(
+        resolve_id("./b.js", Some("lib/a.js")).as_deref(),
+        Some("lib/b.js")
+    );
+    assert_eq!(
+        resolve_id("../c", Some("lib/sub/a.js")).as_deref(),
+        Some("lib/c")
+    );
+    // Bare specs ignore the referrer.
+    assert_eq!(
+        resolve_id("lib/c", Some("other/a.js")).as_deref(),
+        Some("lib/c")
+    );
+    assert_eq!(resolve_id("../escape.js", Some("a.js")), None);
+}
+
+#[test]
+fn bundle_resolves_extension_and_index() {
+    let b = bundle();
+    assert_eq!(b.resolve("lib/util", None).as_deref(), Some("lib/util.js"));
+    assert_eq!(
+        b.resolve("./util", Some("lib/x.js")).as_deref(),
+        Some("lib/util.js")
+    );
+    assert_eq!(
+        b.resolve("lib/answer", None).as_deref(),
+        Some("lib/answer/index.js")
+    );
+    assert_eq!(b.resolve("lib/missing", None), None);
+}
+
+#[test]
+fn require_returns_exports_and_caches() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.set_module_loader(bundle());
+    {
+        let token = ctx.token();
+        let scope = token.enter_scope();
+        let mut env = Env::new(&scope);
+
+        let util = env.require("lib/util").unwrap().as_local();
+        let util: Local<Object> = util.try_into_object(&scope).unwrap();
+        let add: Local<Function> = util.get_path(&env, "add").unwrap();
+        let n: i32 = add.call_with(&env, (), (2, 3)).unwrap();
+        assert_eq!(n, 5);
+        let file: String = util.get_path(&env, "file").unwrap();
+        assert_eq!(file, "lib/util.js");
+        let dir: String = util.get_path(&env, "dir").unwrap();
+        assert_eq!(dir, "lib");
+
+        // Second require hits the cache: the body does not run again.
+        env.require("./lib/util.js").unwrap();
+
+        // `module.exports = ...` replaces the exports object.
+        let answer = env.require("lib/answer").unwrap().as_local();
+        assert_eq!(answer.as_i32(&scope), Some(42));
+    }
+    assert_eq!(ctx.eval("globalThis.loads").unwrap(), "1");
+}
+
+#[test]
+fn require_errors() {
+    let ctx = Context::new(1024 * 1024).unwrap();
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+
+    // No loader installed.
+    let err = env.require("lib/util").unwrap_err();
+    assert!(err.contains("module not found"));
+
+    ctx.set_module_loader(bundle());
+    let err = env.require("lib/missing").unwrap_err();
+    assert_eq!(err, "require lib/missing failed: module not found.");
+
+    // A throwing body is not cached, so the next require runs it again.
+    for _ in 0..2 {
+        let err = env.require("bad").unwrap_err();
+        assert!(err.contains("bad module"), "{err}");
+    }
+}
+
+#[test]
+fn fs_loader_stays_inside_roots() {
+    let root = std::env::temp_dir().join(format!("mquickjs-rs-modules-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("lib")).unwrap();
+    std::fs::write(root.join("lib/a.js"), "exports.b = require('./b');").unwrap();
+    std::fs::write(root.join("lib/b.js"), "module.exports = 'b';").unwrap();
+
+    let loader = FsModuleLoader::new([&root]);
+    let a = loader.resolve("lib/a", None).unwrap();
+    assert_eq!(a, root.join("lib/a.js").to_str().unwrap());
+    assert_eq!(
+        loader.resolve("./b", Some(&a)).unwrap(),
+        root.join("lib/b.js").to_str().unwrap()
+    );
+    assert_eq!(loader.resolve("../../outside", Some(&a)), None);
+    assert_eq!(
+        loader.resolve(root.join("lib/a.js").to_str().unwrap(), None),
+        None
+    );
+
+    let ctx = Context::new(1024 * 1024).unwrap();
+    ctx.set_module_loader(loader);
+    {
+        let token = ctx.token();
+        let scope = token.enter_scope();
+        let mut env = Env::new(&scope);
+        let b = env.require("lib/b").unwrap().as_local();
+        assert_eq!(b.as_string(&scope).as_deref(), Some("b"));
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// Nested and cyclic requires go through the ROM `require` (require.c).
+#[cfg(feature = "ridl-extensions")]
+#[test]
+fn nested_and_cyclic_requires() {
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.set_module_loader(
+        BundleModuleLoader::new()
+            .file(
+                "a.js",
+                "exports.name = 'a'; var b = require('./b'); exports.fromB = b.seenA;",
+            )
+            .file(
+                "b.js",
+                "var a = require('./a'); exports.seenA = a.name; exports.aDone = !!a.fromB;",
+            ),
+    );
+
+    assert_eq!(ctx.eval("require('./a.js').fromB").unwrap(), "a");
+    assert_eq!(ctx.eval("require('b').aDone").unwrap(), "false");
+    assert_eq!(ctx.eval("require('a') === require('./a')").unwrap(), "true");
+    assert!(ctx
+        .eval("require('nope')")
+        .unwrap_err()
+        .contains("require nope failed: module not found."));
+}
+
+/// `current_script` names the `eval_file` script, then the requiring module.
+#[cfg(feature = "ridl-extensions")]
+#[test]
+fn current_script_follows_eval_file_and_modules() {
+    use crate::context::ContextToken;
+    use crate::module_loader::ModuleSource;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Recording {
+        inner: BundleModuleLoader,
+        seen: Rc<RefCell<Vec<(String, Option<String>)>>>,
+    }
+
+    impl ModuleLoader for Recording {
+        fn resolve(&self, spec: &str, referrer: Option<&str>) -> Option<String> {
+            self.inner.resolve(spec, referrer)
+        }
+
+        fn load(&self, id: &str) -> Result<ModuleSource, String> {
+            let script = ContextToken::current().and_then(|t| t.current_script());
+            self.seen.borrow_mut().push((id.to_string(), script));
+            self.inner.load(id)
+        }
+    }
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut ctx = Context::new(1024 * 1024).unwrap();
+    ctx.set_module_loader(Recording {
+        inner: BundleModuleLoader::new()
+            .file("lib/a.js", "module.exports = require('./b');")
+            .file("lib/b.js", "module.exports = 'b';"),
+        seen: seen.clone(),
+    });
+
+    assert_eq!(ctx.eval_file("require('lib/a')", "main.js").unwrap(), "b");
+    assert_eq!(
+        *seen.borrow(),
+        vec![
+            ("lib/a.js".to_string(), Some("main.js".to_string())),
+            ("lib/b.js".to_string(), Some("lib/a.js".to_string())),
+        ]
+    );
+    assert_eq!(ctx.token().current_script(), None);
+}
@@ -0,0 +1,113 @@
+//! Per-context capabilities for RIDL APIs: which native modules `require` may return and
+//! which global singletons (`console`, `performance`, ...) exist.
+//!
+//! Modules are matched with `require` specs (`std.fs`, `std.fs@1.0`, `std.fs@<2`), using the
+//! same version rules as the require table lookup. Deny rules win over allow rules; anything
+//! no rule mentions gets the default (`allow_all` / `deny_all`).
+//!
+//! Set through `ContextBuilder::permissions`: singletons are materialized once, when the
+//! context is created, so visibility cannot change afterwards.
+
+use ridl_require_spec::{parse_require_spec_no_ws, RequireSpec, Version};
+
+use crate::module_loader::NativeModule;
+
+#[derive(Clone, Debug)]
+pub struct Permissions {
+    default_allow: bool,
+    allowed_modules: Vec<RequireSpec>,
+    denied_modules: Vec<RequireSpec>,
+    allowed_singletons: Vec<String>,
+    denied_singletons: Vec<String>,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+fn parse_spec(spec: &str) -> Result<RequireSpec, String> {
+    parse_require_spec_no_ws(spec).ok_or_else(|| format!("invalid module spec: {spec:?}"))
+}
+
+impl Permissions {
+    /// Everything linked into the app is available unless denied (the behavior without
+    /// permissions).
+    pub fn allow_all() -> Self {
+        Self {
+            default_allow: true,
+            allowed_modules: Vec::new(),
+            denied_modules: Vec::new(),
+            allowed_singletons: Vec::new(),
+            denied_singletons: Vec::new(),
+        }
+    }
+
+    /// Nothing is available unless allowed; the starting point for untrusted scripts.
+    pub fn deny_all() -> Self {
+        Self {
+            default_allow: false,
+            ..Self::allow_all()
+        }
+    }
+
+    /// Allow the native modules matching `spec`.
+    pub fn allow_module(mut self, spec: &str) -> Result<Self, String> {
+        self.allowed_modules.push(parse_spec(spec)?);
+        Ok(self)
+    }
+
+    /// Deny the native modules matching `spec`, even if an allow rule matches too.
+    pub fn deny_module(mut self, spec: &str) -> Result<Self, String> {
+        self.denied_modules.push(parse_spec(spec)?);
+        Ok(self)
+    }
+
+    /// Materialize the global singleton `name` (its JS name, e.g. `console`).
+    pub fn allow_singleton(mut self, name: &str) -> Self {
+        self.allowed_singletons.push(name.to_string());
+        self
+    }
+
+    /// Do not materialize the global singleton `name`; scripts see `undefined`.
+    pub fn deny_singleton(mut self, name: &str) -> Self {
+        self.denied_singletons.push(name.to_string());
+        self
+    }
+
+    /// Whether `require` may return module `base@version`.
+    pub fn module_allowed(&self, base: &str, version: Version) -> bool {
+        if self.denied_modules.iter().any(|s| s.matches(base, version)) {
+            return false;
+        }
+        self.default_allow
+            || self
+                .allowed_modules
+                .iter()
+                .any(|s| s.matches(base, version))
+    }
+
+    pub fn singleton_visible(&self, name: &str) -> bool {
+        if self.denied_singletons.iter().any(|s| s == name) {
+            return false;
+        }
+        self.default_allow || self.allowed_singletons.iter().any(|s| s == name)
+    }
+
+    /// Require-time check for `module`; the error is the reason `require` reports.
+    pub fn check_module(&self, module: &NativeModule<'_>) -> Result<(), String> {
+        let version = module
+            .full_name
+            .rsplit_once('@')
+            .and_then(|(_, v)| Version::parse_no_ws(v));
+        let Some(version) = version else {
+            return Err("module has no valid version".to_string());
+        };
+        if self.module_allowed(module.base, version) {
+            Ok(())
+        } else {
+            Err("not permitted in this context".to_string())
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use ridl_require_spec::Version;
+
+use crate::module_loader::NativeModule;
+use crate::{Context, Permissions};
+
+fn v(s: &str) -> Version {
+    Version::parse_no_ws(s).unwrap()
+}
+
+#[test]
+fn deny_rules_win_over_allow_rules_and_the_default() {
+    let p = Permissions::allow_all().deny_module("std.fs@<2").unwrap();
+    assert!(!p.module_allowed("std.fs", v("1.0")));
+    assert!(p.module_allowed("std.fs", v("2.0")));
+    assert!(p.module_allowed("test_module_basic", v("1.0")));
+
+    let p = Permissions::deny_all()
+        .allow_module("std.fs")
+        .unwrap()
+        .deny_module("std.fs@1.0")
+        .unwrap();
+    assert!(!p.module_allowed("std.fs", v("1.0")));
+    assert!(p.module_allowed("std.fs", v("1.1")));
+    assert!(!p.module_allowed("test_module_basic", v("1.0")));
+
+    assert!(Permissions::allow_all().allow_module("std fs").is_err());
+    assert!(Permissions::allow_all().deny_module("std.fs@x").is_err());
+}
+
+#[test]
+fn singletons_follow_the_same_rules() {
+    let p = Permissions::allow_all().deny_singleton("console");
+    assert!(!p.singleton_visible("console"));
+    assert!(p.singleton_visible("performance"));
+
+    let p = Permissions::deny_all().allow_singleton("console");
+    assert!(p.singleton_visible("console"));
+    assert!(!p.singleton_visible("performance"));
+}
+
+#[test]
+fn check_module_uses_the_declared_version() {
+    let p = Permissions::deny_all().allow_module("std.fs@>=1").unwrap();
+    let module = |full_name| NativeModule {
+        base: "std.fs",
+        full_name,
+    };
+    assert!(p.check_module(&module("std.fs@1.0")).is_ok());
+    assert_eq!(
+        p.check_module(&module("std.fs@0.9")),
+        Err("not permitted in this context".to_string())
+    );
+}
+
+#[test]
+fn builder_installs_permissions_before_ridl_init() {
+    let ctx = Context::builder()
+        .permissions(Permissions::allow_all().deny_singleton("console"))
+        .ridl_init(|ctx| {
+            assert!(!ctx.inner.singleton_visible("console"));
+            assert!(ctx.inner.singleton_visible("performance"));
+            Ok(())
+        })
+        .build()
+        .unwrap();
+    assert!(ctx.inner.permissions().is_some());
+
+    let plain = Context::builder().build().unwrap();
+    assert!(plain.inner.singleton_visible("console"));
+}
+
+#[test]
+fn hide_global_fails_init_when_the_global_stays_visible() {
+    use crate::ridl_runtime::hide_global;
+
+    let ctx = Context::builder().build().unwrap();
+    ctx.eval("globalThis.plain = 1; Object.defineProperty(globalThis, 'pinned', { value: 1, writable: false })")
+        .unwrap();
+
+    assert_eq!(unsafe { hide_global(ctx.ctx, "plain") }, Ok(()));
+    assert_eq!(ctx.eval("typeof plain").unwrap(), "undefined");
+
+    let err = unsafe { hide_global(ctx.ctx, "pinned") }.unwrap_err();
+    assert!(err.starts_with("hide global 'pinned'"), "{err}");
+    assert_eq!(ctx.eval("pinned").unwrap(), "1");
+}
@@ -1,5 +1,12 @@
 use core::ffi::c_void;
+use std::cell::RefCell;
+use std::ffi::CString;
 
+use crate::handles::convert::{FromJs, IntoJs};
+use crate::handles::js_call::{is_exception, TempRoots};
+use crate::handles::local::{Local, Value};
+use crate::handles::scope::Scope;
+use crate::mquickjs_ffi;
 use crate::ridl_ext_access;
 
 /// A type-erased ctx slot stored in per-context extension state.
@@ -58,6 +65,206 @@ impl ErasedCtxSlot {
     }
 }
 
+/// Implemented by the generated singleton trait objects (`dyn <Name>Singleton` in a module's
+/// api.rs), naming the ctx slot that holds them.
+///
+/// # Safety
+/// The glue of `SLOT_KEY` must read the slot as `Box<Box<Self>>`.
+pub unsafe trait RidlSingleton {
+    const SLOT_KEY: &'static str;
+}
+
+/// A host-supplied singleton implementation waiting for `ridl_context_init`
+/// (see `ContextBuilder::singleton`).
+pub struct SingletonOverride {
+    ptr: *mut c_void,
+    drop_fn: unsafe extern "C" fn(*mut c_void),
+}
+
+impl SingletonOverride {
+    /// Erase `value` the same way the generated `ridl_create_<name>_singleton` does.
+    pub fn new<S: ?Sized + RidlSingleton + 'static>(value: Box<S>) -> Self {
+        let holder: Box<Box<S>> = Box::new(value);
+        Self {
+            ptr: Box::into_raw(holder) as *mut c_void,
+            drop_fn: drop_boxed_singleton::<S>,
+        }
+    }
+
+    /// Hand the allocation and its drop function to a ctx slot.
+    pub fn into_raw(self) -> (*mut c_void, unsafe extern "C" fn(*mut c_void)) {
+        let this = core::mem::ManuallyDrop::new(self);
+        (this.ptr, this.drop_fn)
+    }
+}
+
+impl Drop for SingletonOverride {
+    fn drop(&mut self) {
+        unsafe { (self.drop_fn)(self.ptr) };
+    }
+}
+
+unsafe extern "C" fn drop_boxed_singleton<S: ?Sized>(p: *mut c_void) {
+    drop(unsafe { Box::from_raw(p as *mut Box<S>) });
+}
+
+/// Error constructor used by [`throw_error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsErrorKind {
+    Error,
+    TypeError,
+    RangeError,
+}
+
+thread_local! {
+    static PENDING_THROW: RefCell<Option<(JsErrorKind, String)>> = const { RefCell::new(None) };
+}
+
+/// Make the RIDL call that is currently running throw `kind(message)` in JS.
+///
+/// RIDL impls return plain Rust values, so this is how they report invalid input: call it
+/// right before returning (the returned value is then discarded by the glue). Every generated
+/// entry point checks for it: functions, methods, constructors and property getters/setters.
+pub fn throw_error(kind: JsErrorKind, message: impl Into<String>) {
+    PENDING_THROW.with(|p| *p.borrow_mut() = Some((kind, message.into())));
+}
+
+/// Glue side of [`throw_error`]: raise the pending error, if any, and return the exception.
+///
+/// # Safety
+/// `ctx` must be a live JSContext.
+pub unsafe fn take_pending_throw(
+    ctx: *mut mquickjs_ffi::JSContext,
+) -> Option<mquickjs_ffi::JSValue> {
+    let (kind, message) = PENDING_THROW.with(|p| p.borrow_mut().take())?;
+    let class = match kind {
+        JsErrorKind::Error => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_ERROR,
+        JsErrorKind::TypeError => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_TYPE_ERROR,
+        JsErrorKind::RangeError => mquickjs_ffi::JSObjectClassEnum_JS_CLASS_RANGE_ERROR,
+    };
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    Some(unsafe {
+        mquickjs_ffi::JS_ThrowError(ctx, class, b"%s\0".as_ptr() as *const _, message.as_ptr())
+    })
+}
+
+/// Map the result of a per-context install step run by the generated `ridl_context_init`
+/// (e.g. JS-only singleton fields) to `Err` if it threw, taking the exception.
+///
+/// # Safety
+/// `ctx` must be a live JSContext.
+pub unsafe fn check_init(
+    ctx: *mut mquickjs_ffi::JSContext,
+    rc: mquickjs_ffi::JSValue,
+    what: &str,
+) -> Result<(), String> {
+    if is_exception(rc) {
+        return Err(crate::handles::js_call::take_exception_message(ctx, what));
+    }
+    Ok(())
+}
+
+/// Field access for the `FromJs` impls generated for RIDL `struct`s.
+///
+/// The source object stays rooted while fields are read: a getter may allocate.
+pub struct StructReader<'a, 'ctx> {
+    scope: &'a Scope<'ctx>,
+    name: &'static str,
+    roots: TempRoots,
+}
+
+impl<'a, 'ctx> StructReader<'a, 'ctx> {
+    /// `name` is the RIDL struct name, used in error messages.
+    pub fn new(
+        scope: &'a Scope<'ctx>,
+        name: &'static str,
+        v: Local<'ctx, Value>,
+    ) -> Result<Self, String> {
+        if v.as_object(scope).is_none() {
+            return Err(format!(
+                "expected {name} object, got {}",
+                v.kind(scope).name()
+            ));
+        }
+        let mut roots = TempRoots::new(scope.ctx_raw());
+        roots.push(v.as_raw());
+        Ok(Self { scope, name, roots })
+    }
+
+    /// Property `js_name`, converted like a typed call result (missing reads as `undefined`).
+    pub fn field<T: FromJs<'ctx>>(&self, js_name: &str) -> Result<T, String> {
+        let obj = self
+            .scope
+            .value(self.roots.get(0))
+            .try_into_object(self.scope)?;
+        let v = obj.get_property(self.scope, js_name)?;
+        T::from_js(self.scope, v).map_err(|e| format!("{}.{js_name}: {e}", self.name))
+    }
+}
+
+/// Builds the plain object returned for a RIDL `struct` (generated `IntoJs` impls).
+///
+/// The object stays rooted while later fields allocate.
+pub struct StructWriter<'a, 'ctx> {
+    scope: &'a Scope<'ctx>,
+    roots: TempRoots,
+}
+
+impl<'a, 'ctx> StructWriter<'a, 'ctx> {
+    pub fn new(scope: &'a Scope<'ctx>) -> Result<Self, String> {
+        let raw = unsafe { mquickjs_ffi::JS_NewObject(scope.ctx_raw()) };
+        if is_exception(raw) {
+            return Err("Exception during JS_NewObject".to_string());
+        }
+        let mut roots = TempRoots::new(scope.ctx_raw());
+        roots.push(raw);
+        Ok(Self { scope, roots })
+    }
+
+    pub fn field<T: IntoJs<'ctx>>(&self, js_name: &str, value: T) -> Result<(), String> {
+        let v = value.into_js(self.scope)?;
+        // Read the object back only now: converting `value` may have moved it.
+        let obj = self
+            .scope
+            .value(self.roots.get(0))
+            .try_into_object(self.scope)?;
+        obj.set_property(self.scope, js_name, v)
+    }
+
+    pub fn finish(self) -> Local<'ctx, Value> {
+        self.scope.value(self.roots.get(0))
+    }
+}
+
+/// Make the global `name` read as `undefined`; used by the generated `ridl_context_init` for
+/// singletons hidden by the context's `Permissions`.
+///
+/// The global is read back afterwards: a singleton that stays reachable (e.g. a non-writable
+/// property) is an init error, not a silently ignored one.
+///
+/// # Safety
+/// `ctx` must be a live JSContext.
+pub unsafe fn hide_global(ctx: *mut mquickjs_ffi::JSContext, name: &str) -> Result<(), String> {
+    let what = format!("hide global '{name}'");
+    let c_name = CString::new(name).map_err(|_| format!("{what}: name contains NUL"))?;
+    unsafe {
+        let global = mquickjs_ffi::JS_GetGlobalObject(ctx);
+        let r = mquickjs_ffi::JS_SetPropertyStr(
+            ctx,
+            global,
+            c_name.as_ptr(),
+            mquickjs_ffi::JS_UNDEFINED,
+        );
+        check_init(ctx, r, &what)?;
+        let v = mquickjs_ffi::JS_GetPropertyStr(ctx, global, c_name.as_ptr());
+        check_init(ctx, v, &what)?;
+        if v != mquickjs_ffi::JS_UNDEFINED {
+            return Err(format!("{what}: still defined after being cleared"));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RidlSlotSetError {
     /// The app did not install the ctx-ext vtable (or it was cleared), so slot lookup is impossible.
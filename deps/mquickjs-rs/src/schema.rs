@@ -0,0 +1,365 @@
+//! Check script values against RIDL types, e.g. a configuration object a script hands back
+//! to Rust.
+//!
+//! Types are normalized with `ridl_tool::parser::normalize::normalize_type` and then checked
+//! with the rules the generated glue applies to arguments: no coercion, `i32` / `i64` need
+//! integral numbers in range, `T?` accepts `null` and `undefined`, `bytes` accepts typed
+//! arrays and `ArrayBuffer`, maps are plain objects whose keys parse as the key type.
+//! Named types (`struct`, `using`, `enum`, `class`) come from a parsed IDL (`Schema::parse`).
+//!
+//! Unlike the glue, which stops at the first bad argument, validation reports every mismatch
+//! with its path, e.g. `$.servers[1].port: expected i32, got string`.
+
+use std::fmt;
+
+use ridl_tool::parser::ast::{IDLItem, Type};
+use ridl_tool::parser::normalize::normalize_type;
+use ridl_tool::parser::{parse_ridl_file, parse_type_expr};
+
+use crate::env::Env;
+use crate::handles::js_call::TempRoots;
+use crate::handles::local::{Local, Object, Value};
+use crate::handles::value_kind::ValueKind;
+use crate::mquickjs_ffi;
+
+/// Largest integer an `i64` argument accepts (`Number.MAX_SAFE_INTEGER`).
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Where the mismatch is: `$` is the value itself, then `.field`, `[index]`, `["key"]`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Named types available to `Type::Custom` references.
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+    items: Vec<IDLItem>,
+}
+
+impl Schema {
+    /// No named types: only built-in types validate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The named types declared in a RIDL source.
+    pub fn parse(ridl: &str) -> Result<Self, String> {
+        let parsed = parse_ridl_file(ridl).map_err(|e| e.to_string())?;
+        Ok(Self::from_items(parsed.items))
+    }
+
+    /// Items from `ridl_tool::parser::parse_ridl_file` (already normalized).
+    pub fn from_items(items: Vec<IDLItem>) -> Self {
+        Self { items }
+    }
+
+    /// Parse a type expression such as `array<Server>` or `i32 | string | null`.
+    pub fn parse_type(expr: &str) -> Result<Type, String> {
+        parse_type_expr(expr).map_err(|e| e.to_string())
+    }
+
+    /// Check `value` against `ty`; every mismatch is reported.
+    pub fn validate<'ctx>(
+        &self,
+        env: &mut Env<'ctx>,
+        ty: &Type,
+        value: Local<'ctx, Value>,
+    ) -> Result<(), Vec<ValidationError>> {
+        let ty = normalize_type(ty.clone()).map_err(|e| {
+            vec![ValidationError {
+                path: "$".to_string(),
+                message: format!("invalid type: {e}"),
+            }]
+        })?;
+        let mut walk = Walk {
+            schema: self,
+            errors: Vec::new(),
+            aliases: Vec::new(),
+        };
+        let mut path = "$".to_string();
+        walk.check(env, &ty, value, &mut path);
+        if walk.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(walk.errors)
+        }
+    }
+
+    /// Check `value` against the named type `name` (a struct, alias, enum or class).
+    pub fn validate_named<'ctx>(
+        &self,
+        env: &mut Env<'ctx>,
+        name: &str,
+        value: Local<'ctx, Value>,
+    ) -> Result<(), Vec<ValidationError>> {
+        self.validate(env, &Type::Custom(name.to_string()), value)
+    }
+
+    fn find(&self, name: &str) -> Option<&IDLItem> {
+        self.items.iter().find(|item| match item {
+            IDLItem::Struct(s) => s.name == name,
+            IDLItem::Using(u) => u.name == name,
+            IDLItem::Enum(e) => e.name == name,
+            IDLItem::Class(c) => c.name == name,
+            _ => false,
+        })
+    }
+}
+
+struct Walk<'s> {
+    schema: &'s Schema,
+    errors: Vec<ValidationError>,
+    // Aliases being expanded, to stop on `using A = B; using B = A;`.
+    aliases: Vec<String>,
+}
+
+/// `.name` for identifiers, `["..."]` otherwise.
+fn push_key(path: &mut String, key: &str) {
+    let mut chars = key.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        path.push('.');
+        path.push_str(key);
+    } else {
+        path.push_str(&format!("[{key:?}]"));
+    }
+}
+
+/// The object rooted at `roots[i]`, read back from its root; `obj` only supplies the context.
+fn rooted_object<'ctx>(
+    roots: &TempRoots,
+    i: usize,
+    obj: Local<'ctx, Object>,
+) -> Local<'ctx, Object> {
+    Local::from_raw_for_same_ctx(roots.get(i)).with_ctx_id(obj.ctx_id())
+}
+
+/// Whether a map key (always a string in JS) converts to the key type, as in the glue.
+fn key_matches(key_ty: &Type, key: &str) -> bool {
+    match key_ty {
+        Type::String => true,
+        Type::Bool => matches!(key, "true" | "false"),
+        Type::I32 => key.parse::<i32>().is_ok(),
+        Type::I64 => key.parse::<i64>().is_ok(),
+        Type::F32 | Type::F64 => key.parse::<f64>().is_ok_and(f64::is_finite),
+        _ => false,
+    }
+}
+
+impl Walk<'_> {
+    fn error(&mut self, path: &str, message: String) {
+        self.errors.push(ValidationError {
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn mismatch(&mut self, path: &str, expected: &dyn fmt::Display, kind: ValueKind) {
+        self.error(path, format!("expected {expected}, got {}", kind.name()));
+    }
+
+    /// Whether `v` matches `ty`, without recording errors (union members).
+    fn matches<'ctx>(&mut self, env: &mut Env<'ctx>, ty: &Type, v: Local<'ctx, Value>) -> bool {
+        let before = self.errors.len();
+        let mut path = String::new();
+        self.check(env, ty, v, &mut path);
+        let ok = self.errors.len() == before;
+        self.errors.truncate(before);
+        ok
+    }
+
+    fn check<'ctx>(
+        &mut self,
+        env: &mut Env<'ctx>,
+        ty: &Type,
+        v: Local<'ctx, Value>,
+        path: &mut String,
+    ) {
+        let scope = env.scope();
+        let kind = v.kind(scope);
+        match ty {
+            Type::Any => {}
+            Type::Group(inner) => self.check(env, inner, v, path),
+            Type::Optional(inner) => {
+                if !kind.is_nullish() {
+                    self.check(env, inner, v, path);
+                }
+            }
+            Type::Null if kind != ValueKind::Null => self.mismatch(path, ty, kind),
+            Type::Void if kind != ValueKind::Undefined => self.mismatch(path, ty, kind),
+            Type::Bool if kind != ValueKind::Bool => self.mismatch(path, ty, kind),
+            Type::String if kind != ValueKind::String => self.mismatch(path, ty, kind),
+            Type::F32 | Type::F64 if !kind.is_number() => self.mismatch(path, ty, kind),
+            Type::I32 | Type::I64 => {
+                let Some(n) = v.as_f64(scope) else {
+                    return self.mismatch(path, ty, kind);
+                };
+                let in_range = if matches!(ty, Type::I32) {
+                    n >= i32::MIN as f64 && n <= i32::MAX as f64
+                } else {
+                    n.abs() <= MAX_SAFE_INTEGER
+                };
+                if !n.is_finite() || n.fract() != 0.0 || !in_range {
+                    self.error(path, format!("expected {ty}, got {n}"));
+                }
+            }
+            Type::Bytes if !(v.is_typed_array(scope) || v.is_array_buffer(scope)) => {
+                self.error(
+                    path,
+                    format!(
+                        "expected bytes (typed array or ArrayBuffer), got {}",
+                        kind.name()
+                    ),
+                );
+            }
+            Type::Object if !kind.is_object() => self.mismatch(path, ty, kind),
+            Type::Callback | Type::CallbackWithParams(_) if kind != ValueKind::Function => {
+                self.mismatch(path, &"function", kind)
+            }
+            Type::ClassRef(_) if !matches!(kind, ValueKind::RidlClass(_)) => {
+                self.mismatch(path, ty, kind)
+            }
+            Type::Array(inner) => {
+                let Some(arr) = v.as_array(scope) else {
+                    return self.mismatch(path, ty, kind);
+                };
+                let items = match arr.to_vec(env) {
+                    Ok(items) => items,
+                    Err(e) => return self.error(path, format!("cannot read array: {e}")),
+                };
+                // Checking an item may allocate, and the GC moves objects: root every item and
+                // read it back right before its check.
+                let mut roots = TempRoots::new(scope.ctx_raw());
+                for item in &items {
+                    roots.push(item.as_raw());
+                }
+                for i in 0..items.len() {
+                    let len = path.len();
+                    path.push_str(&format!("[{i}]"));
+                    self.check(env, inner, scope.value(roots.get(i)), path);
+                    path.truncate(len);
+                }
+            }
+            Type::Map(key_ty, value_ty) => {
+                // Maps are plain objects, checked by class id as in the glue; `ValueKind::Object`
+                // also covers dates, typed arrays and the like.
+                let class_id = unsafe { mquickjs_ffi::JS_GetClassID(scope.ctx_raw(), v.as_raw()) };
+                if class_id != mquickjs_ffi::JSObjectClassEnum_JS_CLASS_OBJECT as i32 {
+                    return self.mismatch(path, ty, kind);
+                }
+                let Some(obj) = v.as_object(scope) else {
+                    return self.mismatch(path, ty, kind);
+                };
+                let mut roots = TempRoots::new(scope.ctx_raw());
+                let root = roots.push(obj.as_raw());
+                let keys = match obj.keys(scope) {
+                    Ok(keys) => keys,
+                    Err(e) => return self.error(path, format!("cannot read keys: {e}")),
+                };
+                for key in keys {
+                    let len = path.len();
+                    push_key(path, &key);
+                    if !key_matches(key_ty, &key) {
+                        self.error(path, format!("key is not a valid {key_ty}"));
+                    }
+                    // The previous value check may have moved the object: re-read its root.
+                    match rooted_object(&roots, root, obj).get_property(scope, &key) {
+                        Ok(item) => self.check(env, value_ty, item, path),
+                        Err(e) => self.error(path, format!("cannot read property: {e}")),
+                    }
+                    path.truncate(len);
+                }
+            }
+            Type::Union(members) => {
+                // A member check may allocate; re-read `v` from its root for every member.
+                let mut roots = TempRoots::new(scope.ctx_raw());
+                let root = roots.push(v.as_raw());
+                if !members
+                    .iter()
+                    .any(|m| self.matches(env, m, scope.value(roots.get(root))))
+                {
+                    self.mismatch(path, ty, kind);
+                }
+            }
+            Type::Custom(name) => self.check_named(env, name, v, path),
+            _ => {}
+        }
+    }
+
+    fn check_named<'ctx>(
+        &mut self,
+        env: &mut Env<'ctx>,
+        name: &str,
+        v: Local<'ctx, Value>,
+        path: &mut String,
+    ) {
+        let scope = env.scope();
+        let kind = v.kind(scope);
+        let schema = self.schema;
+        let Some(item) = schema.find(name) else {
+            return self.error(path, format!("unknown type '{name}'"));
+        };
+        match item {
+            IDLItem::Using(alias) => {
+                if self.aliases.iter().any(|a| a == name) {
+                    return self.error(path, format!("recursive type alias '{name}'"));
+                }
+                self.aliases.push(name.to_string());
+                self.check(env, &alias.alias_type, v, path);
+                self.aliases.pop();
+            }
+            IDLItem::Struct(st) => {
+                let Some(obj) = v
+                    .as_object(scope)
+                    .filter(|_| !matches!(kind, ValueKind::Array | ValueKind::Function))
+                else {
+                    return self.mismatch(path, &name, kind);
+                };
+                let mut roots = TempRoots::new(scope.ctx_raw());
+                let root = roots.push(obj.as_raw());
+                for field in &st.fields {
+                    let len = path.len();
+                    push_key(path, &field.name);
+                    // A missing field reads as `undefined`, which only `T?` accepts. The previous
+                    // field check may have moved the object: re-read its root.
+                    match rooted_object(&roots, root, obj).get_property(scope, &field.name) {
+                        Ok(item) if field.optional && item.is_undefined() => {}
+                        Ok(item) => self.check(env, &field.field_type, item, path),
+                        Err(e) => self.error(path, format!("cannot read property: {e}")),
+                    }
+                    path.truncate(len);
+                }
+            }
+            IDLItem::Enum(en) => {
+                let Some(n) = v.as_f64(scope) else {
+                    return self.mismatch(path, &name, kind);
+                };
+                // Implicit values continue from the previous one, starting at 0.
+                let mut next = 0i64;
+                let valid = en.values.iter().any(|ev| {
+                    let value = ev.value.map(i64::from).unwrap_or(next);
+                    next = value + 1;
+                    value as f64 == n
+                });
+                if !valid {
+                    self.error(path, format!("{n} is not a value of enum {name}"));
+                }
+            }
+            IDLItem::Class(_) if !matches!(kind, ValueKind::RidlClass(_)) => {
+                self.mismatch(path, &name, kind)
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::handles::local::Local;
+use crate::schema::Schema;
+use crate::{Context, Env};
+
+const CONFIG_RIDL: &str = r#"
+enum Level { Low = 1, Mid, High = 10 }
+struct Server { host: string; port: i32; tags: array<string>?; }
+struct Config { name: string; level: Level; servers: array<Server>; limits: map<string, i64>; }
+using Servers = array<Server>;
+"#;
+
+/// Evaluate `code` into a global and return the messages of validating it against `ty`.
+fn errors(schema: &Schema, ty: &str, code: &str) -> Vec<String> {
+    errors_in(1024 * 1024, schema, ty, code)
+}
+
+/// `errors` on a heap of `capacity` bytes.
+fn errors_in(capacity: usize, schema: &Schema, ty: &str, code: &str) -> Vec<String> {
+    let mut ctx = Context::new(capacity).unwrap();
+    ctx.eval(&format!("var result = ({code});")).unwrap();
+    let ty = Schema::parse_type(ty).unwrap();
+
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let value: Local = global.get_path(&env, "result").unwrap();
+    let value = env.handle(value).as_local();
+    match schema.validate(&mut env, &ty, value) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+#[test]
+fn builtin_types_follow_the_glue_rules() {
+    let s = Schema::new();
+    assert!(errors(&s, "i32", "42").is_empty());
+    assert_eq!(errors(&s, "i32", "1.5"), ["$: expected i32, got 1.5"]);
+    assert_eq!(errors(&s, "i32", "'1'"), ["$: expected i32, got string"]);
+    assert_eq!(
+        errors(&s, "i32", "2147483648"),
+        ["$: expected i32, got 2147483648"]
+    );
+    assert!(errors(&s, "i64", "2147483648").is_empty());
+    assert!(errors(&s, "f64", "1.5").is_empty());
+    assert!(errors(&s, "string?", "null").is_empty());
+    assert!(errors(&s, "string?", "undefined").is_empty());
+    assert!(errors(&s, "i32 | string", "'x'").is_empty());
+    assert_eq!(errors(&s, "i32 | string", "true").len(), 1);
+    assert!(errors(&s, "bytes", "new Uint8Array(2)").is_empty());
+    assert!(errors(&s, "bytes", "new ArrayBuffer(2)").is_empty());
+    assert_eq!(
+        errors(&s, "array<i32>", "[1, 'two', 3, null]"),
+        [
+            "$[1]: expected i32, got string",
+            "$[3]: expected i32, got null"
+        ]
+    );
+    assert_eq!(
+        errors(&s, "map<i32, bool>", "({ '1': true, 'x': false, '2': 0 })"),
+        // Integer-like keys come first in property order.
+        [
+            "$[\"2\"]: expected bool, got int",
+            "$.x: key is not a valid i32"
+        ]
+    );
+    // Maps are plain objects only.
+    assert_eq!(
+        errors(&s, "map<string, i32>", "new Date(0)"),
+        ["$: expected map<string, i32>, got object"]
+    );
+    assert_eq!(errors(&s, "Server", "({})"), ["$: unknown type 'Server'"]);
+}
+
+#[test]
+fn named_types_report_every_mismatch_with_its_path() {
+    let s = Schema::parse(CONFIG_RIDL).unwrap();
+    let good = "({ name: 'prod', level: 10, servers: [{ host: 'a', port: 80 }, \
+                { host: 'b', port: 81, tags: ['x'] }], limits: { rps: 100 } })";
+    assert!(errors(&s, "Config", good).is_empty());
+
+    let bad = "({ name: 'prod', level: 4, servers: [{ host: 'a', port: 80 }, \
+               { host: 'b', port: '81', tags: [1] }, []], limits: { 'max rps': 1.5 } })";
+    assert_eq!(
+        errors(&s, "Config", bad),
+        [
+            "$.level: 4 is not a value of enum Level",
+            "$.servers[1].port: expected i32, got string",
+            "$.servers[1].tags[0]: expected string, got int",
+            "$.servers[2]: expected Server, got array",
+            "$.limits[\"max rps\"]: expected i64, got 1.5",
+        ]
+    );
+    assert_eq!(
+        errors(&s, "Servers", "[{ host: 'a' }]"),
+        ["$[0].port: expected i32, got undefined"]
+    );
+}
+
+#[test]
+fn validation_survives_gc_between_fields() {
+    // A small heap, so reading keys and fields collects (and moves the objects) along the way.
+    let s = Schema::parse(CONFIG_RIDL).unwrap();
+    let servers = |init: &str, key: &str| {
+        format!(
+            "(function () {{ var m = {init}; for (var i = 0; i < 120; i++) \
+             m[{key}] = {{ host: 'h' + i, port: i % 40 ? i : 'p' + i, tags: ['t' + i] }}; \
+             return m; }})()"
+        )
+    };
+    let bad_ports = [0, 40, 80];
+
+    let want: Vec<String> = bad_ports
+        .iter()
+        .map(|i| format!("$.s{i}.port: expected i32, got string"))
+        .collect();
+    let got = errors_in(
+        64 * 1024,
+        &s,
+        "map<string, Server>",
+        &servers("{}", "'s' + i"),
+    );
+    assert_eq!(got, want);
+
+    let want: Vec<String> = bad_ports
+        .iter()
+        .map(|i| format!("$[{i}].port: expected i32, got string"))
+        .collect();
+    let got = errors_in(64 * 1024, &s, "array<Server>", &servers("[]", "i"));
+    assert_eq!(got, want);
+}
@@ -1,3 +1,7 @@
+//! `require` specs (`std.fs`, `std.fs@1.0`, `std.fs@<2`) and module versions.
+//!
+//! Used by ridl-tool for the require table and by mquickjs-rs for `Permissions`.
+
 use std::cmp::Ordering;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,6 +79,42 @@ pub enum RequireSpec {
     },
 }
 
+impl VersionOp {
+    /// Whether `version` satisfies `<op><bound>`.
+    pub fn test(self, version: Version, bound: Version) -> bool {
+        match self {
+            VersionOp::Eq => version == bound,
+            VersionOp::Gt => version > bound,
+            VersionOp::Ge => version >= bound,
+            VersionOp::Lt => version < bound,
+            VersionOp::Le => version <= bound,
+        }
+    }
+}
+
+impl RequireSpec {
+    pub fn base(&self) -> &str {
+        match self {
+            RequireSpec::Latest { base }
+            | RequireSpec::Exact { base, .. }
+            | RequireSpec::Range { base, .. } => base,
+        }
+    }
+
+    /// Whether module `base@version` satisfies this spec (same rules as require.c's table
+    /// lookup). `Latest` matches every version of its base.
+    pub fn matches(&self, base: &str, version: Version) -> bool {
+        if self.base() != base {
+            return false;
+        }
+        match self {
+            RequireSpec::Latest { .. } => true,
+            RequireSpec::Exact { version: v, .. } => version == *v,
+            RequireSpec::Range { op, version: v, .. } => op.test(version, *v),
+        }
+    }
+}
+
 /// Parse require(spec) argument.
 ///
 /// Grammar (V1):
@@ -218,6 +258,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn matches_module_versions() {
+        let v = |s| Version::parse_no_ws(s).unwrap();
+        let spec = |s| parse_require_spec_no_ws(s).unwrap();
+
+        assert!(spec("std.fs").matches("std.fs", v("0.1")));
+        assert!(spec("std.fs").matches("std.fs", v("9")));
+        assert!(!spec("std.fs").matches("std.fsx", v("1.0")));
+
+        assert!(spec("std.fs@1").matches("std.fs", v("1.0.0")));
+        assert!(!spec("std.fs@1").matches("std.fs", v("1.0.1")));
+
+        assert!(spec("std.fs@>=1.2").matches("std.fs", v("1.2")));
+        assert!(!spec("std.fs@>1.2").matches("std.fs", v("1.2")));
+        assert!(spec("std.fs@<2").matches("std.fs", v("1.99.99")));
+        assert!(!spec("std.fs@<=1.2").matches("std.fs", v("1.2.1")));
+        assert!(!spec("std.fs@<2").matches("std.net", v("1.0")));
+    }
+
     #[test]
     fn reject_whitespace_and_bad_forms() {
         for s in [
@@ -0,0 +1,750 @@
+//! RIDL API 兼容性检查
+//!
+//! 对比同一模块的两个版本（old.ridl / new.ridl），把 API 差异分类为
+//! breaking / minor / patch，并检查 `module name@x.y.z` 声明的版本号提升是否足够。
+//!
+//! 分类规则（JS 调用方视角）：
+//! - breaking：删除成员、参数类型收窄、新增必填参数/必填结构体字段、返回值类型变化、属性变为只读等
+//! - minor：新增成员、新增可选参数、参数类型放宽、只读属性变为可写
+//! - patch：不影响调用方的变化（例如参数改名）
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::parser::ast::{
+    Class, Constant, Enum, Function, IDLItem, Interface, JsField, Method, Param, Property,
+    PropertyModifier, Singleton, StructDef, Type, Using,
+};
+use crate::parser::require_spec::Version;
+use crate::parser::ParsedIDL;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeLevel {
+    Patch,
+    Minor,
+    Breaking,
+}
+
+impl fmt::Display for ChangeLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeLevel::Patch => write!(f, "patch"),
+            ChangeLevel::Minor => write!(f, "minor"),
+            ChangeLevel::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub level: ChangeLevel,
+    /// 变化所在位置，例如 `singleton console.log`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.level, self.path, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompatReport {
+    pub old_version: Option<Version>,
+    pub new_version: Option<Version>,
+    pub changes: Vec<ApiChange>,
+}
+
+impl CompatReport {
+    /// API 差异所要求的最小版本提升；没有任何差异时返回 None。
+    pub fn required_bump(&self) -> Option<ChangeLevel> {
+        self.changes.iter().map(|c| c.level).max()
+    }
+
+    /// 两个版本号之间实际声明的提升。
+    ///
+    /// 遵循 semver 的 0.x 约定：`0.y.z` 阶段 minor 提升视为 breaking 级别。
+    pub fn declared_bump(&self) -> Result<Option<ChangeLevel>, String> {
+        let (Some(old), Some(new)) = (self.old_version, self.new_version) else {
+            return Err(
+                "both RIDL files must declare a module version (`module name@x.y.z`)".to_string(),
+            );
+        };
+        declared_bump(old, new)
+    }
+
+    /// 检查声明的版本提升是否覆盖了 API 差异。
+    ///
+    /// 没有 API 差异时不要求声明版本号；两边都声明了则仍检查版本没有回退。
+    pub fn check(&self) -> Result<(), String> {
+        let Some(required) = self.required_bump() else {
+            if self.old_version.is_some() && self.new_version.is_some() {
+                self.declared_bump()?;
+            }
+            return Ok(());
+        };
+        let declared = self.declared_bump()?;
+        if declared.map(|d| d >= required).unwrap_or(false) {
+            return Ok(());
+        }
+        let old = self.old_version.expect("checked by declared_bump");
+        let new = self.new_version.expect("checked by declared_bump");
+        Err(format!(
+            "version bump {} -> {} is too small: API changes require a {} bump",
+            fmt_version(old),
+            fmt_version(new),
+            required
+        ))
+    }
+}
+
+fn fmt_version(v: Version) -> String {
+    format!("{}.{}.{}", v.major, v.minor, v.patch)
+}
+
+fn declared_bump(old: Version, new: Version) -> Result<Option<ChangeLevel>, String> {
+    if new < old {
+        return Err(format!(
+            "new version {} is lower than old version {}",
+            fmt_version(new),
+            fmt_version(old)
+        ));
+    }
+    Ok(if new.major != old.major {
+        Some(ChangeLevel::Breaking)
+    } else if new.minor != old.minor {
+        if old.major == 0 {
+            Some(ChangeLevel::Breaking)
+        } else {
+            Some(ChangeLevel::Minor)
+        }
+    } else if new.patch != old.patch {
+        if old.major == 0 && old.minor == 0 {
+            Some(ChangeLevel::Breaking)
+        } else {
+            Some(ChangeLevel::Patch)
+        }
+    } else {
+        None
+    })
+}
+
+/// 对比两个已解析的 RIDL 文件。
+pub fn compare(old: &ParsedIDL, new: &ParsedIDL) -> Result<CompatReport, String> {
+    let old_version = module_version(old)?;
+    let new_version = module_version(new)?;
+
+    let mut d = Differ::default();
+
+    let old_name = old.module.as_ref().map(|m| m.module_path.as_str());
+    let new_name = new.module.as_ref().map(|m| m.module_path.as_str());
+    if old_name != new_name {
+        d.push(
+            ChangeLevel::Breaking,
+            "module",
+            format!(
+                "module renamed from {} to {}",
+                old_name.unwrap_or("<global>"),
+                new_name.unwrap_or("<global>")
+            ),
+        );
+    }
+
+    d.diff_items(&old.items, &new.items);
+
+    Ok(CompatReport {
+        old_version,
+        new_version,
+        changes: d.changes,
+    })
+}
+
+/// 从文件内容解析并对比。
+pub fn compare_sources(old_src: &str, new_src: &str) -> Result<CompatReport, String> {
+    let old = crate::parser::parse_ridl_file(old_src).map_err(|e| format!("old: {e}"))?;
+    let new = crate::parser::parse_ridl_file(new_src).map_err(|e| format!("new: {e}"))?;
+    compare(&old, &new)
+}
+
+fn module_version(parsed: &ParsedIDL) -> Result<Option<Version>, String> {
+    let Some(v) = parsed.module.as_ref().and_then(|m| m.version.as_deref()) else {
+        return Ok(None);
+    };
+    Version::parse_no_ws(v)
+        .map(Some)
+        .ok_or_else(|| format!("invalid module version: {v}"))
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<ApiChange>,
+}
+
+impl Differ {
+    fn push(&mut self, level: ChangeLevel, path: &str, message: impl Into<String>) {
+        self.changes.push(ApiChange {
+            level,
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn diff_items(&mut self, old: &[IDLItem], new: &[IDLItem]) {
+        let old_map = index_items(old);
+        let new_map = index_items(new);
+
+        for (key, o) in &old_map {
+            match new_map.get(key) {
+                None => self.push(ChangeLevel::Breaking, key, "removed"),
+                Some(n) => self.diff_item(key, o, n),
+            }
+        }
+        for key in new_map.keys() {
+            if !old_map.contains_key(key) {
+                self.push(ChangeLevel::Minor, key, "added");
+            }
+        }
+    }
+
+    fn diff_item(&mut self, path: &str, old: &IDLItem, new: &IDLItem) {
+        match (old, new) {
+            (IDLItem::Function(o), IDLItem::Function(n)) => self.diff_function(path, o, n),
+            (IDLItem::Singleton(o), IDLItem::Singleton(n)) => self.diff_singleton(path, o, n),
+            (IDLItem::Class(o), IDLItem::Class(n)) => self.diff_class(path, o, n),
+            (IDLItem::Interface(o), IDLItem::Interface(n)) => self.diff_interface(path, o, n),
+            (IDLItem::Enum(o), IDLItem::Enum(n)) => self.diff_enum(path, o, n),
+            (IDLItem::Struct(o), IDLItem::Struct(n)) => self.diff_struct(path, o, n),
+            (IDLItem::Using(o), IDLItem::Using(n)) => self.diff_using(path, o, n),
+            (IDLItem::Const(o), IDLItem::Const(n)) => self.diff_constant(path, o, n),
+            // index_items 的 key 带有种类前缀，不会出现种类不同的配对
+            _ => {}
+        }
+    }
+
+    fn diff_function(&mut self, path: &str, old: &Function, new: &Function) {
+        self.diff_signature(
+            path,
+            (&old.params, &old.return_type, old.is_async),
+            (&new.params, &new.return_type, new.is_async),
+        );
+    }
+
+    fn diff_method(&mut self, path: &str, old: &Method, new: &Method) {
+        self.diff_signature(
+            path,
+            (&old.params, &old.return_type, old.is_async),
+            (&new.params, &new.return_type, new.is_async),
+        );
+    }
+
+    fn diff_singleton(&mut self, path: &str, old: &Singleton, new: &Singleton) {
+        self.diff_methods(path, &old.methods, &new.methods);
+        self.diff_properties(path, &old.properties, &new.properties);
+        self.diff_js_fields(path, &old.js_fields, &new.js_fields);
+        self.diff_constants(path, &old.constants, &new.constants);
+    }
+
+    fn diff_class(&mut self, path: &str, old: &Class, new: &Class) {
+        match (&old.constructor, &new.constructor) {
+            (Some(o), Some(n)) => self.diff_function(&format!("{path}.constructor"), o, n),
+            (Some(_), None) => self.push(
+                ChangeLevel::Breaking,
+                &format!("{path}.constructor"),
+                "removed",
+            ),
+            (None, Some(_)) => {
+                self.push(ChangeLevel::Minor, &format!("{path}.constructor"), "added")
+            }
+            (None, None) => {}
+        }
+        self.diff_methods(path, &old.methods, &new.methods);
+        self.diff_properties(path, &old.properties, &new.properties);
+
+        self.diff_js_fields(path, &old.js_fields, &new.js_fields);
+        self.diff_constants(path, &old.constants, &new.constants);
+    }
+
+    fn diff_js_fields(&mut self, path: &str, old: &[JsField], new: &[JsField]) {
+        for o in old {
+            let member = format!("{path}.{}", o.name);
+            match new.iter().find(|n| n.name == o.name) {
+                None => self.push(ChangeLevel::Breaking, &member, "removed"),
+                Some(n) => {
+                    if n.field_type != o.field_type || n.modifiers != o.modifiers {
+                        self.push(
+                            ChangeLevel::Breaking,
+                            &member,
+                            format!("field changed from {} to {}", o.field_type, n.field_type),
+                        );
+                    } else if n.init_literal != o.init_literal {
+                        self.push(
+                            ChangeLevel::Minor,
+                            &member,
+                            format!(
+                                "initial value changed from {} to {}",
+                                o.init_literal, n.init_literal
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+        for n in new {
+            if !old.iter().any(|o| o.name == n.name) {
+                self.push(ChangeLevel::Minor, &format!("{path}.{}", n.name), "added");
+            }
+        }
+    }
+
+    fn diff_constants(&mut self, path: &str, old: &[Constant], new: &[Constant]) {
+        for o in old {
+            let member = format!("{path}.{}", o.name);
+            match new.iter().find(|n| n.name == o.name) {
+                None => self.push(ChangeLevel::Breaking, &member, "const removed"),
+                Some(n) => self.diff_constant(&member, o, n),
+            }
+        }
+        for n in new {
+            if !old.iter().any(|o| o.name == n.name) {
+                self.push(
+                    ChangeLevel::Minor,
+                    &format!("{path}.{}", n.name),
+                    "const added",
+                );
+            }
+        }
+    }
+
+    fn diff_constant(&mut self, path: &str, old: &Constant, new: &Constant) {
+        if old.const_type != new.const_type {
+            self.push(
+                ChangeLevel::Breaking,
+                path,
+                format!(
+                    "const type changed from {} to {}",
+                    old.const_type, new.const_type
+                ),
+            );
+        } else if old.value != new.value {
+            // 与 var 初始值一致：值变化不影响调用方式，按 minor 处理
+            self.push(
+                ChangeLevel::Minor,
+                path,
+                format!("const value changed from {} to {}", old.value, new.value),
+            );
+        }
+    }
+
+    fn diff_interface(&mut self, path: &str, old: &Interface, new: &Interface) {
+        self.diff_methods(path, &old.methods, &new.methods);
+        self.diff_properties(path, &old.properties, &new.properties);
+    }
+
+    fn diff_enum(&mut self, path: &str, old: &Enum, new: &Enum) {
+        for o in &old.values {
+            let member = format!("{path}.{}", o.name);
+            match new.values.iter().find(|n| n.name == o.name) {
+                None => self.push(ChangeLevel::Breaking, &member, "removed"),
+                Some(n) if n.value != o.value => {
+                    self.push(ChangeLevel::Breaking, &member, "value changed")
+                }
+                Some(_) => {}
+            }
+        }
+        for n in &new.values {
+            if !old.values.iter().any(|o| o.name == n.name) {
+                self.push(ChangeLevel::Minor, &format!("{path}.{}", n.name), "added");
+            }
+        }
+    }
+
+    fn diff_struct(&mut self, path: &str, old: &StructDef, new: &StructDef) {
+        for o in &old.fields {
+            let member = format!("{path}.{}", o.name);
+            match new.fields.iter().find(|n| n.name == o.name) {
+                None => self.push(ChangeLevel::Breaking, &member, "removed"),
+                Some(n) if n.field_type != o.field_type || n.optional != o.optional => self.push(
+                    ChangeLevel::Breaking,
+                    &member,
+                    format!("type changed from {} to {}", o.field_type, n.field_type),
+                ),
+                Some(_) => {}
+            }
+        }
+        for n in &new.fields {
+            if old.fields.iter().any(|o| o.name == n.name) {
+                continue;
+            }
+            // 结构体可能由调用方构造并传入：新增必填字段会让旧的调用方校验失败
+            let member = format!("{path}.{}", n.name);
+            if n.optional || matches!(n.field_type, Type::Optional(_)) {
+                self.push(ChangeLevel::Minor, &member, "optional field added");
+            } else {
+                self.push(ChangeLevel::Breaking, &member, "required field added");
+            }
+        }
+    }
+
+    fn diff_using(&mut self, path: &str, old: &Using, new: &Using) {
+        if old.alias_type != new.alias_type {
+            self.push(
+                ChangeLevel::Breaking,
+                path,
+                format!(
+                    "alias changed from {} to {}",
+                    old.alias_type, new.alias_type
+                ),
+            );
+        }
+    }
+
+    /// 方法按 JS 名分组（重载变体的 `name` 是 Rust 变体名，JS 名在 `overload_of`）。
+    fn diff_methods(&mut self, path: &str, old: &[Method], new: &[Method]) {
+        let old_sets = group_overloads(old);
+        let new_sets = group_overloads(new);
+        for (js_name, o) in &old_sets {
+            let member = format!("{path}.{js_name}");
+            match new_sets.get(js_name) {
+                None => self.push(ChangeLevel::Breaking, &member, "method removed"),
+                Some(n) if o.len() == 1 && n.len() == 1 => self.diff_method(&member, o[0], n[0]),
+                Some(n) => self.diff_overload_set(&member, o, n),
+            }
+        }
+        for js_name in new_sets.keys() {
+            if !old_sets.contains_key(js_name) {
+                self.push(
+                    ChangeLevel::Minor,
+                    &format!("{path}.{js_name}"),
+                    "method added",
+                );
+            }
+        }
+    }
+
+    /// 重载集合：旧变体须仍可调用（同参数类型的变体，或兼容地接收它的变体），新增变体为 minor。
+    fn diff_overload_set(&mut self, path: &str, old: &[&Method], new: &[&Method]) {
+        let mut matched = vec![false; new.len()];
+        for o in old {
+            if let Some(i) = new.iter().position(|n| same_param_types(o, n)) {
+                matched[i] = true;
+                self.diff_method(path, o, new[i]);
+                continue;
+            }
+            // 参数类型有变化：取第一个对旧调用方式不构成 breaking 的变体
+            let compatible = new.iter().enumerate().find_map(|(i, n)| {
+                let mut trial = Differ::default();
+                trial.diff_method(path, o, n);
+                let ok = trial
+                    .changes
+                    .iter()
+                    .all(|c| c.level < ChangeLevel::Breaking);
+                ok.then_some((i, trial.changes))
+            });
+            match compatible {
+                Some((i, changes)) => {
+                    matched[i] = true;
+                    self.changes.extend(changes);
+                }
+                None => self.push(
+                    ChangeLevel::Breaking,
+                    path,
+                    format!("overload {} removed", overload_signature(o)),
+                ),
+            }
+        }
+        for (n, _) in new.iter().zip(&matched).filter(|(_, m)| !**m) {
+            self.push(
+                ChangeLevel::Minor,
+                path,
+                format!("overload {} added", overload_signature(n)),
+            );
+        }
+    }
+
+    fn diff_properties(&mut self, path: &str, old: &[Property], new: &[Property]) {
+        for o in old {
+            let member = format!("{path}.{}", o.name);
+            let Some(n) = new.iter().find(|n| n.name == o.name) else {
+                self.push(ChangeLevel::Breaking, &member, "property removed");
+                continue;
+            };
+            if n.property_type != o.property_type {
+                self.push(
+                    ChangeLevel::Breaking,
+                    &member,
+                    format!(
+                        "property type changed from {} to {}",
+                        o.property_type, n.property_type
+                    ),
+                );
+            }
+            let o_ro = o.modifiers.contains(&PropertyModifier::ReadOnly);
+            let n_ro = n.modifiers.contains(&PropertyModifier::ReadOnly);
+            if !o_ro && n_ro {
+                self.push(ChangeLevel::Breaking, &member, "property became readonly");
+            } else if o_ro && !n_ro {
+                self.push(ChangeLevel::Minor, &member, "property became writable");
+            }
+            let o_proto = o.modifiers.contains(&PropertyModifier::Proto);
+            let n_proto = n.modifiers.contains(&PropertyModifier::Proto);
+            if o_proto != n_proto {
+                self.push(ChangeLevel::Breaking, &member, "proto modifier changed");
+            }
+        }
+        for n in new {
+            if !old.iter().any(|o| o.name == n.name) {
+                self.push(
+                    ChangeLevel::Minor,
+                    &format!("{path}.{}", n.name),
+                    "property added",
+                );
+            }
+        }
+    }
+
+    fn diff_signature(
+        &mut self,
+        path: &str,
+        (old_params, old_ret, old_async): (&[Param], &Type, bool),
+        (new_params, new_ret, new_async): (&[Param], &Type, bool),
+    ) {
+        if old_ret != new_ret {
+            self.push(
+                ChangeLevel::Breaking,
+                path,
+                format!("return type changed from {} to {}", old_ret, new_ret),
+            );
+        }
+        if old_async != new_async {
+            self.push(ChangeLevel::Breaking, path, "async modifier changed");
+        }
+
+        for (i, o) in old_params.iter().enumerate() {
+            let Some(n) = new_params.get(i) else {
+                self.push(
+                    ChangeLevel::Breaking,
+                    path,
+                    format!("parameter `{}` removed", o.name),
+                );
+                continue;
+            };
+
+            if o.variadic != n.variadic {
+                self.push(
+                    ChangeLevel::Breaking,
+                    path,
+                    format!("parameter `{}` variadic modifier changed", o.name),
+                );
+                continue;
+            }
+
+            if o.param_type != n.param_type {
+                if type_accepts(&n.param_type, &o.param_type) {
+                    self.push(
+                        ChangeLevel::Minor,
+                        path,
+                        format!(
+                            "parameter `{}` widened from {} to {}",
+                            o.name, o.param_type, n.param_type
+                        ),
+                    );
+                } else {
+                    self.push(
+                        ChangeLevel::Breaking,
+                        path,
+                        format!(
+                            "parameter `{}` narrowed from {} to {}",
+                            o.name, o.param_type, n.param_type
+                        ),
+                    );
+                }
+            }
+
+            match (&o.default_literal, &n.default_literal) {
+                (None, Some(d)) => self.push(
+                    ChangeLevel::Minor,
+                    path,
+                    format!("parameter `{}` now defaults to {}", o.name, d),
+                ),
+                (Some(_), None) if !param_can_be_omitted(n) => self.push(
+                    ChangeLevel::Breaking,
+                    path,
+                    format!("parameter `{}` default value removed", o.name),
+                ),
+                (Some(od), Some(nd)) if od != nd => self.push(
+                    ChangeLevel::Minor,
+                    path,
+                    format!(
+                        "parameter `{}` default changed from {} to {}",
+                        o.name, od, nd
+                    ),
+                ),
+                _ => {}
+            }
+
+            if o.name != n.name {
+                self.push(
+                    ChangeLevel::Patch,
+                    path,
+                    format!("parameter `{}` renamed to `{}`", o.name, n.name),
+                );
+            }
+        }
+
+        for n in new_params.iter().skip(old_params.len()) {
+            if param_can_be_omitted(n) {
+                self.push(
+                    ChangeLevel::Minor,
+                    path,
+                    format!("optional parameter `{}` added", n.name),
+                );
+            } else {
+                self.push(
+                    ChangeLevel::Breaking,
+                    path,
+                    format!("required parameter `{}` added", n.name),
+                );
+            }
+        }
+    }
+}
+
+fn group_overloads(methods: &[Method]) -> BTreeMap<&str, Vec<&Method>> {
+    let mut out: BTreeMap<&str, Vec<&Method>> = BTreeMap::new();
+    for m in methods {
+        let js_name = m.overload_of.as_deref().unwrap_or(&m.name);
+        out.entry(js_name).or_default().push(m);
+    }
+    out
+}
+
+fn same_param_types(a: &Method, b: &Method) -> bool {
+    a.params.len() == b.params.len()
+        && a.params
+            .iter()
+            .zip(&b.params)
+            .all(|(x, y)| x.param_type == y.param_type && x.variadic == y.variadic)
+}
+
+/// `(i32, i32)` / `(...string)`，用于重载变体的变更信息。
+fn overload_signature(m: &Method) -> String {
+    let params: Vec<String> = m
+        .params
+        .iter()
+        .map(|p| {
+            if p.variadic {
+                format!("...{}", p.param_type)
+            } else {
+                p.param_type.to_string()
+            }
+        })
+        .collect();
+    format!("({})", params.join(", "))
+}
+
+/// 调用方是否可以省略该参数（nullable / variadic）。
+fn param_can_be_omitted(p: &Param) -> bool {
+    p.optional || p.variadic || matches!(p.param_type, Type::Optional(_) | Type::Any)
+}
+
+/// `new` 能否接收所有 `old` 能接收的 JS 值（即 new 是 old 的放宽）。
+fn type_accepts(new: &Type, old: &Type) -> bool {
+    if new == old {
+        return true;
+    }
+    match (new, old) {
+        (Type::Group(n), _) => type_accepts(n, old),
+        (_, Type::Group(o)) => type_accepts(new, o),
+        (Type::Any, _) => true,
+        // old 是 union：每个成员都必须被 new 接收
+        (_, Type::Union(os)) => os.iter().all(|o| type_accepts(new, o)),
+        (_, Type::Optional(o)) => type_accepts(new, &Type::Null) && type_accepts(new, o),
+        (Type::Optional(_), Type::Null) => true,
+        (Type::Optional(n), _) => type_accepts(n, old),
+        (Type::Union(ns), _) => ns.iter().any(|n| type_accepts(n, old)),
+        (Type::Array(n), Type::Array(o)) => type_accepts(n, o),
+        (Type::Map(nk, nv), Type::Map(ok, ov)) => nk == ok && type_accepts(nv, ov),
+        // JS number：i32 的取值范围被 i64/f64 覆盖
+        (Type::I64 | Type::F64, Type::I32) => true,
+        (Type::F64, Type::F32) => true,
+        (Type::Object, Type::ClassRef(_) | Type::Map(_, _)) => true,
+        _ => false,
+    }
+}
+
+fn index_items(items: &[IDLItem]) -> BTreeMap<String, &IDLItem> {
+    let mut out = BTreeMap::new();
+    for item in items {
+        let key = match item {
+            IDLItem::Function(f) => format!("fn {}", f.name),
+            IDLItem::Singleton(s) => format!("singleton {}", s.name),
+            IDLItem::Class(c) => format!("class {}", c.name),
+            IDLItem::Interface(i) => format!("interface {}", i.name),
+            IDLItem::Enum(e) => format!("enum {}", e.name),
+            IDLItem::Struct(s) => format!("struct {}", s.name),
+            IDLItem::Using(u) => format!("using {}", u.name),
+            IDLItem::Const(c) => format!("const {}", c.name),
+            // import 只影响解析，不属于对外 API
+            IDLItem::Import(_) => continue,
+        };
+        out.insert(key, item);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse_no_ws(s).unwrap()
+    }
+
+    #[test]
+    fn declared_bump_follows_semver() {
+        assert_eq!(declared_bump(v("1.2.3"), v("1.2.3")).unwrap(), None);
+        assert_eq!(
+            declared_bump(v("1.2.3"), v("1.2.4")).unwrap(),
+            Some(ChangeLevel::Patch)
+        );
+        assert_eq!(
+            declared_bump(v("1.2.3"), v("1.3.0")).unwrap(),
+            Some(ChangeLevel::Minor)
+        );
+        assert_eq!(
+            declared_bump(v("1.2.3"), v("2.0.0")).unwrap(),
+            Some(ChangeLevel::Breaking)
+        );
+        // 0.x：minor 提升即 breaking
+        assert_eq!(
+            declared_bump(v("0.1.0"), v("0.2.0")).unwrap(),
+            Some(ChangeLevel::Breaking)
+        );
+        assert!(declared_bump(v("1.2.0"), v("1.1.0")).is_err());
+    }
+
+    #[test]
+    fn type_accepts_widening() {
+        assert!(type_accepts(
+            &Type::Optional(Box::new(Type::I32)),
+            &Type::I32
+        ));
+        assert!(type_accepts(
+            &Type::Union(vec![Type::I32, Type::String]),
+            &Type::String
+        ));
+        assert!(type_accepts(&Type::F64, &Type::I32));
+        assert!(type_accepts(&Type::Any, &Type::String));
+
+        assert!(!type_accepts(
+            &Type::I32,
+            &Type::Optional(Box::new(Type::I32))
+        ));
+        assert!(!type_accepts(
+            &Type::String,
+            &Type::Union(vec![Type::I32, Type::String])
+        ));
+        assert!(!type_accepts(&Type::I32, &Type::F64));
+    }
+}
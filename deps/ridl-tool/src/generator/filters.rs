@@ -1,6 +1,6 @@
 use crate::generator::code_writer::CodeWriter;
 use crate::generator::TemplateParam;
-use crate::parser::ast::{PropertyModifier, Type};
+use crate::parser::ast::{DefaultLiteral, PropertyModifier, Type};
 use crate::parser::FileMode;
 
 // Generator template filters.
@@ -56,6 +56,7 @@ pub fn rust_type_from_idl(idl_type: &Type) -> Result<String, askama::Error> {
         Type::F32 => "f32".to_string(),
         Type::F64 => "f64".to_string(),
         Type::String => "String".to_string(),
+        Type::Bytes => "Vec<u8>".to_string(),
         Type::Void => "()".to_string(),
 
         // Optional(T) at Rust boundary.
@@ -72,11 +73,19 @@ pub fn rust_type_from_idl(idl_type: &Type) -> Result<String, askama::Error> {
             "mquickjs_rs::handles::local::Local<'_, mquickjs_rs::handles::local::Value>".to_string()
         }
 
+        // callback params are borrowed function views (same lifetime rule as `any`).
+        // To call them later, the impl roots them in a `Global<Function>`.
+        Type::Callback | Type::CallbackWithParams(_) => {
+            "mquickjs_rs::handles::local::Local<'_, mquickjs_rs::handles::local::Function>"
+                .to_string()
+        }
+
         // For class refs, treat them as trait objects at Rust boundary.
         Type::ClassRef(name) => format!("Box<dyn crate::api::{}Class>", name),
 
-        // Custom types are not supported as typed returns/params in v1.
-        // They should be lowered to `any` by higher-level generator logic if needed.
+        // Named types are RIDL structs, declared in api.rs (glue converts them via FromJs/IntoJs).
+        // A parenthesized union inside Optional(...) is handled by the union overrides instead.
+        Type::Custom(name) if !name.starts_with('(') => format!("crate::api::{}", name),
         Type::Custom(_name) => {
             return Err(askama::Error::Custom(
                 "v1 rust_type_from_idl: unsupported Custom named type".into(),
@@ -147,10 +156,16 @@ pub fn emit_value_to_js(ty: &Type, value_expr: &str) -> ::askama::Result<String>
         }
         Type::String => {
             w.push_line(format!(
-                "let cstr = CString::new({value}.as_str()).unwrap_or_else(|_| CString::new(\"\").unwrap());",
+                "let v = match unsafe {{ mquickjs_rs::handles::string::new_string_raw(ctx, {value}.as_str()) }} {{ Ok(v) => v, Err(_) => return js_throw_type_error(ctx, \"failed to create string\") }};",
+                value = value_expr
+            ));
+            w.push_line("v");
+        }
+        Type::Bytes => {
+            w.push_line(format!(
+                "let v = match unsafe {{ mquickjs_rs::handles::typed_array::new_uint8_array_raw(ctx, &{value}) }} {{ Ok(v) => v, Err(_) => return js_throw_type_error(ctx, \"failed to create Uint8Array\") }};",
                 value = value_expr
             ));
-            w.push_line("let v = unsafe { mquickjs_rs::mquickjs_ffi::JS_NewString(ctx, cstr.as_ptr()) };");
             w.push_line("v");
         }
         Type::Map(key_ty, value_ty) => {
@@ -255,11 +270,18 @@ pub fn emit_return_convert_typed(
             w.push_line("mquickjs_rs::mquickjs_ffi::JS_UNDEFINED");
         }
         Type::String => {
+            // Interior NULs are kept (`CString` would reject them).
+            w.push_line(format!(
+                "match unsafe {{ mquickjs_rs::handles::string::new_string_raw(ctx, &{result_name}) }} {{ Ok(v) => v, Err(_) => js_throw_type_error(ctx, \"failed to create string\") }}",
+                result_name = result_name
+            ));
+        }
+        Type::Bytes => {
+            // Copied into a fresh Uint8Array (mquickjs has no external-buffer constructor).
             w.push_line(format!(
-                "let cstr = CString::new({result_name}).unwrap_or_else(|_| CString::new(\"\").unwrap());",
+                "match unsafe {{ mquickjs_rs::handles::typed_array::new_uint8_array_raw(ctx, &{result_name}) }} {{ Ok(v) => v, Err(_) => js_throw_type_error(ctx, \"failed to create Uint8Array\") }}",
                 result_name = result_name
             ));
-            w.push_line("unsafe { mquickjs_rs::mquickjs_ffi::JS_NewString(ctx, cstr.as_ptr()) }");
         }
         Type::I32 => {
             w.push_line(format!(
@@ -299,6 +321,12 @@ pub fn emit_return_convert_typed(
             let value_to_js = emit_value_to_js(return_type, result_name)?;
             w.push_line(value_to_js);
         }
+        Type::Custom(name) if !name.starts_with('(') => {
+            emit_into_js_return(&mut w, result_name);
+        }
+        Type::Array(_) => {
+            emit_into_js_return(&mut w, result_name);
+        }
         Type::ClassRef(name) => {
             w.push_line(format!(
                 "unsafe {{ ridl_boxed_{}_to_js(ctx, {result_name}) }}",
@@ -314,6 +342,7 @@ pub fn emit_return_convert_typed(
             let want_i64 = types.iter().any(|t| matches!(t, Type::I64));
             let want_f32 = types.iter().any(|t| matches!(t, Type::F32));
             let want_f64 = types.iter().any(|t| matches!(t, Type::F64));
+            let want_bytes = types.iter().any(|t| matches!(t, Type::Bytes));
 
             let mut arms: Vec<String> = Vec::new();
 
@@ -347,6 +376,12 @@ pub fn emit_return_convert_typed(
                     ty = result_rust_ty
                 ));
             }
+            if want_bytes {
+                arms.push(format!(
+                    "{ty}::Bytes(b) => match unsafe {{ mquickjs_rs::handles::typed_array::new_uint8_array_raw(ctx, &b) }} {{ Ok(v) => v, Err(_) => js_throw_type_error(ctx, \"failed to create Uint8Array\") }}",
+                    ty = result_rust_ty
+                ));
+            }
 
             emit_match_return(&mut w, result_name, arms);
         }
@@ -430,6 +465,7 @@ pub fn emit_return_convert_typed(
                     let want_i64 = types.iter().any(|t| matches!(t, Type::I64));
                     let want_f32 = types.iter().any(|t| matches!(t, Type::F32));
                     let want_f64 = types.iter().any(|t| matches!(t, Type::F64));
+                    let want_bytes = types.iter().any(|t| matches!(t, Type::Bytes));
 
                     let mut inner_arms: Vec<String> = Vec::new();
                     if want_string {
@@ -462,6 +498,12 @@ pub fn emit_return_convert_typed(
                             ty = enum_ty
                         ));
                     }
+                    if want_bytes {
+                        inner_arms.push(format!(
+                            "{ty}::Bytes(b) => match unsafe {{ mquickjs_rs::handles::typed_array::new_uint8_array_raw(ctx, &b) }} {{ Ok(v) => v, Err(_) => js_throw_type_error(ctx, \"failed to create Uint8Array\") }}",
+                            ty = enum_ty
+                        ));
+                    }
 
                     let mut outer_arms: Vec<String> = Vec::new();
                     outer_arms.push("None => mquickjs_rs::mquickjs_ffi::JS_NULL".to_string());
@@ -485,6 +527,13 @@ pub fn emit_return_convert_typed(
                     let value_to_js = emit_value_to_js(cur, result_name)?;
                     w.push_line(value_to_js);
                 }
+                // `IntoJs for Option<T>` maps `None` to null.
+                Type::Custom(name) if !name.starts_with('(') => {
+                    emit_into_js_return(&mut w, result_name);
+                }
+                Type::Array(_) => {
+                    emit_into_js_return(&mut w, result_name);
+                }
                 other => {
                     return Err(askama::Error::Custom(
                         format!("v1 glue: unsupported return type: Optional({other:?})").into(),
@@ -502,6 +551,23 @@ pub fn emit_return_convert_typed(
     Ok(w.into_string())
 }
 
+/// Struct and array results go through `IntoJs` (which keeps the partly built object rooted),
+/// under a scope of their own so this works whether or not the glue opened one.
+fn emit_into_js_return(w: &mut CodeWriter, result_name: &str) {
+    emit_enter_own_scope(w);
+    w.push_line(format!(
+        "match mquickjs_rs::IntoJs::into_js({result_name}, &__ridl_scope) {{ Ok(v) => v.as_raw(), Err(e) => js_throw_type_error(ctx, &format!(\"failed to convert result: {{e}}\")) }}",
+        result_name = result_name
+    ));
+}
+
+fn emit_enter_own_scope(w: &mut CodeWriter) {
+    w.push_line(
+        "let Some(__ridl_h) = (unsafe { mquickjs_rs::context::ContextToken::from_js_ctx(ctx) }) else { return js_throw_type_error(ctx, \"missing ctx user_data (call ridl_context_init)\"); };",
+    );
+    w.push_line("let __ridl_scope = __ridl_h.enter_scope();");
+}
+
 pub fn is_readonly_prop(modifiers: &[PropertyModifier]) -> ::askama::Result<bool> {
     Ok(modifiers.contains(&PropertyModifier::ReadOnly))
 }
@@ -546,10 +612,76 @@ pub fn methods_total_filter(
     }
     for c in classes {
         total += c.methods.len();
+        total += c.overload_sets.len();
     }
     Ok(total)
 }
 
+/// Dispatch condition for one overload variant: required arity + runtime type checks.
+///
+/// There is no upper arity bound: extra trailing arguments are ignored, as for any JS
+/// function, and the dispatcher tries longer variants first (`TemplateOverloadSet::collect`).
+/// Must stay in sync with `validator::overload` (which rejects sets where two variants
+/// could match the same arguments). Types without a cheap runtime check
+/// (object/array/map/callback/any) match any value.
+pub fn emit_overload_match(
+    params: &[TemplateParam],
+    module_name_normalized: &str,
+) -> ::askama::Result<String> {
+    let required = params
+        .iter()
+        .filter(|p| p.default_literal.is_none() && !p.variadic)
+        .count();
+    let mut conds = vec![format!("argc >= {required}")];
+
+    for (i, p) in params.iter().enumerate() {
+        if p.variadic {
+            continue;
+        }
+        let v = format!("unsafe {{ *argv.add({i}) }}");
+        let Some(check) = overload_type_check_expr(&p.ty, &v, module_name_normalized) else {
+            continue;
+        };
+        if p.default_literal.is_some() {
+            conds.push(format!(
+                "(argc <= {i} || mquickjs_rs::mquickjs_ffi::js_value_special_tag({v}) == (mquickjs_rs::mquickjs_ffi::JS_TAG_UNDEFINED as u32) || {check})"
+            ));
+        } else {
+            conds.push(check);
+        }
+    }
+
+    Ok(conds.join("\n        && "))
+}
+
+fn overload_type_check_expr(ty: &Type, v: &str, module_name_normalized: &str) -> Option<String> {
+    let kind = format!("unsafe {{ mquickjs_rs::ValueKind::of_raw(ctx, {v}) }}");
+    match ty {
+        Type::I32 | Type::I64 | Type::F32 | Type::F64 => Some(format!("{kind}.is_number()")),
+        Type::String => Some(format!("{kind} == mquickjs_rs::ValueKind::String")),
+        Type::Bool => Some(format!("{kind} == mquickjs_rs::ValueKind::Bool")),
+        Type::Null => Some(format!("{kind} == mquickjs_rs::ValueKind::Null")),
+        Type::ClassRef(class_name) => Some(format!(
+            "{kind} == mquickjs_rs::ValueKind::RidlClass(mquickjs_rs::ridl_js_class_id::JS_CLASS_{}_{})",
+            module_name_normalized.to_ascii_uppercase(),
+            crate::generator::naming::to_upper_camel_case(class_name).to_ascii_uppercase()
+        )),
+        Type::Group(inner) => overload_type_check_expr(inner, v, module_name_normalized),
+        Type::Optional(inner) => {
+            let inner = overload_type_check_expr(inner, v, module_name_normalized)?;
+            Some(format!("({kind}.is_nullish() || {inner})"))
+        }
+        Type::Union(types) => {
+            let checks = types
+                .iter()
+                .map(|t| overload_type_check_expr(t, v, module_name_normalized))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("({})", checks.join(" || ")))
+        }
+        _ => None,
+    }
+}
+
 pub fn emit_setter_value_extract(prop: &crate::parser::ast::Property) -> ::askama::Result<String> {
     // Contract: setter takes exactly one argument at argv[0].
     // We intentionally do not use `this_val` here.
@@ -616,81 +748,19 @@ pub fn emit_param_extract(
 ) -> ::askama::Result<String> {
     let raw = if param.variadic {
         emit_varargs_collect(&param.rust_name, &param.ty, param.file_mode, *idx0)?
+    } else if let Some(default_literal) = &param.default_literal {
+        emit_param_extract_with_default(param, default_literal, *idx0, module_name_normalized)?
     } else if let Type::Optional(inner) = &param.ty {
         let mut w = CodeWriter::new();
         emit_missing_arg(&mut w, *idx1, &param.rust_name);
         emit_argv_v_let(&mut w, *idx0);
 
-        // V1: Optional(T) parameter decoding
-        // - null/undefined => None
-        // - otherwise decode as T (no implicit type conversions)
-        w.push_line(
-            "let __ridl_tag = mquickjs_rs::mquickjs_ffi::js_value_special_tag(v);".to_string(),
-        );
-        let opt_inner_ty = param
-            .rust_ty
-            .strip_prefix("Option<")
-            .and_then(|s| s.strip_suffix('>'))
-            .unwrap_or(param.rust_ty.as_str());
-
-        w.push_line(format!(
-            "let mut __ridl_opt_{name}: Option<{ty}> = None;",
-            name = param.rust_name,
-            ty = opt_inner_ty
-        ));
-
-        w.push_line(format!(
-            "if __ridl_tag == (mquickjs_rs::mquickjs_ffi::JS_TAG_NULL as u32) || __ridl_tag == (mquickjs_rs::mquickjs_ffi::JS_TAG_UNDEFINED as u32) {{"
-        ));
-        w.push_line(format!("__ridl_opt_{name} = None;", name = param.rust_name));
-        w.push_line("} else {".to_string());
-
-        // Decode inner into a local temp, then wrap Some(...)
-        let inner_name = format!("{name}_inner", name = param.rust_name);
-
-        // Reuse the already extracted JSValue `v` for optional inner decoding.
-        // (Do not emit another `let v = ...` shadowing; it would also lose the original JSValue.)
-        if matches!(inner.as_ref(), Type::Union(_)) {
-            // For Optional(Union), decode from the already-extracted `v`.
-            let inner_extract =
-                emit_union_param_extract_from_jsvalue(&inner_name, inner.as_ref(), opt_inner_ty)?;
-            for line in inner_extract.lines() {
-                w.push_line(line.to_string());
-            }
-        } else {
-            // For Optional(T), decode from the already-extracted `v`.
-            let inner_extract = emit_single_param_extract_from_jsvalue(
-                &inner_name,
-                inner.as_ref(),
-                module_name_normalized,
-            )?;
-            for line in inner_extract.lines() {
-                w.push_line(line.to_string());
-            }
+        let inner_extract =
+            emit_optional_param_extract_from_jsvalue(param, inner, module_name_normalized)?;
+        for line in inner_extract.lines() {
+            w.push_line(line);
         }
 
-        w.push_line(format!(
-            "    __ridl_opt_{name} = Some({inner_name});",
-            name = param.rust_name,
-            inner_name = inner_name
-        ));
-        w.push_line("}".to_string());
-
-        // Optional(T) decoding always yields `Option<Inner>`.
-        // For union types, rust_ty might have been overridden to `Enum` or `Option<Enum>`;
-        // here we must bind the final param as `Option<Enum>`.
-        let final_rust_ty = if param.rust_ty.starts_with("Option<") {
-            param.rust_ty.clone()
-        } else {
-            format!("Option<{}>", param.rust_ty)
-        };
-
-        w.push_line(format!(
-            "let {name}: {ty} = __ridl_opt_{name};",
-            name = param.rust_name,
-            ty = final_rust_ty
-        ));
-
         w.into_string()
     } else if matches!(&param.ty, Type::Union(_)) {
         emit_union_param_extract(
@@ -772,6 +842,7 @@ fn emit_union_param_extract_from_jsvalue(
     let want_i64 = types.iter().any(|t| matches!(t, Type::I64));
     let want_f32 = types.iter().any(|t| matches!(t, Type::F32));
     let want_f64 = types.iter().any(|t| matches!(t, Type::F64));
+    let want_bytes = types.iter().any(|t| matches!(t, Type::Bytes));
 
     w.push_line(format!(
         "let {name}: {rust_ty} = match (|| -> Result<{rust_ty}, JSValue> {{",
@@ -784,20 +855,18 @@ fn emit_union_param_extract_from_jsvalue(
 
     if want_string {
         w.push_line(
-            "if unsafe { mquickjs_rs::mquickjs_ffi::JS_IsString(ctx, v) } != 0 {".to_string(),
+            "if unsafe { mquickjs_rs::ValueKind::of_raw(ctx, v) } == mquickjs_rs::ValueKind::String {"
+                .to_string(),
         );
         w.indent();
         // Inline string conversion so error path can return `Result::Err(...)` from this closure.
-        w.push_line(
-            "let mut ptr_buf = mquickjs_rs::mquickjs_ffi::JSCStringBuf { buf: [0u8; 5] };"
-                .to_string(),
-        );
-        w.push_line("let ptr_ptr = unsafe { mquickjs_rs::mquickjs_ffi::JS_ToCString(ctx, v, &mut ptr_buf as *mut _) };".to_string());
-        w.push_line(format!("if ptr_ptr.is_null() {{ return Err(js_throw_type_error(ctx, \"invalid union argument: {name}\")); }}", name = name));
-        w.push_line("let ptr: *const core::ffi::c_char = ptr_ptr;".to_string());
-        w.push_line("let s = unsafe { core::ffi::CStr::from_ptr(ptr) };".to_string());
+        // Copied with its full length, so interior NULs survive (as for plain `string` params).
+        w.push_line(format!(
+            "let Some(s) = (unsafe {{ mquickjs_rs::handles::string::to_rust_string_raw(ctx, v) }}) else {{ return Err(js_throw_type_error(ctx, \"invalid union argument: {name}\")); }};",
+            name = name
+        ));
         w.push_line(format!(
-            "return Ok({rust_ty}::String(s.to_string_lossy().into_owned()));",
+            "return Ok({rust_ty}::String(s));",
             rust_ty = rust_ty
         ));
         w.dedent();
@@ -806,7 +875,7 @@ fn emit_union_param_extract_from_jsvalue(
 
     if want_i32 || want_i64 || want_f32 || want_f64 {
         w.push_line(
-            "if unsafe { mquickjs_rs::mquickjs_ffi::JS_IsNumber(ctx, v) } != 0 {".to_string(),
+            "if unsafe { mquickjs_rs::ValueKind::of_raw(ctx, v) }.is_number() {".to_string(),
         );
         w.indent();
 
@@ -855,6 +924,15 @@ fn emit_union_param_extract_from_jsvalue(
         w.push_line("}".to_string());
     }
 
+    if want_bytes {
+        // Typed arrays and ArrayBuffers; anything else fails without a pending exception.
+        w.push_line("if let Ok(b) = unsafe { mquickjs_rs::handles::typed_array::bytes_from_raw(ctx, v) } {");
+        w.indent();
+        w.push_line(format!("return Ok({rust_ty}::Bytes(b));", rust_ty = rust_ty));
+        w.dedent();
+        w.push_line("}");
+    }
+
     w.push_line(format!(
         "Err(js_throw_type_error(ctx, \"invalid union argument: {name}\"))",
         name = name
@@ -869,6 +947,178 @@ pub fn emit_call_arg(param: &TemplateParam) -> ::askama::Result<String> {
     Ok(param.rust_name.clone())
 }
 
+/// Escape `s` for use inside a Rust `"..."` literal (quotes, backslashes, control chars).
+pub fn escape_rust_str(s: &str) -> ::askama::Result<String> {
+    Ok(s.escape_debug().to_string())
+}
+
+/// Doc line listing RIDL default values, e.g. `/// Defaults: `port = 8080`.`
+///
+/// Emits a leading newline + indentation so the template site can stay on one line;
+/// returns an empty string when no param has a default.
+pub fn emit_param_defaults_doc(params: &[TemplateParam]) -> ::askama::Result<String> {
+    Ok(param_defaults_doc(params, "    "))
+}
+
+/// Same as `emit_param_defaults_doc` for top-level glue of global functions, whose impls
+/// (`crate::impls::<name>`) have no generated trait to carry the doc.
+pub fn emit_fn_defaults_doc(params: &[TemplateParam]) -> ::askama::Result<String> {
+    Ok(param_defaults_doc(params, ""))
+}
+
+fn param_defaults_doc(params: &[TemplateParam], indent: &str) -> String {
+    let defaults: Vec<String> = params
+        .iter()
+        .filter_map(|p| {
+            p.default_literal
+                .as_ref()
+                .map(|lit| format!("`{} = {}`", p.name, lit))
+        })
+        .collect();
+    if defaults.is_empty() {
+        return String::new();
+    }
+    format!("\n{indent}/// Defaults: {}.", defaults.join(", "))
+}
+
+/// Decode an `Optional(T)` parameter from the already-extracted JSValue `v`.
+fn emit_optional_param_extract_from_jsvalue(
+    param: &TemplateParam,
+    inner: &Type,
+    module_name_normalized: &str,
+) -> ::askama::Result<String> {
+    let mut w = CodeWriter::new();
+
+    // V1: Optional(T) parameter decoding
+    // - null/undefined => None
+    // - otherwise decode as T (no implicit type conversions)
+    w.push_line("let __ridl_kind = unsafe { mquickjs_rs::ValueKind::of_raw(ctx, v) };".to_string());
+    let opt_inner_ty = param
+        .rust_ty
+        .strip_prefix("Option<")
+        .and_then(|s| s.strip_suffix('>'))
+        .unwrap_or(param.rust_ty.as_str());
+
+    w.push_line(format!(
+        "let mut __ridl_opt_{name}: Option<{ty}> = None;",
+        name = param.rust_name,
+        ty = opt_inner_ty
+    ));
+
+    w.push_line("if __ridl_kind.is_nullish() {".to_string());
+    w.push_line(format!("__ridl_opt_{name} = None;", name = param.rust_name));
+    w.push_line("} else {".to_string());
+
+    // Decode inner into a local temp, then wrap Some(...)
+    let inner_name = format!("{name}_inner", name = param.rust_name);
+
+    // Reuse the already extracted JSValue `v` for optional inner decoding.
+    // (Do not emit another `let v = ...` shadowing; it would also lose the original JSValue.)
+    if matches!(inner, Type::Union(_)) {
+        // For Optional(Union), decode from the already-extracted `v`.
+        let inner_extract =
+            emit_union_param_extract_from_jsvalue(&inner_name, inner, opt_inner_ty)?;
+        for line in inner_extract.lines() {
+            w.push_line(line.to_string());
+        }
+    } else {
+        // For Optional(T), decode from the already-extracted `v`.
+        let inner_extract =
+            emit_single_param_extract_from_jsvalue(&inner_name, inner, module_name_normalized)?;
+        for line in inner_extract.lines() {
+            w.push_line(line.to_string());
+        }
+    }
+
+    w.push_line(format!(
+        "    __ridl_opt_{name} = Some({inner_name});",
+        name = param.rust_name,
+        inner_name = inner_name
+    ));
+    w.push_line("}".to_string());
+
+    // Optional(T) decoding always yields `Option<Inner>`.
+    // For union types, rust_ty might have been overridden to `Enum` or `Option<Enum>`;
+    // here we must bind the final param as `Option<Enum>`.
+    let final_rust_ty = if param.rust_ty.starts_with("Option<") {
+        param.rust_ty.clone()
+    } else {
+        format!("Option<{}>", param.rust_ty)
+    };
+
+    w.push_line(format!(
+        "let {name}: {ty} = __ridl_opt_{name};",
+        name = param.rust_name,
+        ty = final_rust_ty
+    ));
+
+    Ok(w.into_string())
+}
+
+/// Parameter with a RIDL default value (`port: i32 = 8080`).
+///
+/// Missing or `undefined` arguments take the default; anything else is decoded as usual
+/// (including `null` for nullable params). The impl always receives a concrete value.
+fn emit_param_extract_with_default(
+    param: &TemplateParam,
+    default_literal: &DefaultLiteral,
+    idx0: usize,
+    module_name_normalized: &str,
+) -> ::askama::Result<String> {
+    let mut w = CodeWriter::new();
+    let default_expr = default_value_rust_expr(&param.ty, default_literal)?;
+
+    w.push_line(format!(
+        "let {name}: {ty} = if argc <= {idx0} || mquickjs_rs::mquickjs_ffi::js_value_special_tag(unsafe {{ *argv.add({idx0}) }}) == (mquickjs_rs::mquickjs_ffi::JS_TAG_UNDEFINED as u32) {{",
+        name = param.rust_name,
+        ty = param.rust_ty,
+        idx0 = idx0
+    ));
+    w.push_line(default_expr);
+    w.push_line("} else {");
+    emit_argv_v_let(&mut w, idx0);
+
+    let inner_extract = if let Type::Optional(inner) = &param.ty {
+        emit_optional_param_extract_from_jsvalue(param, inner, module_name_normalized)?
+    } else {
+        emit_single_param_extract_from_jsvalue(&param.rust_name, &param.ty, module_name_normalized)?
+    };
+    for line in inner_extract.lines() {
+        w.push_line(line);
+    }
+
+    w.push_line(param.rust_name.clone());
+    w.push_line("};");
+
+    Ok(w.into_string())
+}
+
+/// Rust expression for a RIDL default literal (validated by the parser).
+pub(crate) fn default_value_rust_expr(
+    ty: &Type,
+    literal: &DefaultLiteral,
+) -> ::askama::Result<String> {
+    Ok(match (ty, literal) {
+        (Type::Optional(_), DefaultLiteral::Null) => "None".to_string(),
+        (Type::Optional(inner), _) => {
+            format!("Some({})", default_value_rust_expr(inner, literal)?)
+        }
+        (Type::String, DefaultLiteral::String(s)) => format!("String::from({:?})", s),
+        (Type::F32 | Type::F64, DefaultLiteral::Literal(text)) if !text.contains('.') => {
+            format!("{text}.0")
+        }
+        (
+            Type::Bool | Type::I32 | Type::I64 | Type::F32 | Type::F64,
+            DefaultLiteral::Literal(text),
+        ) => text.clone(),
+        (other, literal) => {
+            return Err(askama::Error::Custom(
+                format!("default value {literal} not supported for type {other}").into(),
+            ))
+        }
+    })
+}
+
 fn emit_missing_arg(w: &mut CodeWriter, idx1: usize, name: &str) {
     w.push_line(format!(
         "if argc < {idx1} {{ return js_throw_type_error(ctx, \"missing argument: {name}\"); }}",
@@ -893,7 +1143,7 @@ fn emit_argv_v_let(w: &mut CodeWriter, idx0: usize) {
 
 fn emit_check_is_string_expr(w: &mut CodeWriter, value_expr: &str, err_expr: &str) {
     w.push_line(format!(
-        "if unsafe {{ mquickjs_rs::mquickjs_ffi::JS_IsString(ctx, {v}) }} == 0 {{ return js_throw_type_error(ctx, {err}); }}",
+        "if unsafe {{ mquickjs_rs::ValueKind::of_raw(ctx, {v}) }} != mquickjs_rs::ValueKind::String {{ return js_throw_type_error(ctx, {err}); }}",
         v = value_expr,
         err = err_expr
     ));
@@ -901,7 +1151,7 @@ fn emit_check_is_string_expr(w: &mut CodeWriter, value_expr: &str, err_expr: &st
 
 fn emit_check_is_number_expr(w: &mut CodeWriter, value_expr: &str, err_expr: &str) {
     w.push_line(format!(
-        "if unsafe {{ mquickjs_rs::mquickjs_ffi::JS_IsNumber(ctx, {v}) }} == 0 {{ return js_throw_type_error(ctx, {err}); }}",
+        "if !unsafe {{ mquickjs_rs::ValueKind::of_raw(ctx, {v}) }}.is_number() {{ return js_throw_type_error(ctx, {err}); }}",
         v = value_expr,
         err = err_expr
     ));
@@ -1037,14 +1287,14 @@ fn emit_to_cstring_ptr_expr(w: &mut CodeWriter, value_expr: &str, name: &str, er
 
 fn emit_extract_bool_expr(w: &mut CodeWriter, value_expr: &str, name: &str, err_expr: &str) {
     w.push_line(format!(
-        "let {name}_v: u32 = {v} as u32;",
-        name = name,
-        v = value_expr
+        "if unsafe {{ mquickjs_rs::ValueKind::of_raw(ctx, {v}) }} != mquickjs_rs::ValueKind::Bool {{ return js_throw_type_error(ctx, {err}); }}",
+        v = value_expr,
+        err = err_expr
     ));
     w.push_line(format!(
-        "if ({name}_v & ((1 << mquickjs_rs::mquickjs_ffi::JS_TAG_SPECIAL_BITS) - 1)) != 3 {{ return js_throw_type_error(ctx, {err}); }}",
+        "let {name}_v: u32 = {v} as u32;",
         name = name,
-        err = err_expr
+        v = value_expr
     ));
     w.push_line(format!("let {name}: bool = {name}_v != 3;", name = name));
 }
@@ -1081,13 +1331,17 @@ fn emit_single_param_extract_from_jsvalue(
         Type::String => {
             let err = format!("invalid string argument: {name}");
             emit_check_is_string_expr(&mut w, "v", &format!("\"{}\"", err));
-            emit_to_cstring_ptr_expr(&mut w, "v", "ptr", &format!("\"{}\"", err));
-
-            // In this mquickjs fork, JS_ToCString returns a borrowed pointer.
-            // Convert immediately into an owned Rust String, truncating at NUL.
-            w.push_line("let s = unsafe { core::ffi::CStr::from_ptr(ptr) };".to_string());
+            // Copied with its full length, so interior NULs survive (binary strings, `atob`).
             w.push_line(format!(
-                "let {name}: String = s.to_string_lossy().into_owned();",
+                "let {name}: String = match unsafe {{ mquickjs_rs::handles::string::to_rust_string_raw(ctx, v) }} {{ Some(s) => s, None => return js_throw_type_error(ctx, \"{err}\") }};",
+                name = name,
+                err = err
+            ));
+        }
+        Type::Bytes => {
+            // Accepts any typed array or ArrayBuffer; contents are copied into a Vec<u8>.
+            w.push_line(format!(
+                "let {name}: Vec<u8> = match unsafe {{ mquickjs_rs::handles::typed_array::bytes_from_raw(ctx, v) }} {{ Ok(b) => b, Err(_) => return js_throw_type_error(ctx, \"invalid bytes argument: {name}\") }};",
                 name = name
             ));
         }
@@ -1122,6 +1376,30 @@ fn emit_single_param_extract_from_jsvalue(
                 name = name
             ));
         }
+        Type::Custom(struct_name) if !struct_name.starts_with('(') => {
+            // RIDL struct: decoded by its generated `FromJs` impl.
+            // Optional(T) decodes into `<name>_inner`; report the declared param name.
+            let param_name = name.strip_suffix("_inner").unwrap_or(name);
+            w.push_line(format!(
+                "let {name}: crate::api::{struct_name} = {{",
+                name = name,
+                struct_name = struct_name
+            ));
+            emit_enter_own_scope(&mut w);
+            w.push_line(format!(
+                "match <crate::api::{struct_name} as mquickjs_rs::FromJs>::from_js(&__ridl_scope, __ridl_scope.value(v)) {{ Ok(s) => s, Err(e) => return js_throw_type_error(ctx, &format!(\"invalid {struct_name} argument: {param_name}: {{e}}\")) }}",
+                param_name = param_name,
+                struct_name = struct_name
+            ));
+            w.push_line("};");
+        }
+        Type::Callback | Type::CallbackWithParams(_) => {
+            // Only checks callability; the declared callback signature is documentation.
+            w.push_line(format!(
+                "let Some({name}) = scope.value(v).as_function(&scope) else {{ return js_throw_type_error(ctx, \"invalid callback argument: {name}\") }};",
+                name = name
+            ));
+        }
         Type::Optional(inner) => {
             let mut cur: &Type = inner;
             while let Type::Group(g) = cur {
@@ -1134,7 +1412,7 @@ fn emit_single_param_extract_from_jsvalue(
                     // - null/undefined => None
                     // - otherwise => Some(scope.value(v))
                     w.push_line(format!(
-                        "let {name}: Option<mquickjs_rs::handles::local::Local<'_, mquickjs_rs::handles::local::Value>> = if __ridl_tag == (mquickjs_rs::mquickjs_ffi::JS_TAG_NULL as u32) || __ridl_tag == (mquickjs_rs::mquickjs_ffi::JS_TAG_UNDEFINED as u32) {{ None }} else {{ Some(scope.value(v)) }};",
+                        "let {name}: Option<mquickjs_rs::handles::local::Local<'_, mquickjs_rs::handles::local::Value>> = if __ridl_kind.is_nullish() {{ None }} else {{ Some(scope.value(v)) }};",
                         name = name
                     ));
                 }
@@ -1169,15 +1447,9 @@ fn emit_single_param_extract_from_jsvalue(
 
             let err_invalid = format!("invalid class argument: {name}");
 
-            // Only objects can have class_id/opaque.
-            // We avoid relying on C macro tags (bindgen may not expose them).
+            // Only instances of this class carry the expected opaque.
             w.push_line(format!(
-                "let __ridl_cid_{name} = unsafe {{ mquickjs_rs::mquickjs_ffi::JS_GetClassID(ctx, v) }};",
-                name = name
-            ));
-            w.push_line(format!(
-                "if __ridl_cid_{name} != mquickjs_rs::ridl_js_class_id::{cid} {{ return js_throw_type_error(ctx, \"{err}\"); }}",
-                name = name,
+                "if unsafe {{ mquickjs_rs::ValueKind::of_raw(ctx, v) }} != mquickjs_rs::ValueKind::RidlClass(mquickjs_rs::ridl_js_class_id::{cid}) {{ return js_throw_type_error(ctx, \"{err}\"); }}",
                 cid = class_id_const,
                 err = err_invalid
             ));
@@ -147,6 +147,7 @@ fn union_enum_path_for_ty(
                         "i64" => keys.push("I64"),
                         "f32" => keys.push("F32"),
                         "f64" => keys.push("F64"),
+                        "bytes" => keys.push("Bytes"),
                         _ => return None,
                     }
                 }
@@ -177,6 +178,7 @@ fn union_enum_path_for_ty(
                     Type::I64 => keys.push("I64"),
                     Type::F32 => keys.push("F32"),
                     Type::F64 => keys.push("F64"),
+                    Type::Bytes => keys.push("Bytes"),
                     Type::Null => nullable = true,
                     _ => {}
                 }
@@ -252,7 +254,7 @@ fn to_rust_type_ident_simple(name: &str) -> String {
 
 mod code_writer;
 mod filters;
-mod naming;
+pub(crate) mod naming;
 mod union_types;
 
 fn generate_register_h_and_symbols(
@@ -296,6 +298,7 @@ fn generate_register_h_and_symbols(
         let mut interfaces: Vec<TemplateInterface> = Vec::new();
         let mut classes: Vec<TemplateClass> = Vec::new();
         let mut singletons: Vec<TemplateSingleton> = Vec::new();
+        let mut constants: Vec<TemplateConst> = Vec::new();
 
         for item in parsed.items {
             match item {
@@ -339,19 +342,24 @@ fn generate_register_h_and_symbols(
                         .map(|m| m.module_path.as_str())
                         .unwrap_or("GLOBAL")
                         .to_string();
+                    let methods: Vec<TemplateMethod> = s
+                        .methods
+                        .into_iter()
+                        .map(|m| TemplateMethod::from_with_mode(m, parsed.mode))
+                        .collect();
                     singletons.push(TemplateSingleton {
+                        slot_key: singleton_aggregate::singleton_slot_key(&module_name, &s.name),
                         name: s.name,
                         module_name_normalized: crate::generator::filters::normalize_ident(
                             &module_name,
                         )
                         .unwrap_or_else(|_| "GLOBAL".to_string()),
                         module_name,
-                        methods: s
-                            .methods
-                            .into_iter()
-                            .map(|m| TemplateMethod::from_with_mode(m, parsed.mode))
-                            .collect(),
+                        overload_sets: TemplateOverloadSet::collect(&methods),
+                        methods,
                         properties: s.properties,
+                        js_fields: TemplateJsField::collect(s.js_fields),
+                        constants: TemplateConst::collect(&s.constants),
                     })
                 }
                 crate::parser::ast::IDLItem::Class(c) => {
@@ -365,6 +373,9 @@ fn generate_register_h_and_symbols(
                         parsed.mode,
                     ))
                 }
+                crate::parser::ast::IDLItem::Const(k) => {
+                    constants.push(TemplateConst::from_constant(&k))
+                }
                 _ => {}
             }
         }
@@ -402,6 +413,7 @@ fn generate_register_h_and_symbols(
             functions,
             singletons,
             classes,
+            constants,
         });
     }
 
@@ -535,6 +547,7 @@ struct RustGlueTemplate {
     functions: Vec<TemplateFunction>,
     singletons: Vec<TemplateSingleton>,
     classes: Vec<TemplateClass>,
+    structs: Vec<TemplateStruct>,
 }
 
 impl RustGlueLikeTemplate for RustGlueTemplate {
@@ -562,6 +575,9 @@ struct RustApiTemplate {
     functions: Vec<TemplateFunction>,
     singletons: Vec<TemplateSingleton>,
     classes: Vec<TemplateClass>,
+    // Module-level `const` declarations.
+    constants: Vec<TemplateConst>,
+    structs: Vec<TemplateStruct>,
 
     union_types_by_domain: Vec<TemplateUnionDomain>,
 }
@@ -635,15 +651,24 @@ pub(super) struct TemplateModule {
     functions: Vec<TemplateFunction>,
     singletons: Vec<TemplateSingleton>,
     pub(super) classes: Vec<TemplateClass>,
+    // Module-level `const` (module object props, or globalThis in global mode).
+    constants: Vec<TemplateConst>,
 }
 
 #[derive(Debug, Clone)]
 struct TemplateSingleton {
     name: String,
+    // `RidlSingleton::SLOT_KEY` (see `singleton_aggregate::singleton_slot_key`).
+    slot_key: String,
     module_name: String,
     module_name_normalized: String,
     methods: Vec<TemplateMethod>,
+    // JS entry points dispatching to overload variants (variants themselves are in `methods`).
+    overload_sets: Vec<TemplateOverloadSet>,
     properties: Vec<crate::parser::ast::Property>,
+    // `var` / `proto var`: installed on the singleton object by `ridl_context_init`.
+    js_fields: Vec<TemplateJsField>,
+    constants: Vec<TemplateConst>,
 }
 
 #[derive(Debug, Clone)]
@@ -665,8 +690,10 @@ pub(super) struct TemplateClass {
     pub(super) class_id: u32,
     constructor: Option<TemplateFunction>,
     methods: Vec<TemplateMethod>,
+    overload_sets: Vec<TemplateOverloadSet>,
     properties: Vec<crate::parser::ast::Property>,
     js_fields: Vec<TemplateJsField>,
+    constants: Vec<TemplateConst>,
 }
 
 #[derive(Debug, Clone)]
@@ -677,6 +704,137 @@ struct TemplateJsField {
     is_proto: bool,
 }
 
+impl TemplateJsField {
+    fn collect(fields: Vec<crate::parser::ast::JsField>) -> Vec<Self> {
+        fields
+            .into_iter()
+            .map(|f| TemplateJsField {
+                name: f.name,
+                field_type: f.field_type,
+                init_literal: f.init_literal,
+                is_proto: f
+                    .modifiers
+                    .contains(&crate::parser::ast::PropertyModifier::Proto),
+            })
+            .collect()
+    }
+}
+
+/// RIDL `const`, pre-rendered for the ROM property tables and `api.rs`.
+#[derive(Debug, Clone)]
+struct TemplateConst {
+    // UPPER_SNAKE_CASE name of the Rust `const` in api.rs.
+    rust_name: String,
+    rust_ty: String,
+    rust_value: String,
+    // JSPropDef entry (without trailing comma). Numbers/strings are ROM values;
+    // bools use the shared `js_ridl_const_true/false` getters (no ROM bool prop kind).
+    rom_def: String,
+}
+
+impl TemplateConst {
+    fn from_constant(c: &crate::parser::ast::Constant) -> Self {
+        let rust_name = crate::generator::naming::to_snake_case(&c.name).to_uppercase();
+        let (rust_ty, rust_value, rom_def) = match &c.const_type {
+            Type::String => (
+                "&str".to_string(),
+                format!("{:?}", c.value),
+                format!(
+                    "JS_PROP_STRING_DEF(\"{}\", {}, 0)",
+                    c.name,
+                    c_string_literal(&c.value)
+                ),
+            ),
+            Type::Bool => (
+                "bool".to_string(),
+                c.value.clone(),
+                format!(
+                    "JS_CGETSET_DEF(\"{}\", js_ridl_const_{}, NULL)",
+                    c.name, c.value
+                ),
+            ),
+            ty => {
+                let rust_ty = crate::generator::filters::rust_type_from_idl(ty)
+                    .unwrap_or_else(|_| "f64".to_string());
+                let mut rust_value = c.value.clone();
+                if matches!(ty, Type::F32 | Type::F64) && !rust_value.contains('.') {
+                    rust_value.push_str(".0");
+                }
+                let rom_def = format!("JS_PROP_DOUBLE_DEF(\"{}\", {}, 0)", c.name, rust_value);
+                (rust_ty, rust_value, rom_def)
+            }
+        };
+        Self {
+            rust_name,
+            rust_ty,
+            rust_value,
+            rom_def,
+        }
+    }
+
+    fn collect(constants: &[crate::parser::ast::Constant]) -> Vec<Self> {
+        constants.iter().map(Self::from_constant).collect()
+    }
+}
+
+/// RIDL `struct`: a plain Rust struct in api.rs, passed to/from JS as a plain object.
+#[derive(Debug, Clone)]
+struct TemplateStruct {
+    name: String,
+    fields: Vec<TemplateStructField>,
+}
+
+#[derive(Debug, Clone)]
+struct TemplateStructField {
+    // JS property name (as declared in RIDL).
+    name: String,
+    rust_name: String,
+    rust_ty: String,
+}
+
+impl TemplateStruct {
+    fn from_struct_def(s: &crate::parser::ast::StructDef) -> Self {
+        let fields = s
+            .fields
+            .iter()
+            .map(|f| TemplateStructField {
+                name: f.name.clone(),
+                rust_name: crate::generator::filters::rust_ident(
+                    &crate::generator::naming::to_snake_case(&f.name),
+                )
+                .unwrap_or_else(|_| "_".to_string()),
+                rust_ty: crate::generator::filters::rust_type_from_idl(&f.field_type)
+                    .unwrap_or_else(|_| "()".to_string()),
+            })
+            .collect();
+        Self {
+            name: s.name.clone(),
+            fields,
+        }
+    }
+}
+
+/// Quote `s` as a C string literal (control chars as octal escapes; UTF-8 passes through).
+fn c_string_literal(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+                out.push_str(&format!("\\{:03o}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 #[derive(Debug, Clone)]
 struct TemplateMethod {
     name: String,
@@ -685,6 +843,62 @@ struct TemplateMethod {
     return_rust_ty: String,
     has_variadic: bool,
     needs_scope: bool,
+    // Overload variant: JS-visible name of the overload set (`name` is the Rust variant name).
+    overload_of: Option<String>,
+}
+
+/// One JS-visible method backed by several overload variants.
+///
+/// Glue emits a dispatcher `js_..._<name>` that tries variants (required arity + runtime type
+/// checks) and forwards to the variant glue `js_..._<variant>`. Variants are ordered by how many
+/// arguments they take, most first: a call with extra trailing arguments then reaches the
+/// variant that uses the most of them, while calls within a variant's arity range match the same
+/// variant as in declaration order (the validator rejects overlapping variants).
+#[derive(Debug, Clone)]
+struct TemplateOverloadSet {
+    name: String,
+    // JS `Function.length`: the smallest required arity among variants.
+    length: usize,
+    variants: Vec<TemplateMethod>,
+}
+
+impl TemplateOverloadSet {
+    fn collect(methods: &[TemplateMethod]) -> Vec<Self> {
+        let mut sets: Vec<Self> = Vec::new();
+        for m in methods {
+            let Some(js_name) = &m.overload_of else {
+                continue;
+            };
+            let required = m
+                .params
+                .iter()
+                .filter(|p| p.default_literal.is_none() && !p.variadic)
+                .count();
+            match sets.iter_mut().find(|s| &s.name == js_name) {
+                Some(set) => {
+                    set.length = set.length.min(required);
+                    set.variants.push(m.clone());
+                }
+                None => sets.push(Self {
+                    name: js_name.clone(),
+                    length: required,
+                    variants: vec![m.clone()],
+                }),
+            }
+        }
+        for set in &mut sets {
+            // Stable: same-arity variants keep declaration order.
+            set.variants.sort_by_key(|m| {
+                let max = if m.params.iter().any(|p| p.variadic) {
+                    usize::MAX
+                } else {
+                    m.params.len()
+                };
+                std::cmp::Reverse(max)
+            });
+        }
+        sets
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -693,7 +907,11 @@ pub(crate) struct TemplateParam {
     pub(crate) rust_name: String,
     pub(crate) ty: Type,
     pub(crate) variadic: bool,
+    // `callback(...)`: passed to the impl as `Local<'ctx, Function>`.
+    pub(crate) is_callback: bool,
     pub(crate) file_mode: crate::parser::FileMode,
+    // RIDL default value literal (`port: i32 = 8080`); glue substitutes it for missing/undefined args.
+    pub(crate) default_literal: Option<crate::parser::ast::DefaultLiteral>,
 
     // Filled during template construction.
     // For union types this will be a fully qualified path under `crate::api::{domain}::union::*`.
@@ -708,6 +926,8 @@ struct TemplateFunction {
     params: Vec<TemplateParam>,
     return_type: Type,
     return_rust_ty: String,
+    // Glue opens a scope and passes `&mut Env` first (see `needs_scope`).
+    needs_scope: bool,
 }
 
 impl TemplateInterface {
@@ -739,15 +959,7 @@ impl TemplateMethod {
             .collect();
 
         let has_variadic = params.iter().any(|p| p.variadic);
-
-        fn is_any_like(ty: &Type) -> bool {
-            matches!(ty, Type::Any)
-                || matches!(ty, Type::Optional(inner) if matches!(inner.as_ref(), Type::Any))
-        }
-
-        let needs_scope = params.iter().any(|p| is_any_like(&p.ty))
-            || (has_variadic && params.iter().any(|p| p.variadic && is_any_like(&p.ty)))
-            || is_any_like(&method.return_type);
+        let needs_scope = method.needs_env || needs_scope(&params, &method.return_type);
 
         let return_type = method.return_type;
         let mut return_rust_ty = crate::generator::filters::rust_type_from_idl(&return_type)
@@ -773,10 +985,35 @@ impl TemplateMethod {
             return_rust_ty,
             has_variadic,
             needs_scope,
+            overload_of: method.overload_of,
         }
     }
 }
 
+/// `any` values and callbacks are views into the JS heap: glue has to open a `Scope`/`Env`
+/// and the impl receives `&mut Env<'ctx>` to bind their lifetime.
+///
+/// Declarations marked `[env]` get the `Env` regardless, for impls that need the calling
+/// context itself (its host data, clock, ...).
+fn needs_scope(params: &[TemplateParam], return_type: &Type) -> bool {
+    fn is_any_like(ty: &Type) -> bool {
+        matches!(ty, Type::Any)
+            || matches!(ty, Type::Optional(inner) if matches!(inner.as_ref(), Type::Any))
+    }
+    fn is_callback_like(ty: &Type) -> bool {
+        match ty {
+            Type::Callback | Type::CallbackWithParams(_) => true,
+            Type::Optional(inner) => is_callback_like(inner),
+            _ => false,
+        }
+    }
+
+    params
+        .iter()
+        .any(|p| is_any_like(&p.ty) || is_callback_like(&p.ty))
+        || is_any_like(return_type)
+}
+
 impl TemplateParam {
     fn from_with_mode(param: Param, file_mode: crate::parser::FileMode) -> Self {
         let ty = param.param_type;
@@ -787,12 +1024,16 @@ impl TemplateParam {
         )
         .unwrap_or_else(|_| "_".to_string());
 
+        let is_callback = matches!(ty, Type::Callback | Type::CallbackWithParams(_));
+
         Self {
             name: param.name,
             rust_name,
             ty,
             variadic: param.variadic,
+            is_callback,
             file_mode,
+            default_literal: param.default_literal,
             rust_ty,
         }
     }
@@ -811,8 +1052,16 @@ impl TemplateFunction {
             .collect();
 
         let return_type = function.return_type;
-        let return_rust_ty = crate::generator::filters::rust_type_from_idl(&return_type)
+        let needs_scope = function.needs_env || needs_scope(&params, &return_type);
+        let mut return_rust_ty = crate::generator::filters::rust_type_from_idl(&return_type)
             .unwrap_or_else(|_| "()".to_string());
+        // Same rule as methods: `any` returns are owned `ReturnAny`, pinned by the glue.
+        if matches!(return_type, Type::Any) {
+            return_rust_ty = "mquickjs_rs::handles::return_safe::ReturnAny".to_string();
+        }
+        if matches!(return_type, Type::Optional(ref inner) if matches!(inner.as_ref(), Type::Any)) {
+            return_rust_ty = "Option<mquickjs_rs::handles::return_safe::ReturnAny>".to_string();
+        }
 
         Self {
             name: function.name,
@@ -820,6 +1069,7 @@ impl TemplateFunction {
             params,
             return_type,
             return_rust_ty,
+            needs_scope,
         }
     }
 }
@@ -832,6 +1082,12 @@ impl TemplateClass {
         file_mode: crate::parser::FileMode,
     ) -> Self {
         let module_name_normalized_cloned = module_name_normalized.clone();
+        let methods: Vec<TemplateMethod> = class
+            .methods
+            .into_iter()
+            .map(|m| TemplateMethod::from_with_mode(m, file_mode))
+            .collect();
+        let constants = TemplateConst::collect(&class.constants);
 
         Self {
             module_name,
@@ -845,24 +1101,11 @@ impl TemplateClass {
                     module_name_normalized_cloned.clone(),
                 )
             }),
-            methods: class
-                .methods
-                .into_iter()
-                .map(|m| TemplateMethod::from_with_mode(m, file_mode))
-                .collect(),
+            overload_sets: TemplateOverloadSet::collect(&methods),
+            methods,
             properties: class.properties,
-            js_fields: class
-                .js_fields
-                .into_iter()
-                .map(|f| TemplateJsField {
-                    name: f.name,
-                    field_type: f.field_type,
-                    init_literal: f.init_literal,
-                    is_proto: f
-                        .modifiers
-                        .contains(&crate::parser::ast::PropertyModifier::Proto),
-                })
-                .collect(),
+            js_fields: TemplateJsField::collect(class.js_fields),
+            constants,
         }
     }
 }
@@ -886,6 +1129,7 @@ pub fn collect_definitions(ridl_files: &[String]) -> Result<Vec<IDL>, Box<dyn st
         let mut using = Vec::new();
         let mut imports = Vec::new();
         let mut singletons = Vec::new();
+        let mut constants = Vec::new();
         let module = None;
 
         for item in items {
@@ -902,6 +1146,7 @@ pub fn collect_definitions(ridl_files: &[String]) -> Result<Vec<IDL>, Box<dyn st
                     s.module = module.clone();
                     singletons.push(s)
                 }
+                crate::parser::ast::IDLItem::Const(c) => constants.push(c),
             }
         }
 
@@ -915,6 +1160,7 @@ pub fn collect_definitions(ridl_files: &[String]) -> Result<Vec<IDL>, Box<dyn st
             using,
             imports,
             singletons,
+            constants,
             module,
         };
 
@@ -934,6 +1180,8 @@ pub fn generate_module_files(
     let mut functions = Vec::new();
     let mut interfaces = Vec::new();
     let mut classes = Vec::new();
+    let mut constants = Vec::new();
+    let mut structs = Vec::new();
 
     for item in items {
         match item {
@@ -976,6 +1224,12 @@ pub fn generate_module_files(
                     file_mode,
                 ))
             }
+            crate::parser::ast::IDLItem::Const(k) => {
+                constants.push(TemplateConst::from_constant(k))
+            }
+            crate::parser::ast::IDLItem::Struct(st) => {
+                structs.push(TemplateStruct::from_struct_def(st))
+            }
             // 其他类型暂不处理，可根据需要添加
             _ => {}
         }
@@ -993,20 +1247,25 @@ pub fn generate_module_files(
                 .map(|m| m.module_path.as_str())
                 .unwrap_or("GLOBAL")
                 .to_string();
+            let methods: Vec<TemplateMethod> = s
+                .methods
+                .clone()
+                .into_iter()
+                .map(|m| TemplateMethod::from_with_mode(m, file_mode))
+                .collect();
             singletons.push(TemplateSingleton {
                 name: s.name.clone(),
+                slot_key: singleton_aggregate::singleton_slot_key(&singleton_module_name, &s.name),
                 module_name_normalized: crate::generator::filters::normalize_ident(
                     &singleton_module_name,
                 )
                 .unwrap_or_else(|_| "GLOBAL".to_string()),
                 module_name: singleton_module_name,
-                methods: s
-                    .methods
-                    .clone()
-                    .into_iter()
-                    .map(|m| TemplateMethod::from_with_mode(m, file_mode))
-                    .collect(),
+                overload_sets: TemplateOverloadSet::collect(&methods),
+                methods,
                 properties: s.properties.clone(),
+                js_fields: TemplateJsField::collect(s.js_fields.clone()),
+                constants: TemplateConst::collect(&s.constants),
             });
         }
     }
@@ -1018,6 +1277,7 @@ pub fn generate_module_files(
         functions: functions.clone(),
         singletons,
         classes: classes.clone(),
+        structs: structs.clone(),
     };
 
     let union_types = collect_union_types(
@@ -1043,6 +1303,8 @@ pub fn generate_module_files(
         functions: functions.clone(),
         singletons: rust_glue_template.singletons.clone(),
         classes: classes.clone(),
+        constants,
+        structs,
         union_types_by_domain: group_union_types_by_domain(union_types.clone()),
     };
 
@@ -9,6 +9,8 @@ use std::path::Path;
 struct RidlContextExtTemplate {
     slots: Vec<Slot>,
     slot_inits: Vec<SlotInit>,
+    // `<crate>::ridl_install_<singleton>_js_fields` for singletons declaring `var` / `proto var`.
+    js_field_installers: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +27,10 @@ struct SlotInit {
     slot_index: u32,
     vt_ident: String,
     slot_key: String,
+    // Singleton slots may be filled by a host override (`ContextBuilder::singleton`).
+    is_singleton: bool,
+    // JS name of a global-mode singleton (hidden per context by `Permissions`); empty otherwise.
+    global_name: String,
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +92,7 @@ pub(super) fn generate_ridl_context_ext(
     // Slot indices are global within an app aggregate.
     let mut slots: Vec<Slot> = Vec::new();
     let mut slot_inits: Vec<SlotInit> = Vec::new();
+    let mut js_field_installers: Vec<String> = Vec::new();
     // Temporary: used for mapping slot_key -> final sorted index (filled after sorting).
     let mut slot_map: BTreeMap<String, u32> = BTreeMap::new();
     let mut proto_keys: BTreeMap<String, String> = BTreeMap::new();
@@ -110,9 +117,8 @@ pub(super) fn generate_ridl_context_ext(
                             .as_ref()
                             .map(|m| m.module_path.as_str())
                             .unwrap_or("GLOBAL");
-                        let module_ns = sanitize_ident(module_ns).to_lowercase();
 
-                        let slot_key = format!("singleton_{}_{}", module_ns, name.to_lowercase());
+                        let slot_key = singleton_slot_key(module_ns, &s.name);
                         let slot_index = *slot_map.entry(slot_key.clone()).or_insert_with(|| {
                             let next = slots.len() as u32;
                             slots.push(Slot {
@@ -130,7 +136,21 @@ pub(super) fn generate_ridl_context_ext(
                                 crate::generator::naming::to_snake_case(&name).to_uppercase()
                             ),
                             slot_key: slot_key.clone(),
+                            is_singleton: true,
+                            global_name: if parsed.module.is_none() {
+                                s.name.clone()
+                            } else {
+                                String::new()
+                            },
                         });
+
+                        if !s.js_fields.is_empty() {
+                            js_field_installers.push(format!(
+                                "{}::ridl_install_{}_js_fields",
+                                m.crate_name,
+                                crate::generator::naming::to_snake_case(&name)
+                            ));
+                        }
                     }
                     crate::parser::ast::IDLItem::Class(c) => {
                         // Only generate proto backing when the class has at least one proto property.
@@ -208,6 +228,8 @@ pub(super) fn generate_ridl_context_ext(
                                 crate::generator::naming::to_snake_case(&c.name).to_uppercase()
                             ),
                             slot_key: field_name,
+                            is_singleton: false,
+                            global_name: String::new(),
                         });
                     }
                     _ => {}
@@ -250,11 +272,25 @@ pub(super) fn generate_ridl_context_ext(
         }
     }
 
-    let t = RidlContextExtTemplate { slots, slot_inits };
+    let t = RidlContextExtTemplate {
+        slots,
+        slot_inits,
+        js_field_installers,
+    };
     std::fs::write(out_dir.join("ridl_context_ext.rs"), t.render()?)?;
     Ok(())
 }
 
+/// Ctx slot key of a singleton; also the `RidlSingleton::SLOT_KEY` emitted into the module's
+/// api.rs, which host overrides are matched by.
+pub(crate) fn singleton_slot_key(module_path: &str, name: &str) -> String {
+    format!(
+        "singleton_{}_{}",
+        sanitize_ident(module_path).to_lowercase(),
+        sanitize_ident(name).to_lowercase()
+    )
+}
+
 fn sanitize_ident(name: &str) -> String {
     // Keep it simple: allow [A-Za-z_][A-Za-z0-9_]*; otherwise map to underscores.
     // Also avoid Rust keywords minimally.
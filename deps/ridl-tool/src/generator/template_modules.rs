@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use crate::parser;
 
 use super::{
-    TemplateClass, TemplateFunction, TemplateInterface, TemplateModule, TemplateSingleton,
+    TemplateClass, TemplateConst, TemplateFunction, TemplateInterface, TemplateModule, TemplateSingleton,
 };
 
 pub(super) fn build_template_modules(
@@ -33,6 +33,7 @@ pub(super) fn build_template_modules(
         let mut functions: Vec<TemplateFunction> = Vec::new();
         let singletons: Vec<TemplateSingleton> = Vec::new();
         let mut local_classes: Vec<TemplateClass> = Vec::new();
+        let mut constants: Vec<TemplateConst> = Vec::new();
 
         for item in &parsed.items {
             match item {
@@ -62,6 +63,7 @@ pub(super) fn build_template_modules(
                         parsed.mode,
                     ))
                 }
+                parser::ast::IDLItem::Const(k) => constants.push(TemplateConst::from_constant(k)),
                 _ => {}
             }
         }
@@ -99,6 +101,7 @@ pub(super) fn build_template_modules(
             functions,
             singletons,
             classes: local_classes,
+            constants,
         });
     }
 
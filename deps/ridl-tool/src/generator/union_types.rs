@@ -141,6 +141,10 @@ fn map_union_member(ty: &Type) -> Option<TemplateUnionMember> {
             variant: "F64".to_string(),
             rust_ty: "f64".to_string(),
         }),
+        Type::Bytes => Some(TemplateUnionMember {
+            variant: "Bytes".to_string(),
+            rust_ty: "Vec<u8>".to_string(),
+        }),
         // Keep narrow for now; other complex member types will be added in V1-B2.
         other => {
             let _ = rust_type_from_idl(other);
@@ -198,6 +202,7 @@ fn union_name_from_members(member_types: &[Type]) -> String {
             Type::I64 => keys.push("I64"),
             Type::F32 => keys.push("F32"),
             Type::F64 => keys.push("F64"),
+            Type::Bytes => keys.push("Bytes"),
             _ => {}
         }
     }
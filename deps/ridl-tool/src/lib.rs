@@ -2,6 +2,7 @@
 //!
 //! 用于将IDL定义转换为Rust胶水代码和C绑定代码的工具
 
+pub mod compat;
 pub mod generator;
 pub mod parser;
 pub mod plan;
@@ -1,6 +1,6 @@
 use std::{env, path::Path};
 
-use ridl_tool::{generator, parser, validator};
+use ridl_tool::{compat, generator, parser, validator};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
@@ -9,6 +9,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         eprintln!("Usage: {} <command> [args...]", args[0]);
         eprintln!("Commands:");
         eprintln!("  module <ridl-files...> <output-dir> - Generate module-specific files");
+        eprintln!(
+            "  compat <old.ridl> <new.ridl> - Check API compatibility against the version bump"
+        );
         std::process::exit(1);
     }
 
@@ -58,6 +61,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )?;
             }
         }
+        "compat" => {
+            if remaining_args.len() != 2 {
+                eprintln!("Usage: {} compat <old.ridl> <new.ridl>", args[0]);
+                std::process::exit(1);
+            }
+
+            let old_src = std::fs::read_to_string(remaining_args[0])?;
+            let new_src = std::fs::read_to_string(remaining_args[1])?;
+            let report = compat::compare_sources(&old_src, &new_src)?;
+
+            for change in &report.changes {
+                println!("{}", change);
+            }
+            match report.required_bump() {
+                Some(level) => println!("required bump: {}", level),
+                None => println!("no API changes"),
+            }
+
+            if let Err(e) = report.check() {
+                eprintln!("error: {}", e);
+                std::process::exit(2);
+            }
+        }
         _ => {
             eprintln!("Unknown command: {}", command);
             std::process::exit(1);
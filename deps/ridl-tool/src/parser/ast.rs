@@ -27,6 +27,7 @@ pub struct IDL {
     pub imports: Vec<Import>,
     pub singletons: Vec<Singleton>,
     pub callbacks: Vec<Function>, // 为简单起见，将回调作为函数处理
+    pub constants: Vec<Constant>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -39,6 +40,7 @@ pub enum IDLItem {
     Using(Using),
     Import(Import),
     Singleton(Singleton),
+    Const(Constant),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -58,6 +60,8 @@ pub struct Class {
     pub methods: Vec<Method>,
     pub properties: Vec<Property>,
     pub js_fields: Vec<JsField>,
+    #[serde(default)]
+    pub constants: Vec<Constant>,
     pub module: Option<ModuleDeclaration>,
 }
 
@@ -83,6 +87,11 @@ pub struct Singleton {
     pub pos: Option<SourcePos>,
     pub methods: Vec<Method>,
     pub properties: Vec<Property>,
+    /// JS-only 字段（`var` / `proto var`），在 `ridl_context_init` 时安装到 singleton 对象上。
+    #[serde(default)]
+    pub js_fields: Vec<JsField>,
+    #[serde(default)]
+    pub constants: Vec<Constant>,
     pub module: Option<ModuleDeclaration>,
 }
 
@@ -93,6 +102,9 @@ pub struct Function {
     pub return_type: Type,
     pub is_async: bool,
     pub module: Option<ModuleDeclaration>,
+    /// `[env]`: the impl receives `&mut Env` even without `any` / callback parameters.
+    #[serde(default)]
+    pub needs_env: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -101,6 +113,13 @@ pub struct Method {
     pub params: Vec<Param>,
     pub return_type: Type,
     pub is_async: bool,
+    /// Set for overload variants: the JS-visible method name shared by the overload set.
+    /// `name` then holds the per-variant Rust name (see `parser::overload`).
+    #[serde(default)]
+    pub overload_of: Option<String>,
+    /// `[env]`, as on `Function`.
+    #[serde(default)]
+    pub needs_env: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -108,6 +127,9 @@ pub struct Property {
     pub modifiers: Vec<PropertyModifier>,
     pub name: String,
     pub property_type: Type,
+    /// `[env] readonly property`: the singleton getter receives `&mut Env` (singletons only).
+    #[serde(default)]
+    pub needs_env: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -117,6 +139,20 @@ pub enum PropertyModifier {
     Proto,
 }
 
+/// `const NAME: type = literal;`：只读常量（模块级、singleton 或 class 成员）。
+///
+/// 常量直接写入 ROM 属性表，不在 context 创建时分配堆内存。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Constant {
+    pub name: String,
+    #[serde(default)]
+    pub pos: Option<SourcePos>,
+    pub const_type: Type,
+    /// 字面量文本；字符串字面量保存解码后的内容。
+    pub value: String,
+    pub module: Option<ModuleDeclaration>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum JsFieldKind {
     Var,
@@ -153,6 +189,29 @@ pub struct Param {
     pub param_type: Type,
     pub optional: bool,
     pub variadic: bool,
+    /// Default value literal (`port: i32 = 8080`).
+    #[serde(default)]
+    pub default_literal: Option<DefaultLiteral>,
+}
+
+/// Parameter default value. The literal kind is kept, so `= null` and `= "null"` differ.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DefaultLiteral {
+    Null,
+    /// String literal, stored decoded.
+    String(String),
+    /// Bool or number literal, as written.
+    Literal(String),
+}
+
+impl fmt::Display for DefaultLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefaultLiteral::Null => write!(f, "null"),
+            DefaultLiteral::String(s) => write!(f, "{:?}", s),
+            DefaultLiteral::Literal(text) => write!(f, "{}", text),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -190,6 +249,8 @@ pub enum Type {
     F32,
     F64,
     String,
+    /// Binary data: `Uint8Array` / `ArrayBuffer` on the JS side, `Vec<u8>` in Rust.
+    Bytes,
     Void,
     Object,
     Array(Box<Type>),
@@ -219,6 +280,7 @@ impl fmt::Display for Type {
             Type::F32 => write!(f, "f32"),
             Type::F64 => write!(f, "f64"),
             Type::String => write!(f, "string"),
+            Type::Bytes => write!(f, "bytes"),
             Type::Void => write!(f, "void"),
             Type::Object => write!(f, "object"),
             Type::Array(t) => write!(f, "array<{}>", t),
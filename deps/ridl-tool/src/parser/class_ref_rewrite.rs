@@ -47,7 +47,7 @@ pub fn rewrite_item_class_refs(item: &mut IDLItem, class_names: &HashSet<String>
                 rewrite_method_class_refs(m, class_names);
             }
         }
-        IDLItem::Enum(_) | IDLItem::Using(_) | IDLItem::Import(_) => {}
+        IDLItem::Enum(_) | IDLItem::Using(_) | IDLItem::Import(_) | IDLItem::Const(_) => {}
     }
 }
 
@@ -91,6 +91,7 @@ fn rewrite_type_class_refs(ty: &mut Type, class_names: &HashSet<String>) {
         | Type::F32
         | Type::F64
         | Type::String
+        | Type::Bytes
         | Type::Void
         | Type::Object
         | Type::Callback
@@ -8,16 +8,18 @@ pub struct IDLParser;
 
 pub mod ast;
 mod class_ref_rewrite;
-mod normalize;
+pub mod normalize;
+pub mod overload;
 
-pub mod require_spec;
+pub use ridl_require_spec as require_spec;
 
 use ast::{
-    Class, Enum, EnumValue, Field, Function, IDLItem, Interface, Method, ModuleDeclaration, Param,
+    Class, Constant, DefaultLiteral, Enum, EnumValue, Field, Function, IDLItem, Interface, Method, ModuleDeclaration, Param,
     Property, PropertyModifier, SerializationFormat, StructDef, Type,
 };
 use class_ref_rewrite::rewrite_item_class_refs;
-use normalize::normalize_idl_items;
+use normalize::{normalize_idl_items, normalize_type};
+use overload::resolve_item_overloads;
 
 fn pair_pos(pair: &pest::iterators::Pair<Rule>) -> ast::SourcePos {
     let (line, column) = pair.line_col();
@@ -95,6 +97,12 @@ pub fn parse_idl_file(content: &str) -> Result<ParsedIDL, Box<dyn std::error::Er
         }
     }
 
+    // Same-name methods on singletons/classes form an overload set: give each variant a
+    // distinct Rust name (after class_ref rewrite, since the name is derived from param types).
+    for it in &mut items {
+        resolve_item_overloads(it);
+    }
+
     Ok(ParsedIDL {
         module,
         mode,
@@ -125,6 +133,20 @@ pub fn parse_ridl_file(content: &str) -> Result<ParsedIDL, Box<dyn std::error::E
     parse_idl_file(content)
 }
 
+/// 解析单独的类型表达式（如 `array<string>`、`i32 | string | null`、`Config?`），
+/// 结果按 `normalize::normalize_type` 规范化，与 IDL 文件中同一写法得到的类型一致。
+///
+/// 命名类型保持为 `Type::Custom`（没有 IDL 上下文可改写为 `ClassRef`）。
+pub fn parse_type_expr(content: &str) -> Result<Type, Box<dyn std::error::Error>> {
+    let mut pairs =
+        IDLParser::parse(Rule::type_expr, content).map_err(|e| format!("Parse error: {}", e))?;
+    let type_pair = pairs
+        .next()
+        .and_then(|p| p.into_inner().find(|p| p.as_rule() == Rule::r#type))
+        .ok_or("Type expression is empty")?;
+    normalize_type(parse_type(type_pair)?)
+}
+
 fn decode_ridl_string_literal(
     pos: &crate::parser::ast::SourcePos,
     raw: &str,
@@ -341,6 +363,11 @@ fn parse_definition_content(
             singleton.module = module;
             Ok(IDLItem::Singleton(singleton))
         }
+        Rule::const_def => {
+            let mut constant = parse_const(pair)?;
+            constant.module = module;
+            Ok(IDLItem::Const(constant))
+        }
         _ => Err(format!("Unexpected definition content: {:?}", pair.as_rule()).into()),
     }
 }
@@ -394,6 +421,7 @@ fn parse_class(pair: pest::iterators::Pair<Rule>) -> Result<Class, Box<dyn std::
     let mut methods = Vec::new();
     let mut properties = Vec::new();
     let mut js_fields = Vec::new();
+    let mut constants = Vec::new();
     let mut constructor = None;
 
     for pair in class_pairs {
@@ -420,6 +448,13 @@ fn parse_class(pair: pest::iterators::Pair<Rule>) -> Result<Class, Box<dyn std::
                     }
                     Rule::readonly_prop => {
                         let prop = parse_readonly_property(member_pair)?;
+                        if prop.needs_env {
+                            return Err(format!(
+                                "[env] is only supported on singleton properties: {}.{}",
+                                name, prop.name
+                            )
+                            .into());
+                        }
                         properties.push(prop);
                     }
                     Rule::normal_prop => {
@@ -427,7 +462,7 @@ fn parse_class(pair: pest::iterators::Pair<Rule>) -> Result<Class, Box<dyn std::
                         properties.push(prop);
                     }
                     Rule::const_member => {
-                        return Err("`const` is not supported in mquickjs RIDL; use `var`".into());
+                        constants.push(parse_const(member_pair)?);
                     }
                     Rule::var_member => {
                         let f = parse_var_field(member_pair)?;
@@ -464,6 +499,7 @@ fn parse_class(pair: pest::iterators::Pair<Rule>) -> Result<Class, Box<dyn std::
         methods,
         properties,
         js_fields,
+        constants,
         module: None,
     })
 }
@@ -512,7 +548,12 @@ fn parse_var_field(
 fn parse_readonly_property(
     pair: pest::iterators::Pair<Rule>,
 ) -> Result<Property, Box<dyn std::error::Error>> {
-    let inner_pairs = pair.into_inner();
+    let mut inner_pairs = pair.into_inner().peekable();
+
+    let mut needs_env = false;
+    if let Some(attrs) = inner_pairs.next_if(|p| p.as_rule() == Rule::attrs) {
+        needs_env = parse_attrs(attrs)?;
+    }
 
     // 过滤掉WS规则，只保留有意义的元素
     let elements: Vec<_> = inner_pairs.filter(|p| p.as_rule() != Rule::WS).collect();
@@ -544,6 +585,7 @@ fn parse_readonly_property(
         modifiers: vec![PropertyModifier::ReadOnly],
         name,
         property_type,
+        needs_env,
     })
 }
 
@@ -584,6 +626,7 @@ fn parse_readwrite_property(
         modifiers: vec![PropertyModifier::ReadWrite],
         name,
         property_type,
+        needs_env: false,
     })
 }
 
@@ -624,6 +667,7 @@ fn parse_normal_property(
         modifiers: vec![PropertyModifier::ReadWrite], // 普通属性默认可读写
         name,
         property_type,
+        needs_env: false,
     })
 }
 
@@ -667,6 +711,7 @@ fn parse_class_constructor(
         return_type: Type::Void,
         is_async: false,
         module: None,
+        needs_env: false,
     })
 }
 
@@ -676,9 +721,13 @@ fn parse_method(pair: pest::iterators::Pair<Rule>) -> Result<Method, Box<dyn std
     let mut name = String::new();
     let mut params = Vec::new();
     let mut return_type = Type::Void;
+    let mut needs_env = false;
 
     for p in inner_pairs {
         match p.as_rule() {
+            Rule::attrs => {
+                needs_env = parse_attrs(p)?;
+            }
             Rule::identifier => {
                 // identifier可能是方法名或参数名，我们需要更仔细地处理
                 if name.is_empty() {
@@ -710,6 +759,8 @@ fn parse_method(pair: pest::iterators::Pair<Rule>) -> Result<Method, Box<dyn std
         params,
         return_type,
         is_async: false,
+        overload_of: None,
+        needs_env,
     })
 }
 
@@ -723,9 +774,14 @@ fn parse_global_function(
 fn parse_function(
     pair: pest::iterators::Pair<Rule>,
 ) -> Result<Function, Box<dyn std::error::Error>> {
-    let mut inner_pairs = pair.into_inner();
+    let mut inner_pairs = pair.into_inner().peekable();
+
+    let mut needs_env = false;
+    if let Some(attrs) = inner_pairs.next_if(|p| p.as_rule() == Rule::attrs) {
+        needs_env = parse_attrs(attrs)?;
+    }
 
-    // First element is the function name
+    // Next is the function name
     let name_pair = inner_pairs.next().ok_or("Function has no name")?;
     let name = name_pair.as_str().to_string();
 
@@ -755,9 +811,22 @@ fn parse_function(
         return_type,
         is_async,
         module: None,
+        needs_env,
     })
 }
 
+/// `[env]` is the only attribute so far; unknown names are rejected rather than ignored.
+fn parse_attrs(pair: pest::iterators::Pair<Rule>) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut needs_env = false;
+    for attr in pair.into_inner() {
+        match attr.as_str() {
+            "env" => needs_env = true,
+            other => return Err(format!("unknown attribute: [{other}]").into()),
+        }
+    }
+    Ok(needs_env)
+}
+
 fn parse_param_list(
     pair: pest::iterators::Pair<Rule>,
 ) -> Result<Vec<Param>, Box<dyn std::error::Error>> {
@@ -776,6 +845,21 @@ fn parse_param_list(
         }
     }
 
+    // 语义约束：带默认值的参数之后不能再出现必填参数（variadic 除外）
+    if let Some(first_default_idx) = params.iter().position(|p| p.default_literal.is_some()) {
+        if let Some(p) = params
+            .iter()
+            .skip(first_default_idx + 1)
+            .find(|p| p.default_literal.is_none() && !p.variadic)
+        {
+            return Err(format!(
+                "Parameter '{}' without default value cannot follow a parameter with default value",
+                p.name
+            )
+            .into());
+        }
+    }
+
     // 语义约束：variadic 参数必须是最后一个
     if let Some(last_variadic_idx) = params.iter().position(|p| p.variadic) {
         if last_variadic_idx != params.len() - 1 {
@@ -823,17 +907,124 @@ fn parse_param(pair: pest::iterators::Pair<Rule>) -> Result<Param, Box<dyn std::
             }
             let param_type = parse_type(type_pair)?;
 
+            // 可选的默认值：`port: i32 = 8080`
+            let default_literal = match inner_pairs.find(|p| p.as_rule() == Rule::literal) {
+                Some(literal_pair) => Some(parse_param_default(&name, &param_type, literal_pair)?),
+                None => None,
+            };
+
             Ok(Param {
                 name,
                 param_type,
-                optional: false,
+                optional: default_literal.is_some(),
                 variadic,
+                default_literal,
             })
         }
         _ => Err(format!("Unexpected rule for param: {:?}", pair.as_rule()).into()),
     }
 }
 
+/// 校验参数默认值字面量与参数类型匹配，返回带字面量种类的值（字符串字面量已解码）。
+///
+/// 仅支持基础类型（bool/i32/i64/f32/f64/string）及其 nullable 形式；
+/// nullable 参数额外允许 `null`。
+fn parse_param_default(
+    name: &str,
+    param_type: &Type,
+    literal_pair: pest::iterators::Pair<Rule>,
+) -> Result<DefaultLiteral, Box<dyn std::error::Error>> {
+    let kind = literal_pair.clone().into_inner().next().map(|p| p.as_rule());
+    let text = parse_typed_literal(
+        &format!("default value for parameter '{}'", name),
+        param_type,
+        literal_pair,
+    )?;
+    Ok(match kind {
+        Some(Rule::null_literal) => DefaultLiteral::Null,
+        Some(Rule::string_literal) => DefaultLiteral::String(text),
+        _ => DefaultLiteral::Literal(text),
+    })
+}
+
+/// 校验字面量与声明类型匹配，返回字面量文本（字符串字面量返回解码后的内容）。
+///
+/// `what` 用于错误信息，例如 "default value for parameter 'port'"。
+fn parse_typed_literal(
+    what: &str,
+    param_type: &Type,
+    literal_pair: pest::iterators::Pair<Rule>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let pos = pair_pos(&literal_pair);
+    let lit = literal_pair
+        .into_inner()
+        .next()
+        .ok_or("Literal is empty")?;
+
+    let (base_type, nullable) = match param_type {
+        Type::Optional(inner) => (inner.as_ref(), true),
+        other => (other, false),
+    };
+
+    let ok = match lit.as_rule() {
+        Rule::null_literal => nullable,
+        Rule::bool_literal => matches!(base_type, Type::Bool),
+        Rule::integer_literal => matches!(base_type, Type::I32 | Type::I64 | Type::F32 | Type::F64),
+        Rule::float_literal => matches!(base_type, Type::F32 | Type::F64),
+        Rule::string_literal => matches!(base_type, Type::String),
+        _ => false,
+    };
+    if !ok {
+        return Err(format!(
+            "Invalid {} at {}:{}: `{}` is not a valid {} literal",
+            what,
+            pos.line,
+            pos.column,
+            lit.as_str(),
+            param_type
+        )
+        .into());
+    }
+
+    match lit.as_rule() {
+        Rule::string_literal => decode_ridl_string_literal(&pos, lit.as_str()),
+        Rule::integer_literal if matches!(base_type, Type::I32) => {
+            lit.as_str().parse::<i32>().map_err(|_| {
+                format!(
+                    "Invalid {} at {}:{}: `{}` is out of i32 range",
+                    what,
+                    pos.line,
+                    pos.column,
+                    lit.as_str()
+                )
+            })?;
+            Ok(lit.as_str().to_string())
+        }
+        // 与 glue 的 i64 参数规则一致：JS number 只能精确表示 |n| <= 2^53-1 的整数
+        Rule::integer_literal if matches!(base_type, Type::I64) => {
+            let in_range = lit
+                .as_str()
+                .parse::<i64>()
+                .is_ok_and(|v| v.unsigned_abs() <= MAX_SAFE_INTEGER);
+            if !in_range {
+                return Err(format!(
+                    "Invalid {} at {}:{}: `{}` is out of i64 range (|n| <= 2^53-1)",
+                    what,
+                    pos.line,
+                    pos.column,
+                    lit.as_str()
+                )
+                .into());
+            }
+            Ok(lit.as_str().to_string())
+        }
+        _ => Ok(lit.as_str().to_string()),
+    }
+}
+
+/// `Number.MAX_SAFE_INTEGER`：i64 字面量的上限。
+const MAX_SAFE_INTEGER: u64 = 9007199254740991;
+
 fn parse_enum(pair: pest::iterators::Pair<Rule>) -> Result<Enum, Box<dyn std::error::Error>> {
     let mut inner_pairs = pair.into_inner();
 
@@ -993,6 +1184,7 @@ fn parse_type(pair: pest::iterators::Pair<Rule>) -> Result<Type, Box<dyn std::er
                     "f32" => Ok(Type::F32),
                     "f64" => Ok(Type::F64),
                     "string" => Ok(Type::String),
+                    "bytes" => Ok(Type::Bytes),
                     "void" => Ok(Type::Void),
                     "object" => Ok(Type::Object),
                     "null" => Ok(Type::Null),
@@ -1123,6 +1315,7 @@ fn parse_type(pair: pest::iterators::Pair<Rule>) -> Result<Type, Box<dyn std::er
                 "f32" => Ok(Type::F32),
                 "f64" => Ok(Type::F64),
                 "string" => Ok(Type::String),
+                "bytes" => Ok(Type::Bytes),
                 "void" => Ok(Type::Void),
                 "object" => Ok(Type::Object),
                 "null" => Ok(Type::Null),
@@ -1308,6 +1501,8 @@ fn parse_singleton(
     // singleton body
     let mut methods = Vec::new();
     let mut properties = Vec::new();
+    let mut js_fields = Vec::new();
+    let mut constants = Vec::new();
 
     for p in inner_pairs {
         match p.as_rule() {
@@ -1328,6 +1523,17 @@ fn parse_singleton(
                         let prop = parse_readwrite_property(member_pair)?;
                         properties.push(prop);
                     }
+                    Rule::const_member => {
+                        constants.push(parse_const(member_pair)?);
+                    }
+                    Rule::var_member => {
+                        js_fields.push(parse_var_field(member_pair)?);
+                    }
+                    Rule::proto_var_member => {
+                        let mut f = parse_var_field(member_pair)?;
+                        f.modifiers.insert(0, PropertyModifier::Proto);
+                        js_fields.push(f);
+                    }
                     _ => {}
                 }
             }
@@ -1341,6 +1547,69 @@ fn parse_singleton(
         pos,
         methods,
         properties,
+        js_fields,
+        constants,
+        module: None,
+    })
+}
+
+/// `const NAME: type = literal`（class/singleton 成员或模块级 `const_def`）。
+fn parse_const(pair: pest::iterators::Pair<Rule>) -> Result<Constant, Box<dyn std::error::Error>> {
+    let pos = pair_pos(&pair);
+    let pair = if pair.as_rule() == Rule::const_def {
+        pair.into_inner()
+            .find(|p| p.as_rule() == Rule::const_member)
+            .ok_or("Expected const member")?
+    } else {
+        pair
+    };
+    let mut elements = pair.into_inner().filter(|p| p.as_rule() != Rule::WS);
+
+    let name = elements
+        .next()
+        .ok_or("Expected identifier for const")?
+        .as_str()
+        .to_string();
+    let const_type = parse_type(elements.next().ok_or("Expected type for const")?)?;
+    let literal_pair = elements.next().ok_or("Expected literal value for const")?;
+
+    // ROM 属性表只能表示 number/string/bool；nullable 与复合类型不允许作为常量。
+    if !matches!(
+        const_type,
+        Type::Bool | Type::I32 | Type::I64 | Type::F32 | Type::F64 | Type::String
+    ) {
+        return Err(format!(
+            "Invalid const '{}' at {}:{}: type {} is not allowed (expected bool/i32/i64/f32/f64/string)",
+            name, pos.line, pos.column, const_type
+        )
+        .into());
+    }
+
+    let value = parse_typed_literal(
+        &format!("value for const '{}'", name),
+        &const_type,
+        literal_pair,
+    )?;
+
+    // number 常量在 ROM 中以 double 存储，i64 超出 2^53 会丢失精度。
+    if const_type == Type::I64 {
+        let v: i64 = value
+            .parse()
+            .map_err(|_| format!("Invalid const '{}': `{}` is out of i64 range", name, value))?;
+        if v.unsigned_abs() > (1u64 << 53) {
+            return Err(format!(
+                "Invalid const '{}' at {}:{}: `{}` cannot be represented exactly as a JS number",
+                name, pos.line, pos.column, value
+            )
+            .into());
+        }
+    }
+
+    Ok(Constant {
+        name,
+        pos: Some(pos),
+        const_type,
+        value,
         module: None,
     })
 }
@@ -1379,6 +1648,7 @@ fn parse_callback(
         return_type: Type::Void, // 回调函数没有返回值
         is_async: false,
         module: None,
+        needs_env: false,
     };
 
     Ok(IDLItem::Function(callback_func))
@@ -2401,4 +2671,88 @@ interface Test {}"#;
         let result = IDLParser::parse(Rule::module_decl, input);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_param_default_values() {
+        let input = r#"fn connect(host: string, port: i32 = 8080, secure: bool = false, tag: string? = "x");"#;
+        let items = parse_idl(input).unwrap();
+        let IDLItem::Function(f) = &items[0] else {
+            panic!("expected function");
+        };
+        assert_eq!(f.params[0].default_literal, None);
+        assert!(!f.params[0].optional);
+        assert_eq!(
+            f.params[1].default_literal,
+            Some(DefaultLiteral::Literal("8080".into()))
+        );
+        assert!(f.params[1].optional);
+        assert_eq!(
+            f.params[2].default_literal,
+            Some(DefaultLiteral::Literal("false".into()))
+        );
+        assert_eq!(
+            f.params[3].default_literal,
+            Some(DefaultLiteral::String("x".into()))
+        );
+    }
+
+    #[test]
+    fn test_param_default_float_and_negative() {
+        let input = r#"fn scale(factor: f64 = -1.5, offset: i32 = -3);"#;
+        let items = parse_idl(input).unwrap();
+        let IDLItem::Function(f) = &items[0] else {
+            panic!("expected function");
+        };
+        assert_eq!(
+            f.params[0].default_literal,
+            Some(DefaultLiteral::Literal("-1.5".into()))
+        );
+        assert_eq!(
+            f.params[1].default_literal,
+            Some(DefaultLiteral::Literal("-3".into()))
+        );
+    }
+
+    #[test]
+    fn test_param_default_type_mismatch_is_error() {
+        assert!(parse_idl(r#"fn f(port: i32 = "8080");"#).is_err());
+        assert!(parse_idl(r#"fn f(port: i32 = 1.5);"#).is_err());
+        assert!(parse_idl(r#"fn f(port: i32 = null);"#).is_err());
+        assert!(parse_idl(r#"fn f(items: array<i32> = null);"#).is_err());
+        assert!(parse_idl(r#"fn f(port: i32? = null);"#).is_ok());
+    }
+
+    #[test]
+    fn test_param_default_integer_ranges() {
+        assert!(parse_idl(r#"fn f(port: i32 = 2147483648);"#).is_err());
+        assert!(parse_idl(r#"fn f(n: i64 = 9007199254740991);"#).is_ok());
+        assert!(parse_idl(r#"fn f(n: i64 = -9007199254740991);"#).is_ok());
+        let err = parse_idl(r#"fn f(n: i64 = 9007199254740992);"#).unwrap_err();
+        assert!(err.to_string().contains("out of i64 range"), "{err}");
+        assert!(parse_idl(r#"fn f(n: i64 = 99999999999999999999);"#).is_err());
+    }
+
+    #[test]
+    fn test_required_param_after_default_is_error() {
+        let err = parse_idl(r#"fn f(port: i32 = 1, host: string);"#).unwrap_err();
+        assert!(err.to_string().contains("host"), "{err}");
+        assert!(parse_idl(r#"fn f(port: i32 = 1, ...rest: string);"#).is_ok());
+    }
+
+    #[test]
+    fn test_identifier_may_start_with_keyword() {
+        let items = parse_idl(
+            r#"
+        singleton console {
+            fn assert(...args: any);
+            fn format(asText: bool, mapping: string) -> string;
+        }
+        fn classify(fnName: string) -> string;
+        "#,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(parse_idl(r#"fn as(x: i32);"#).is_err());
+        assert!(parse_idl(r#"fn f(map: i32);"#).is_err());
+    }
 }
@@ -66,6 +66,10 @@ fn normalize_item(item: IDLItem) -> Result<IDLItem, Box<dyn std::error::Error>>
             }
             IDLItem::Struct(st)
         }
+        IDLItem::Using(mut u) => {
+            u.alias_type = normalize_type(u.alias_type.clone())?;
+            IDLItem::Using(u)
+        }
         other => other,
     })
 }
@@ -75,7 +79,10 @@ fn normalize_property(p: &mut Property) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
-fn normalize_type(ty: Type) -> Result<Type, Box<dyn std::error::Error>> {
+/// Canonical form of one type: `T | null` becomes `T?`, redundant groups and nested
+/// optionals collapse. Codegen sees only normalized types; runtime checks against RIDL types
+/// should normalize the same way.
+pub fn normalize_type(ty: Type) -> Result<Type, Box<dyn std::error::Error>> {
     // First, normalize children.
     let ty = match ty {
         Type::Array(inner) => Type::Array(Box::new(normalize_type(*inner)?)),
@@ -211,6 +218,7 @@ fn parse_atom_type(s: &str) -> Type {
         "f32" => Type::F32,
         "f64" => Type::F64,
         "string" => Type::String,
+        "bytes" => Type::Bytes,
         "void" => Type::Void,
         "object" => Type::Object,
         "null" => Type::Null,
@@ -0,0 +1,127 @@
+//! Method overloading (singleton / class)
+//!
+//! Multiple `fn` declarations with the same name on a singleton or class form an overload
+//! set. JS sees a single method; each variant becomes a distinct Rust trait method whose
+//! name is derived from the parameter types:
+//!
+//! ```text
+//! fn draw(x: i32, y: i32);   // -> draw_i32_i32
+//! fn draw(p: Point);         // -> draw_point
+//! fn draw();                 // -> draw_none
+//! ```
+//!
+//! The variant keeps the JS name in `Method::overload_of`. Ambiguity checks live in the
+//! validator; dispatch glue is generated from the same information.
+
+use super::ast::{Class, IDLItem, Method, Singleton, Type};
+
+pub fn resolve_item_overloads(item: &mut IDLItem) {
+    match item {
+        IDLItem::Singleton(Singleton { methods, .. }) | IDLItem::Class(Class { methods, .. }) => {
+            resolve_method_overloads(methods);
+        }
+        _ => {}
+    }
+}
+
+fn resolve_method_overloads(methods: &mut [Method]) {
+    let mut counts = std::collections::HashMap::<String, usize>::new();
+    for m in methods.iter() {
+        *counts.entry(m.name.clone()).or_insert(0) += 1;
+    }
+
+    for m in methods.iter_mut() {
+        if counts.get(&m.name).copied().unwrap_or(0) < 2 {
+            continue;
+        }
+        let rust_name = overload_variant_name(&m.name, m);
+        m.overload_of = Some(std::mem::replace(&mut m.name, rust_name));
+    }
+}
+
+/// `draw(x: i32, y: i32)` -> `draw_i32_i32`.
+pub fn overload_variant_name(js_name: &str, method: &Method) -> String {
+    if method.params.is_empty() {
+        return format!("{js_name}_none");
+    }
+    let suffix: Vec<String> = method
+        .params
+        .iter()
+        .map(|p| type_name_suffix(&p.param_type))
+        .collect();
+    format!("{js_name}_{}", suffix.join("_"))
+}
+
+fn type_name_suffix(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::I32 => "i32".to_string(),
+        Type::I64 => "i64".to_string(),
+        Type::F32 => "f32".to_string(),
+        Type::F64 => "f64".to_string(),
+        Type::String => "string".to_string(),
+        Type::Bytes => "bytes".to_string(),
+        Type::Void => "void".to_string(),
+        Type::Object => "object".to_string(),
+        Type::Null => "null".to_string(),
+        Type::Any => "any".to_string(),
+        Type::Callback | Type::CallbackWithParams(_) => "callback".to_string(),
+        Type::Array(t) => format!("array_{}", type_name_suffix(t)),
+        Type::Map(k, v) => format!("map_{}_{}", type_name_suffix(k), type_name_suffix(v)),
+        Type::Optional(t) => format!("opt_{}", type_name_suffix(t)),
+        Type::Group(t) => type_name_suffix(t),
+        Type::Union(types) => types
+            .iter()
+            .map(type_name_suffix)
+            .collect::<Vec<_>>()
+            .join("_or_"),
+        Type::ClassRef(name) | Type::Custom(name) => {
+            crate::generator::naming::to_snake_case(name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::IDLItem;
+    use crate::parser::parse_idl;
+
+    #[test]
+    fn overloads_get_type_derived_names() {
+        let items = parse_idl(
+            r#"
+class Point {
+    constructor(x: i32, y: i32);
+}
+singleton canvas {
+    fn draw(x: i32, y: i32);
+    fn draw(p: Point);
+    fn draw();
+    fn clear();
+}
+"#,
+        )
+        .unwrap();
+        let s = items
+            .iter()
+            .find_map(|it| match it {
+                IDLItem::Singleton(s) => Some(s),
+                _ => None,
+            })
+            .unwrap();
+        let names: Vec<(&str, Option<&str>)> = s
+            .methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.overload_of.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("draw_i32_i32", Some("draw")),
+                ("draw_point", Some("draw")),
+                ("draw_none", Some("draw")),
+                ("clear", None),
+            ]
+        );
+    }
+}
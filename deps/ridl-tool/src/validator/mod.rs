@@ -2,6 +2,7 @@
 //! 提供对解析后的RIDL AST的语义验证功能
 
 mod normalize;
+mod overload;
 
 use crate::parser::ast::*;
 use std::collections::HashMap;
@@ -81,6 +82,12 @@ impl SemanticValidator {
         // 语义约束：JS-only 字段与 native property 互斥等
         self.validate_js_fields(idl);
 
+        // 方法重名 / 重载集合二义性
+        self.validate_overloads(idl);
+
+        // const 与同一作用域内其他成员重名
+        self.validate_constants(idl);
+
         if self.errors.is_empty() {
             Ok(())
         } else {
@@ -165,9 +172,26 @@ impl SemanticValidator {
         }
 
         // 验证结构体字段类型
+        // 结构体在 glue 中经 FromJs/IntoJs 与普通 JS 对象互转：字段只支持 bool/i32/f64/string（及其 T?）。
         for struct_def in &idl.structs {
             for field in &struct_def.fields {
                 self.validate_type(&field.field_type);
+                let base = match &field.field_type {
+                    Type::Optional(inner) => inner.as_ref(),
+                    other => other,
+                };
+                if !matches!(base, Type::Bool | Type::I32 | Type::F64 | Type::String) {
+                    self.errors.push(RIDLError::new(
+                        format!(
+                            "Unsupported struct field type: {}.{}: {} (expected bool, i32, f64 or string)",
+                            struct_def.name, field.name, field.field_type
+                        ),
+                        0,
+                        0,
+                        self.file_path.clone(),
+                        RIDLErrorType::SemanticError,
+                    ));
+                }
             }
         }
 
@@ -271,6 +295,7 @@ impl SemanticValidator {
             | Type::F32
             | Type::F64
             | Type::String
+            | Type::Bytes
             | Type::Void
             | Type::Object
             | Type::Callback
@@ -382,15 +407,62 @@ impl SemanticValidator {
 
     fn validate_js_fields(&mut self, idl: &IDL) {
         for class in &idl.classes {
-            // Disallow name collisions between js_fields and native properties/methods/ctor.
-            for f in &class.js_fields {
-                let (line, col) = f.pos.as_ref().map(|p| (p.line, p.column)).unwrap_or((0, 0));
+            self.validate_js_field_set(
+                &format!("class '{}'", class.name),
+                class.pos.as_ref(),
+                &class.js_fields,
+                &class.properties,
+                &class.methods,
+            );
+        }
+        for singleton in &idl.singletons {
+            self.validate_js_field_set(
+                &format!("singleton '{}'", singleton.name),
+                singleton.pos.as_ref(),
+                &singleton.js_fields,
+                &singleton.properties,
+                &singleton.methods,
+            );
+        }
+
+        // JS-only fields are installed on the global singleton object by `ridl_context_init`;
+        // module-mode singletons live on the module object instead, so reject rather than
+        // silently dropping them.
+        for singleton in &idl.singletons {
+            let (Some(module), Some(f)) = (&singleton.module, singleton.js_fields.first()) else {
+                continue;
+            };
+            let (line, col) = f
+                .pos
+                .as_ref()
+                .or(singleton.pos.as_ref())
+                .map(|p| (p.line, p.column))
+                .unwrap_or((0, 0));
+            self.errors.push(RIDLError::new(
+                format!(
+                    "Invalid singleton '{}': JS-only field '{}' is not supported in module '{}' (only global singletons may declare `var` / `proto var`)",
+                    singleton.name, f.name, module.module_path
+                ),
+                line,
+                col,
+                self.file_path.clone(),
+                RIDLErrorType::SemanticError,
+            ));
+        }
 
-                if class.properties.iter().any(|p| p.name == f.name) {
+        // Ensure singletons do not use proto property modifiers (parser should already reject).
+        for singleton in &idl.singletons {
+            for p in &singleton.properties {
+                if p.modifiers.contains(&PropertyModifier::Proto) {
+                    let (line, col) = singleton
+                        .pos
+                        .as_ref()
+                        .map(|p| (p.line, p.column))
+                        .unwrap_or((0, 0));
                     self.errors.push(RIDLError::new(
                         format!(
-                            "Invalid js field '{}': js-only fields cannot share name with native property in class '{}'",
-                            f.name, class.name
+                            "Invalid singleton property '{}': singleton cannot declare proto property",
+                            p.name
                         ),
                         line,
                         col,
@@ -398,12 +470,117 @@ impl SemanticValidator {
                         RIDLErrorType::SemanticError,
                     ));
                 }
+            }
+        }
+    }
 
-                if class.methods.iter().any(|m| m.name == f.name) {
+    /// 校验一组 JS-only 字段（class 或 singleton）：与 native 成员重名、字面量类型、字段间重名。
+    fn validate_js_field_set(
+        &mut self,
+        owner: &str,
+        owner_pos: Option<&SourcePos>,
+        js_fields: &[JsField],
+        properties: &[Property],
+        methods: &[Method],
+    ) {
+        // Disallow name collisions between js_fields and native properties/methods/ctor.
+        for f in js_fields {
+            let (line, col) = f.pos.as_ref().map(|p| (p.line, p.column)).unwrap_or((0, 0));
+
+            if properties.iter().any(|p| p.name == f.name) {
+                self.errors.push(RIDLError::new(
+                    format!(
+                        "Invalid js field '{}': js-only fields cannot share name with native property in {}",
+                        f.name, owner
+                    ),
+                    line,
+                    col,
+                    self.file_path.clone(),
+                    RIDLErrorType::SemanticError,
+                ));
+            }
+
+            if methods
+                .iter()
+                .any(|m| m.overload_of.as_deref().unwrap_or(&m.name) == f.name) {
+                self.errors.push(RIDLError::new(
+                    format!(
+                        "Invalid js field '{}': js-only fields cannot share name with method in {}",
+                        f.name, owner
+                    ),
+                    line,
+                    col,
+                    self.file_path.clone(),
+                    RIDLErrorType::SemanticError,
+                ));
+            }
+
+            if f.name == "constructor" {
+                self.errors.push(RIDLError::new(
+                    format!(
+                        "Invalid js field '{}': reserved name in {}",
+                        f.name, owner
+                    ),
+                    line,
+                    col,
+                    self.file_path.clone(),
+                    RIDLErrorType::SemanticError,
+                ));
+            }
+
+            // MVP literal/type constraints.
+            // - Only primitive + null are supported for now.
+            // - Custom types may only be initialized with null.
+            match &f.field_type {
+                Type::Bool
+                | Type::I32
+                | Type::I64
+                | Type::F32
+                | Type::F64
+                | Type::String
+                | Type::Null => {}
+                Type::Any => {
+                    // any is allowed to be initialized with null (and other literals in the future).
+                }
+                Type::Optional(inner) => {
+                    // For nullable fields, only null init is guaranteed to be valid in MVP.
+                    // Non-null literal validation is handled by the underlying type.
+                    if f.init_literal == "null" {
+                        // ok
+                    } else if matches!(**inner, Type::String) {
+                        // ok: string literal already decoded in parser
+                    } else {
+                        self.errors.push(RIDLError::new(
+                            format!(
+                                "Invalid js field '{}': only null or string literal init is supported for nullable fields in MVP",
+                                f.name
+                            ),
+                            line,
+                            col,
+                            self.file_path.clone(),
+                            RIDLErrorType::SemanticError,
+                        ));
+                    }
+                }
+                Type::Custom(_) => {
+                    if f.init_literal != "null" {
+                        self.errors.push(RIDLError::new(
+                            format!(
+                                "Invalid js field '{}': custom type can only be initialized with null in MVP",
+                                f.name
+                            ),
+                            line,
+                            col,
+                            self.file_path.clone(),
+                            RIDLErrorType::SemanticError,
+                        ));
+                    }
+                }
+                _ => {
                     self.errors.push(RIDLError::new(
                         format!(
-                            "Invalid js field '{}': js-only fields cannot share name with method in class '{}'",
-                            f.name, class.name
+                            "Invalid js field '{}': unsupported js-only field type '{:?}' in MVP",
+                            f.name, f.field_type
                         ),
                         line,
                         col,
@@ -411,12 +588,56 @@ impl SemanticValidator {
                         RIDLErrorType::SemanticError,
                     ));
                 }
+            }
+        }
+
+        // Disallow duplicate names among js_fields themselves.
+        let mut js_names = std::collections::HashMap::<&str, usize>::new();
+        for f in js_fields {
+            *js_names.entry(&f.name).or_insert(0) += 1;
+        }
+        for (name, cnt) in js_names {
+            if cnt > 1 {
+                let (line, col) = owner_pos.map(|p| (p.line, p.column)).unwrap_or((0, 0));
+                self.errors.push(RIDLError::new(
+                    format!(
+                        "Duplicate js field '{}': js-only fields must have unique names within {}",
+                        name, owner
+                    ),
+                    line,
+                    col,
+                    self.file_path.clone(),
+                    RIDLErrorType::SemanticError,
+                ));
+            }
+        }
+    }
+
+    /// 校验方法重载
+    ///
+    /// - singleton/class：同名方法构成重载集合（parser 已为每个变体分配 Rust 名），
+    ///   集合内任意两个变体不得同时匹配同一组实参。
+    /// - interface/全局函数：不支持重载，禁止重名。
+    fn validate_overloads(&mut self, idl: &IDL) {
+        let mut owners: Vec<(String, &[Method], Option<&SourcePos>)> = Vec::new();
+        for s in &idl.singletons {
+            owners.push((s.name.clone(), &s.methods, s.pos.as_ref()));
+        }
+        for c in &idl.classes {
+            owners.push((c.name.clone(), &c.methods, c.pos.as_ref()));
+        }
+
+        for (owner, methods, pos) in owners {
+            let (line, col) = pos.map(|p| (p.line, p.column)).unwrap_or((0, 0));
 
-                if f.name == "constructor" {
+            let mut seen = std::collections::HashSet::<&str>::new();
+            for m in methods {
+                if !seen.insert(m.name.as_str()) {
                     self.errors.push(RIDLError::new(
                         format!(
-                            "Invalid js field '{}': reserved name in class '{}'",
-                            f.name, class.name
+                            "Duplicate method '{}' in '{}': overloads must differ in parameter types",
+                            m.overload_of.as_deref().unwrap_or(&m.name),
+                            owner
                         ),
                         line,
                         col,
@@ -424,87 +645,22 @@ impl SemanticValidator {
                         RIDLErrorType::SemanticError,
                     ));
                 }
-
-                // MVP literal/type constraints.
-                // - Only primitive + null are supported for now.
-                // - Custom types may only be initialized with null.
-                match &f.field_type {
-                    Type::Bool
-                    | Type::I32
-                    | Type::I64
-                    | Type::F32
-                    | Type::F64
-                    | Type::String
-                    | Type::Null => {}
-                    Type::Any => {
-                        // any is allowed to be initialized with null (and other literals in the future).
-                    }
-                    Type::Optional(inner) => {
-                        // For nullable fields, only null init is guaranteed to be valid in MVP.
-                        // Non-null literal validation is handled by the underlying type.
-                        if f.init_literal == "null" {
-                            // ok
-                        } else if matches!(**inner, Type::String) {
-                            // ok: string literal already decoded in parser
-                        } else {
-                            self.errors.push(RIDLError::new(
-                                format!(
-                                    "Invalid js field '{}': only null or string literal init is supported for nullable fields in MVP",
-                                    f.name
-                                ),
-                                line,
-                                col,
-                                self.file_path.clone(),
-                                RIDLErrorType::SemanticError,
-                            ));
-                        }
-                    }
-                    Type::Custom(_) => {
-                        if f.init_literal != "null" {
-                            self.errors.push(RIDLError::new(
-                                format!(
-                                    "Invalid js field '{}': custom type can only be initialized with null in MVP",
-                                    f.name
-                                ),
-                                line,
-                                col,
-                                self.file_path.clone(),
-                                RIDLErrorType::SemanticError,
-                            ));
-                        }
-                    }
-                    _ => {
-                        self.errors.push(RIDLError::new(
-                            format!(
-                                "Invalid js field '{}': unsupported js-only field type '{:?}' in MVP",
-                                f.name, f.field_type
-                            ),
-                            line,
-                            col,
-                            self.file_path.clone(),
-                            RIDLErrorType::SemanticError,
-                        ));
-                    }
-                }
             }
 
-            // Disallow duplicate names among js_fields themselves.
-            let mut js_names = std::collections::HashMap::<&str, usize>::new();
-            for f in &class.js_fields {
-                *js_names.entry(&f.name).or_insert(0) += 1;
+            let mut sets: Vec<(&str, Vec<&Method>)> = Vec::new();
+            for m in methods {
+                let Some(js_name) = m.overload_of.as_deref() else {
+                    continue;
+                };
+                match sets.iter_mut().find(|(n, _)| *n == js_name) {
+                    Some((_, v)) => v.push(m),
+                    None => sets.push((js_name, vec![m])),
+                }
             }
-            for (name, cnt) in js_names {
-                if cnt > 1 {
-                    let (line, col) = class
-                        .pos
-                        .as_ref()
-                        .map(|p| (p.line, p.column))
-                        .unwrap_or((0, 0));
+            for (js_name, variants) in sets {
+                for msg in overload::check_overload_set(&owner, js_name, &variants) {
                     self.errors.push(RIDLError::new(
-                        format!(
-                            "Duplicate js field '{}': js-only fields must have unique names within class '{}'",
-                            name, class.name
-                        ),
+                        msg,
                         line,
                         col,
                         self.file_path.clone(),
@@ -514,28 +670,89 @@ impl SemanticValidator {
             }
         }
 
-        // Ensure singletons do not use proto property modifiers (parser should already reject).
-        for singleton in &idl.singletons {
-            for p in &singleton.properties {
-                if p.modifiers.contains(&PropertyModifier::Proto) {
-                    let (line, col) = singleton
-                        .pos
-                        .as_ref()
-                        .map(|p| (p.line, p.column))
-                        .unwrap_or((0, 0));
+        for interface in &idl.interfaces {
+            let mut seen = std::collections::HashSet::<&str>::new();
+            for m in &interface.methods {
+                if !seen.insert(m.name.as_str()) {
                     self.errors.push(RIDLError::new(
                         format!(
-                            "Invalid singleton property '{}': singleton cannot declare proto property",
-                            p.name
+                            "Duplicate method '{}' in interface '{}': overloading is only supported on singleton/class methods",
+                            m.name, interface.name
                         ),
-                        line,
-                        col,
+                        0,
+                        0,
                         self.file_path.clone(),
                         RIDLErrorType::SemanticError,
                     ));
                 }
             }
         }
+
+        let mut seen = std::collections::HashSet::<&str>::new();
+        for f in &idl.functions {
+            if !seen.insert(f.name.as_str()) {
+                self.errors.push(RIDLError::new(
+                    format!(
+                        "Duplicate function '{}': overloading is only supported on singleton/class methods",
+                        f.name
+                    ),
+                    0,
+                    0,
+                    self.file_path.clone(),
+                    RIDLErrorType::SemanticError,
+                ));
+            }
+        }
+    }
+
+    /// 校验 const 命名：同一作用域（模块 / singleton / class）内不得与其他成员重名。
+    fn validate_constants(&mut self, idl: &IDL) {
+        let mut scopes: Vec<(String, &[Constant], Vec<&str>)> = Vec::new();
+
+        let mut module_members: Vec<&str> = Vec::new();
+        module_members.extend(idl.functions.iter().map(|f| f.name.as_str()));
+        module_members.extend(idl.classes.iter().map(|c| c.name.as_str()));
+        module_members.extend(idl.singletons.iter().map(|s| s.name.as_str()));
+        scopes.push(("module".to_string(), &idl.constants, module_members));
+
+        for s in &idl.singletons {
+            let mut members: Vec<&str> = Vec::new();
+            members.extend(s.methods.iter().map(|m| m.overload_of.as_deref().unwrap_or(&m.name)));
+            members.extend(s.properties.iter().map(|p| p.name.as_str()));
+            members.extend(s.js_fields.iter().map(|f| f.name.as_str()));
+            scopes.push((format!("singleton '{}'", s.name), &s.constants, members));
+        }
+        for c in &idl.classes {
+            let mut members: Vec<&str> = Vec::new();
+            members.extend(c.methods.iter().map(|m| m.overload_of.as_deref().unwrap_or(&m.name)));
+            members.extend(c.properties.iter().map(|p| p.name.as_str()));
+            members.extend(c.js_fields.iter().map(|f| f.name.as_str()));
+            scopes.push((format!("class '{}'", c.name), &c.constants, members));
+        }
+
+        for (scope, constants, members) in scopes {
+            let mut seen = std::collections::HashSet::<&str>::new();
+            for k in constants {
+                let (line, col) = k.pos.as_ref().map(|p| (p.line, p.column)).unwrap_or((0, 0));
+                let message = if !seen.insert(k.name.as_str()) {
+                    format!("Duplicate const '{}' in {}", k.name, scope)
+                } else if members.contains(&k.name.as_str()) {
+                    format!(
+                        "Invalid const '{}': name is already used by another member of {}",
+                        k.name, scope
+                    )
+                } else {
+                    continue;
+                };
+                self.errors.push(RIDLError::new(
+                    message,
+                    line,
+                    col,
+                    self.file_path.clone(),
+                    RIDLErrorType::SemanticError,
+                ));
+            }
+        }
     }
 
     /// 检查标识符是否使用了关键字
@@ -599,6 +816,7 @@ pub fn validate_with_mode(
         imports: vec![],
         singletons: vec![],
         callbacks: vec![],
+        constants: vec![],
     };
 
     // 从items中提取各种定义到idl中
@@ -612,6 +830,7 @@ pub fn validate_with_mode(
             IDLItem::Using(using) => idl.using.push(using.clone()),
             IDLItem::Import(import) => idl.imports.push(import.clone()),
             IDLItem::Singleton(singleton) => idl.singletons.push(singleton.clone()),
+            IDLItem::Const(constant) => idl.constants.push(constant.clone()),
         }
     }
 
@@ -0,0 +1,165 @@
+//! 重载集合校验
+//!
+//! 生成的 JS 入口按「参数个数 + 运行时类型检查」分派到各个重载变体，
+//! 因此任意一组实参最多只能匹配一个变体，否则视为二义。
+//!
+//! 运行时可区分的类型：number / string / bool / null / RIDL class 实例。
+//! 其余类型（object/array/map/callback/any 等）在分派时不做检查，按「匹配任意值」处理。
+
+use crate::parser::ast::{Method, Type};
+
+#[derive(Debug, Clone, PartialEq)]
+enum RuntimeKind {
+    Any,
+    Number,
+    String,
+    Bool,
+    Null,
+    Undefined,
+    Class(String),
+}
+
+fn runtime_kinds(ty: &Type, out: &mut Vec<RuntimeKind>) {
+    match ty {
+        Type::I32 | Type::I64 | Type::F32 | Type::F64 => out.push(RuntimeKind::Number),
+        Type::String => out.push(RuntimeKind::String),
+        Type::Bool => out.push(RuntimeKind::Bool),
+        Type::Null => out.push(RuntimeKind::Null),
+        Type::ClassRef(name) => out.push(RuntimeKind::Class(name.clone())),
+        Type::Optional(inner) => {
+            out.push(RuntimeKind::Null);
+            out.push(RuntimeKind::Undefined);
+            runtime_kinds(inner, out);
+        }
+        Type::Group(inner) => runtime_kinds(inner, out),
+        Type::Union(types) => {
+            for t in types {
+                runtime_kinds(t, out);
+            }
+        }
+        _ => out.push(RuntimeKind::Any),
+    }
+}
+
+fn types_overlap(a: &Type, b: &Type) -> bool {
+    let mut ka = Vec::new();
+    let mut kb = Vec::new();
+    runtime_kinds(a, &mut ka);
+    runtime_kinds(b, &mut kb);
+    if ka.contains(&RuntimeKind::Any) || kb.contains(&RuntimeKind::Any) {
+        return true;
+    }
+    ka.iter().any(|k| kb.contains(k))
+}
+
+/// 可接受的实参个数范围：[min, max]，max 为 None 表示 variadic（无上限）。
+pub(crate) fn arity_range(method: &Method) -> (usize, Option<usize>) {
+    let min = method
+        .params
+        .iter()
+        .filter(|p| p.default_literal.is_none() && !p.variadic)
+        .count();
+    let max = if method.params.iter().any(|p| p.variadic) {
+        None
+    } else {
+        Some(method.params.len())
+    };
+    (min, max)
+}
+
+/// 第 i 个实参对应的参数类型；超出定长参数部分由 variadic 吸收（分派时不检查元素类型）。
+fn param_type_at(method: &Method, i: usize) -> Type {
+    match method.params.get(i) {
+        Some(p) if p.variadic => Type::Any,
+        Some(p) => p.param_type.clone(),
+        None => Type::Any,
+    }
+}
+
+/// 两个重载变体是否存在同时匹配的实参列表。
+fn is_ambiguous(a: &Method, b: &Method) -> Option<usize> {
+    let (a_min, a_max) = arity_range(a);
+    let (b_min, b_max) = arity_range(b);
+
+    let lo = a_min.max(b_min);
+    let cap = a.params.len().max(b.params.len()) + 1;
+    let hi = match (a_max, b_max) {
+        (Some(x), Some(y)) => x.min(y),
+        (Some(x), None) | (None, Some(x)) => x,
+        (None, None) => cap,
+    };
+
+    (lo..=hi).find(|&n| (0..n).all(|i| types_overlap(&param_type_at(a, i), &param_type_at(b, i))))
+}
+
+/// 校验一个重载集合，返回错误信息列表。
+pub(crate) fn check_overload_set(owner: &str, js_name: &str, variants: &[&Method]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (i, a) in variants.iter().enumerate() {
+        for b in variants.iter().skip(i + 1) {
+            if let Some(argc) = is_ambiguous(a, b) {
+                errors.push(format!(
+                    "Ambiguous overloads of '{}.{}': '{}' and '{}' both accept {} argument(s) of compatible runtime types",
+                    owner, js_name, a.name, b.name, argc
+                ));
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::{DefaultLiteral, Param};
+
+    fn m(name: &str, params: Vec<(Type, Option<&str>)>) -> Method {
+        Method {
+            name: name.to_string(),
+            params: params
+                .into_iter()
+                .enumerate()
+                .map(|(i, (t, d))| Param {
+                    name: format!("p{i}"),
+                    param_type: t,
+                    optional: d.is_some(),
+                    variadic: false,
+                    default_literal: d.map(|d| DefaultLiteral::Literal(d.to_string())),
+                })
+                .collect(),
+            return_type: Type::Void,
+            is_async: false,
+            overload_of: Some("f".to_string()),
+            needs_env: false,
+        }
+    }
+
+    #[test]
+    fn distinct_arity_or_kind_is_not_ambiguous() {
+        let a = m("a", vec![(Type::I32, None), (Type::I32, None)]);
+        let b = m("b", vec![(Type::ClassRef("Point".into()), None)]);
+        let c = m("c", vec![(Type::String, None)]);
+        assert!(check_overload_set("s", "f", &[&a, &b, &c]).is_empty());
+    }
+
+    #[test]
+    fn numeric_types_overlap() {
+        let a = m("a", vec![(Type::I32, None)]);
+        let b = m("b", vec![(Type::F64, None)]);
+        assert_eq!(check_overload_set("s", "f", &[&a, &b]).len(), 1);
+    }
+
+    #[test]
+    fn defaults_extend_arity_range() {
+        let a = m("a", vec![(Type::I32, None)]);
+        let b = m("b", vec![(Type::I32, None), (Type::I32, Some("0"))]);
+        assert_eq!(check_overload_set("s", "f", &[&a, &b]).len(), 1);
+    }
+
+    #[test]
+    fn any_overlaps_everything() {
+        let a = m("a", vec![(Type::Object, None)]);
+        let b = m("b", vec![(Type::String, None)]);
+        assert_eq!(check_overload_set("s", "f", &[&a, &b]).len(), 1);
+    }
+}
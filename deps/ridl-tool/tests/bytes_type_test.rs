@@ -0,0 +1,74 @@
+use std::fs;
+
+use ridl_tool::{
+    generator::generate_module_files, parser::parse_ridl_file, validator::validate_with_mode,
+};
+
+#[test]
+fn bytes_maps_to_vec_u8_and_typed_array_glue() {
+    let ridl = r#"
+fn digest(data: bytes) -> bytes;
+
+singleton codec {
+    fn encode(data: bytes?, level: i32) -> bytes;
+}
+"#;
+
+    let parsed = parse_ridl_file(ridl).expect("parse ridl");
+    validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+
+    assert!(api.contains("data: Option<Vec<u8>>"), "{api}");
+    assert!(api.contains("-> Vec<u8>"), "{api}");
+
+    // global fn: decoded in glue, passed to the user impl as Vec<u8>
+    assert!(glue.contains("let data: Vec<u8> = match unsafe { mquickjs_rs::handles::typed_array::bytes_from_raw(ctx, v) }"));
+    assert!(glue.contains("invalid bytes argument: data"));
+    assert!(glue.contains("mquickjs_rs::handles::typed_array::new_uint8_array_raw(ctx, &"));
+}
+
+#[test]
+fn bytes_union_members_decode_typed_arrays() {
+    let ridl = r#"
+fn digest(data: string | bytes) -> bytes;
+fn echo(v: (string | bytes)?) -> (string | bytes)?;
+"#;
+
+    let parsed = parse_ridl_file(ridl).expect("parse ridl");
+    validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+
+    assert!(api.contains("pub enum UnionBytesString {"), "{api}");
+    assert!(api.contains("Bytes(Vec<u8>),"), "{api}");
+
+    // Strings are tried first; anything that is not a string falls back to typed array bytes.
+    assert!(
+        glue.contains("return Ok(crate::api::global::union::UnionBytesString::Bytes(b));"),
+        "{glue}"
+    );
+    assert!(glue.contains("UnionBytesString::Bytes(b) => match unsafe { mquickjs_rs::handles::typed_array::new_uint8_array_raw(ctx, &b) }"));
+}
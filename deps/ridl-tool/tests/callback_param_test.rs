@@ -0,0 +1,73 @@
+use std::fs;
+
+fn generate(ridl: &str) -> (String, String) {
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+    (glue, api)
+}
+
+/// Body of one generated `extern "C"` glue function.
+fn glue_fn<'a>(glue: &'a str, name: &str) -> &'a str {
+    let start = glue
+        .find(&format!("pub unsafe extern \"C\" fn {name}("))
+        .unwrap_or_else(|| panic!("missing glue fn {name}"));
+    let body = &glue[start..];
+    &body[..body.find("\n}\n").unwrap_or(body.len())]
+}
+
+#[test]
+fn global_function_with_callback_opens_env() {
+    let (glue, _api) = generate(
+        r#"
+mode strict;
+
+fn setTimeout(handler: callback(), delay: f64 = 0, ...args: any) -> i32;
+fn clearTimeout(id: i32? = null) -> void;
+"#,
+    );
+
+    // Callback params are checked for callability and handed over as Local<Function>.
+    assert!(glue.contains("scope.value(v).as_function(&scope)"));
+    assert!(glue.contains("invalid callback argument: handler"));
+
+    // Functions that take callbacks/any get a scope and `&mut env` as first argument.
+    let set_timeout = glue_fn(&glue, "js_settimeout");
+    assert!(set_timeout.contains("ContextToken::from_js_ctx(ctx)"));
+    assert!(set_timeout.contains("let mut env = mquickjs_rs::Env::new(&scope);"));
+    assert!(set_timeout.contains("set_timeout(\n        &mut env, handler, delay, args"));
+
+    // Plain functions stay scope-free.
+    let clear_timeout = glue_fn(&glue, "js_cleartimeout");
+    assert!(!clear_timeout.contains("Env::new"));
+    assert!(clear_timeout.contains("clear_timeout(id"));
+}
+
+#[test]
+fn singleton_callback_param_is_ctx_bound_in_api() {
+    let (glue, api) = generate(
+        r#"
+singleton bus {
+    fn on(name: string, handler: callback(payload: any)) -> void;
+}
+"#,
+    );
+
+    assert!(api.contains(
+        "handler: mquickjs_rs::handles::local::Local<'ctx, mquickjs_rs::handles::local::Function>"
+    ));
+    assert!(api.contains("env: &mut mquickjs_rs::Env<'ctx>"));
+    assert!(glue.contains("invalid callback argument: handler"));
+}
@@ -0,0 +1,241 @@
+use ridl_tool::compat::{compare_sources, ChangeLevel};
+
+const BASE: &str = r#"
+module net.http@1.2.0
+fn fetch(url: string, timeout: i32) -> string;
+singleton client {
+    fn get(url: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+
+#[test]
+fn identical_files_have_no_changes() {
+    let report = compare_sources(BASE, BASE).unwrap();
+    assert!(report.changes.is_empty());
+    assert_eq!(report.required_bump(), None);
+    assert!(report.check().is_ok());
+}
+
+#[test]
+fn removed_method_is_breaking() {
+    let new = r#"
+module net.http@1.3.0
+fn fetch(url: string, timeout: i32) -> string;
+singleton client {
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, new).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Breaking));
+    assert!(report
+        .changes
+        .iter()
+        .any(|c| c.path == "singleton client.get" && c.level == ChangeLevel::Breaking));
+    let err = report.check().unwrap_err();
+    assert!(err.contains("too small"), "{err}");
+}
+
+#[test]
+fn narrowed_param_and_changed_return_are_breaking() {
+    let new = r#"
+module net.http@2.0.0
+fn fetch(url: string, timeout: i32) -> i32;
+singleton client {
+    fn get(url: i32) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, new).unwrap();
+    let breaking: Vec<_> = report
+        .changes
+        .iter()
+        .filter(|c| c.level == ChangeLevel::Breaking)
+        .collect();
+    assert_eq!(breaking.len(), 2, "{:?}", report.changes);
+    assert!(report.check().is_ok());
+}
+
+#[test]
+fn new_required_param_is_breaking_but_optional_is_minor() {
+    let required = r#"
+module net.http@1.3.0
+fn fetch(url: string, timeout: i32, retries: i32) -> string;
+singleton client {
+    fn get(url: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, required).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Breaking));
+
+    let optional = r#"
+module net.http@1.3.0
+fn fetch(url: string, timeout: i32, retries: i32?) -> string;
+singleton client {
+    fn get(url: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, optional).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Minor));
+    assert!(report.check().is_ok());
+}
+
+#[test]
+fn added_member_requires_minor_bump() {
+    let new = r#"
+module net.http@1.2.1
+fn fetch(url: string, timeout: i32) -> string;
+fn ping() -> bool;
+singleton client {
+    fn get(url: string) -> string;
+    fn post(url: string, body: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, new).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Minor));
+    assert!(report.check().is_err());
+}
+
+#[test]
+fn widened_param_and_rename_are_compatible() {
+    let new = r#"
+module net.http@1.3.0
+fn fetch(target: string, timeout: i32 | string) -> string;
+singleton client {
+    fn get(url: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, new).unwrap();
+    assert!(report
+        .changes
+        .iter()
+        .any(|c| c.level == ChangeLevel::Patch && c.message.contains("renamed")));
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Minor));
+    assert!(report.check().is_ok());
+}
+
+#[test]
+fn missing_version_is_reported() {
+    let new = r#"
+fn fetch(url: string, timeout: i32) -> string;
+"#;
+    let report = compare_sources(BASE, new).unwrap();
+    assert!(report.check().is_err());
+}
+
+#[test]
+fn default_value_changes_are_classified() {
+    let with_default = r#"
+module net.http@1.3.0
+fn fetch(url: string, timeout: i32 = 30) -> string;
+singleton client {
+    fn get(url: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(BASE, with_default).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Minor));
+
+    let removed = r#"
+module net.http@1.4.0
+fn fetch(url: string, timeout: i32) -> string;
+singleton client {
+    fn get(url: string) -> string;
+    readonly property ready: bool;
+}
+"#;
+    let report = compare_sources(with_default, removed).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Breaking));
+}
+
+#[test]
+fn unversioned_files_without_changes_pass() {
+    let src = r#"
+fn fetch(url: string) -> string;
+"#;
+    let report = compare_sources(src, src).unwrap();
+    assert!(report.changes.is_empty());
+    assert!(report.check().is_ok());
+}
+
+#[test]
+fn added_struct_fields_are_classified_by_optionality() {
+    let old = r#"
+module net.http@1.2.0
+struct Request { url: string; }
+"#;
+    let required = r#"
+module net.http@1.3.0
+struct Request { url: string; timeout: i32; }
+"#;
+    let report = compare_sources(old, required).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Breaking));
+    assert!(report.check().is_err());
+
+    let optional = r#"
+module net.http@1.3.0
+struct Request { url: string; timeout: i32?; }
+"#;
+    let report = compare_sources(old, optional).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Minor));
+    assert!(report.check().is_ok());
+}
+
+#[test]
+fn overload_sets_are_compared_by_js_name() {
+    let old = r#"
+module gfx@1.2.0
+singleton canvas {
+    fn draw(x: i32, y: i32) -> void;
+}
+"#;
+    // Adding an overload keeps the existing call shape working.
+    let added = r#"
+module gfx@1.3.0
+singleton canvas {
+    fn draw(x: i32, y: i32) -> void;
+    fn draw(label: string) -> void;
+}
+"#;
+    let report = compare_sources(old, added).unwrap();
+    assert_eq!(
+        report.required_bump(),
+        Some(ChangeLevel::Minor),
+        "{:?}",
+        report.changes
+    );
+    assert!(report
+        .changes
+        .iter()
+        .any(|c| c.path == "singleton canvas.draw" && c.message == "overload (string) added"));
+    assert!(report.check().is_ok());
+
+    // Adding a third one to an existing set, and widening a variant, stay minor.
+    let widened = r#"
+module gfx@1.4.0
+singleton canvas {
+    fn draw(x: f64, y: i32) -> void;
+    fn draw(label: string) -> void;
+    fn draw() -> void;
+}
+"#;
+    let report = compare_sources(added, widened).unwrap();
+    assert_eq!(
+        report.required_bump(),
+        Some(ChangeLevel::Minor),
+        "{:?}",
+        report.changes
+    );
+
+    // Dropping a variant (or the whole set) is breaking.
+    let report = compare_sources(added, old.replace("1.2.0", "2.0.0").as_str()).unwrap();
+    assert!(report
+        .changes
+        .iter()
+        .any(|c| c.level == ChangeLevel::Breaking && c.message == "overload (string) removed"));
+    assert!(report.check().is_ok());
+}
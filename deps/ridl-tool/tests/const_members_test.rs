@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ridl_tool::generator::generate_aggregate_consolidated;
+use ridl_tool::plan::{GeneratedPaths, RidlModule, RidlPlan};
+
+const RIDL: &str = r#"
+module gfx@1.0;
+
+const VERSION: string = "1.0-beta";
+const MAX_LAYERS: i32 = 16;
+
+singleton screen {
+    const DPI: f64 = 96;
+    const HDR: bool = true;
+    fn width() -> i32;
+}
+
+class Canvas {
+    const MAX_SIZE: i32 = 4096;
+    constructor();
+}
+"#;
+
+#[test]
+fn constants_are_emitted_into_rom_tables_and_rust_api() {
+    let parsed = ridl_tool::parser::parse_ridl_file(RIDL).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "gfx",
+    )
+    .expect("generate module files");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+
+    assert!(api.contains(r#"pub const VERSION: &str = "1.0-beta";"#), "{api}");
+    assert!(api.contains("pub const MAX_LAYERS: i32 = 16;"));
+    assert!(api.contains("pub mod screen_consts {"));
+    assert!(api.contains("pub const DPI: f64 = 96.0;"));
+    assert!(api.contains("pub const HDR: bool = true;"));
+    assert!(api.contains("pub mod canvas_consts {"));
+    assert!(api.contains("pub const MAX_SIZE: i32 = 4096;"));
+
+    let out_dir = tmp.path().join("agg");
+    fs::create_dir_all(&out_dir).unwrap();
+    let ridl_path = tmp.path().join("gfx.ridl");
+    fs::write(&ridl_path, RIDL).unwrap();
+    let plan = RidlPlan {
+        schema_version: 0,
+        cargo_toml: PathBuf::from("Cargo.toml"),
+        modules: vec![RidlModule {
+            crate_name: "gfx".to_string(),
+            name: "gfx".to_string(),
+            crate_dir: PathBuf::from("."),
+            ridl_files: vec![ridl_path],
+        }],
+        generated: GeneratedPaths {
+            out_dir: out_dir.clone(),
+            mquickjs_ridl_register_h: out_dir.join("mquickjs_ridl_register.h"),
+            mquickjs_ridl_module_class_ids_h: out_dir.join("mquickjs_ridl_module_class_ids.h"),
+            mqjs_ridl_user_class_ids_h: out_dir.join("mqjs_ridl_user_class_ids.h"),
+            ridl_class_id_rs: out_dir.join("ridl_class_id.rs"),
+        },
+        inputs: vec![],
+    };
+    generate_aggregate_consolidated(&plan, &out_dir).unwrap();
+    let hdr = fs::read_to_string(out_dir.join("mquickjs_ridl_register.h")).unwrap();
+
+    // Module object + singleton + class static props all come from ROM tables.
+    assert!(
+        hdr.contains(r#"JS_PROP_STRING_DEF("VERSION", "1.0-beta", 0),"#),
+        "{hdr}"
+    );
+    assert!(hdr.contains(r#"JS_PROP_DOUBLE_DEF("MAX_LAYERS", 16, 0),"#));
+    assert!(hdr.contains(r#"JS_PROP_DOUBLE_DEF("DPI", 96.0, 0), \"#));
+    assert!(hdr.contains(r#"JS_CGETSET_DEF("HDR", js_ridl_const_true, NULL), \"#));
+    assert!(hdr.contains("js_gfx_class_canvas_class_props[] = {"));
+    assert!(hdr.contains(r#"JS_PROP_DOUBLE_DEF("MAX_SIZE", 4096, 0),"#));
+}
+
+#[test]
+fn invalid_constants_are_rejected() {
+    let err = ridl_tool::parser::parse_ridl_file("const X: i32 = \"nope\";")
+        .expect_err("type mismatch must be rejected");
+    assert!(err.to_string().contains("value for const 'X'"), "{err}");
+
+    let err = ridl_tool::parser::parse_ridl_file("const X: string? = null;")
+        .expect_err("nullable const must be rejected");
+    assert!(err.to_string().contains("is not allowed"), "{err}");
+
+    let parsed = ridl_tool::parser::parse_ridl_file(
+        "singleton s {\n    const width: i32 = 1;\n    fn width() -> i32;\n}\n",
+    )
+    .expect("parse ridl");
+    let err = ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode)
+        .expect_err("name clash must be rejected");
+    assert!(
+        err.to_string()
+            .contains("Invalid const 'width': name is already used by another member of singleton 's'"),
+        "{err}"
+    );
+}
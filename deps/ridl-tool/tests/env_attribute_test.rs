@@ -0,0 +1,111 @@
+use std::fs;
+
+fn generate(ridl: &str) -> (String, String) {
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+    (glue, api)
+}
+
+#[test]
+fn env_attribute_passes_env_to_plain_typed_functions_and_methods() {
+    let (glue, api) = generate(
+        r#"
+[env] fn forget(id: i32? = null) -> void;
+fn plain(id: i32) -> i32;
+singleton clock {
+    [env] fn now() -> f64;
+    fn label() -> string;
+}
+"#,
+    );
+
+    // Free function: the glue opens a scope and hands the impl `&mut env` first.
+    let forget = glue
+        .split("pub unsafe extern \"C\" fn js_forget(")
+        .nth(1)
+        .expect("js_forget glue");
+    let forget = forget.split("\n}\n").next().unwrap();
+    assert!(forget.contains("let mut env = mquickjs_rs::Env::new(&scope);"));
+    assert!(forget.contains("&mut env, id"));
+
+    let plain = glue
+        .split("pub unsafe extern \"C\" fn js_plain(")
+        .nth(1)
+        .expect("js_plain glue");
+    let plain = plain.split("\n}\n").next().unwrap();
+    assert!(!plain.contains("Env::new"));
+
+    // Singleton method: the trait signature takes the Env.
+    let now = api.split("fn now<'ctx>(").nth(1).expect("now in trait");
+    let now = now.split(") ->").next().unwrap();
+    assert!(now.contains("env: &mut mquickjs_rs::Env<'ctx>"));
+    let label = api.split("fn label<'ctx>(").nth(1).expect("label in trait");
+    let label = label.split(") ->").next().unwrap();
+    assert!(!label.contains("Env"));
+}
+
+#[test]
+fn unknown_function_attributes_are_rejected() {
+    let err = ridl_tool::parser::parse_ridl_file("[async] fn f() -> void;").unwrap_err();
+    assert!(err.to_string().contains("unknown attribute: [async]"));
+}
+
+#[test]
+fn env_attribute_on_singleton_properties() {
+    let (glue, api) = generate(
+        r#"
+singleton clock {
+    [env] readonly property origin: f64;
+    readonly property ready: bool;
+}
+"#,
+    );
+
+    assert!(api.contains("fn origin<'ctx>(&self, env: &mut mquickjs_rs::Env<'ctx>) -> f64;"));
+    assert!(api.contains("fn ready(&self) -> bool;"));
+
+    let origin = glue
+        .split("pub unsafe extern \"C\" fn js_global_singleton_clock_get_origin(")
+        .nth(1)
+        .expect("origin getter glue");
+    let origin = origin.split("\n}\n").next().unwrap();
+    assert!(origin.contains("let result = singleton.origin(&mut env);"));
+    assert!(origin.contains("JS_NewFloat64(ctx, result)"));
+
+    // Non-bool getters convert the value itself; only bool goes through the 0/1 form.
+    let ready = glue
+        .split("pub unsafe extern \"C\" fn js_global_singleton_clock_get_ready(")
+        .nth(1)
+        .expect("ready getter glue");
+    let ready = ready.split("\n}\n").next().unwrap();
+    assert!(ready.contains("let result = singleton.ready();"));
+    assert!(ready.contains("let result = if result { 1 } else { 0 };"));
+}
+
+#[test]
+fn env_attribute_is_rejected_on_class_properties() {
+    let err = ridl_tool::parser::parse_ridl_file(
+        r#"
+class Point {
+    [env] readonly property x: i32;
+}
+"#,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("[env] is only supported on singleton properties: Point.x"));
+}
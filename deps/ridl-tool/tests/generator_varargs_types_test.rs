@@ -28,7 +28,7 @@ fn h(...rest_b: bool) -> void;
 
     // string varargs: check + to cstring.
     assert!(glue.contains("invalid string argument: rest_s["));
-    assert!(glue.contains("JS_IsString"));
+    assert!(glue.contains("!= mquickjs_rs::ValueKind::String"));
     assert!(glue.contains("JS_ToCString"));
 
     // f64 varargs: check + to number.
@@ -37,5 +37,49 @@ fn h(...rest_b: bool) -> void;
 
     // bool varargs: tag check.
     assert!(glue.contains("invalid bool argument: rest_b["));
-    assert!(glue.contains("JS_TAG_SPECIAL_BITS"));
+    assert!(glue.contains("!= mquickjs_rs::ValueKind::Bool"));
+}
+
+#[test]
+fn glue_argument_type_checks_go_through_value_kind() {
+    let ridl = r#"
+class Point {
+    fn x() -> i32;
+}
+fn takes_point(p: Point) -> void;
+fn takes_map(m: map<string, i32>) -> void;
+fn takes_opt(s: string?) -> void;
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    assert!(glue.contains(
+        "!= mquickjs_rs::ValueKind::RidlClass(mquickjs_rs::ridl_js_class_id::JS_CLASS_GLOBAL_POINT)"
+    ), "{glue}");
+    assert!(glue.contains("!= mquickjs_rs::mquickjs_ffi::JSObjectClassEnum_JS_CLASS_OBJECT as i32 { return js_throw_type_error(ctx, \"map: expected object\"); }"));
+    assert!(glue.contains("if __ridl_kind.is_nullish() {"));
+
+    // Receiver checks and the plain-object check of maps aside, argument conversion no longer
+    // inspects raw tags or class ids.
+    let args_only: String = glue
+        .lines()
+        .filter(|l| !l.contains("this_val") && !l.trim_start().starts_with("//"))
+        .filter(|l| !l.contains("map: expected object"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(!args_only.contains("JS_GetClassID"), "{glue}");
+    assert!(!args_only.contains("JS_IsNumber"), "{glue}");
+    assert!(!args_only.contains("JS_IsString"), "{glue}");
 }
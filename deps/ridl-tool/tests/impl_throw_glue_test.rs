@@ -0,0 +1,78 @@
+use std::fs;
+
+use ridl_tool::{
+    generator::generate_module_files, parser::parse_ridl_file, validator::validate_with_mode,
+};
+
+fn generate(ridl: &str) -> String {
+    let parsed = parse_ridl_file(ridl).expect("parse ridl");
+    validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+    fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue")
+}
+
+#[test]
+fn every_impl_call_checks_for_a_pending_throw() {
+    let glue = generate(
+        r#"
+fn parse(s: string) -> string;
+
+singleton codec {
+    fn decode(s: string) -> bytes;
+}
+
+class Decoder {
+    constructor(label: string? = null);
+    fn decode(input: bytes) -> string;
+}
+"#,
+    );
+
+    let check =
+        "if let Some(exc) = unsafe { mquickjs_rs::ridl_runtime::take_pending_throw(ctx) } {";
+    // global fn, singleton method, class method, constructor
+    assert_eq!(glue.matches(check).count(), 4, "{glue}");
+    // A constructor that threw does not attach its instance.
+    assert!(glue.contains("drop(inst);\n        return exc;"), "{glue}");
+}
+
+#[test]
+fn accessor_glue_checks_for_a_pending_throw() {
+    let glue = generate(
+        r#"
+singleton codec {
+    readonly property label: string;
+}
+
+class Decoder {
+    property mode: i32;
+    proto property greeting: string;
+}
+"#,
+    );
+
+    let check =
+        "if let Some(exc) = unsafe { mquickjs_rs::ridl_runtime::take_pending_throw(ctx) } {";
+    // singleton getter, class getter + setter, proto getter + setter, default constructor
+    assert_eq!(glue.matches(check).count(), 6, "{glue}");
+}
+
+#[test]
+fn string_params_and_returns_keep_interior_nuls() {
+    let glue = generate("fn echo(s: string) -> string;\n");
+
+    assert!(glue.contains(
+        "let s: String = match unsafe { mquickjs_rs::handles::string::to_rust_string_raw(ctx, v) }"
+    ));
+    assert!(glue.contains("mquickjs_rs::handles::string::new_string_raw(ctx, &result)"));
+    assert!(!glue.contains("CStr::from_ptr(ptr)"), "{glue}");
+}
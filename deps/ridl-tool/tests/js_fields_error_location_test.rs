@@ -25,6 +25,7 @@ class A {
         imports: vec![],
         singletons: vec![],
         callbacks: vec![],
+        constants: vec![],
     };
     for item in &parsed.items {
         if let ridl_tool::parser::ast::IDLItem::Class(c) = item {
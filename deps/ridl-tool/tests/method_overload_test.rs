@@ -0,0 +1,105 @@
+use std::fs;
+
+#[test]
+fn overloaded_methods_get_dispatcher_and_typed_variants() {
+    let ridl = r#"
+class Point {
+    constructor(x: i32, y: i32);
+}
+
+class Canvas {
+    constructor();
+    fn draw(x: i32, y: i32) -> void;
+    fn draw(p: Point) -> void;
+    fn draw(label: string, size: i32 = 12) -> void;
+}
+
+singleton gfx {
+    fn scale(f: f64) -> f64;
+    fn scale() -> f64;
+}
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+
+    // Each variant becomes an ordinary trait method with a type-suffixed name.
+    assert!(api.contains("fn draw_i32_i32<"));
+    assert!(api.contains("fn draw_point<"));
+    assert!(api.contains("fn draw_string_i32<"));
+    assert!(api.contains("fn scale_f64<"));
+    assert!(api.contains("fn scale_none<"));
+
+    // The JS-visible name is a dispatcher that forwards to the variants.
+    assert!(glue.contains("js_global_class_canvas_draw("));
+    assert!(glue.contains("js_global_class_canvas_draw_point(ctx, this_val, argc, argv)"));
+    assert!(glue.contains("mquickjs_rs::ValueKind::RidlClass(mquickjs_rs::ridl_js_class_id::JS_CLASS_GLOBAL_POINT)"));
+    assert!(glue.contains("js_global_singleton_gfx_scale("));
+    assert!(glue.contains("no overload of draw matches the given arguments"));
+
+    // Extra trailing arguments are ignored (no upper arity bound); longer variants are tried
+    // first so such calls reach the variant that uses the most arguments.
+    let scale = glue
+        .split("pub unsafe extern \"C\" fn js_global_singleton_gfx_scale(")
+        .nth(1)
+        .expect("scale dispatcher");
+    let scale = scale.split("\n}\n").next().unwrap();
+    assert!(!scale.contains("argc <="), "{scale}");
+    let f64_at = scale.find("js_global_singleton_gfx_scale_f64(").unwrap();
+    let none_at = scale.find("js_global_singleton_gfx_scale_none(").unwrap();
+    assert!(f64_at < none_at, "{scale}");
+
+    let draw = glue
+        .split("fn js_global_class_canvas_draw(")
+        .nth(1)
+        .expect("draw dispatcher");
+    let draw = draw.split("\n}\n").next().unwrap();
+    let order: Vec<usize> = ["draw_i32_i32(", "draw_string_i32(", "draw_point("]
+        .iter()
+        .map(|v| draw.find(v).unwrap())
+        .collect();
+    assert!(order.windows(2).all(|w| w[0] < w[1]), "{draw}");
+}
+
+#[test]
+fn ambiguous_overloads_are_rejected() {
+    let ridl = r#"
+singleton gfx {
+    fn scale(f: f64) -> f64;
+    fn scale(n: i32) -> f64;
+}
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    let errs = ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode)
+        .expect_err("ambiguous overloads must be rejected");
+    let msg = errs.to_string();
+    assert!(msg.contains("Ambiguous overloads of 'gfx.scale'"), "{msg}");
+}
+
+#[test]
+fn overloading_global_functions_is_rejected() {
+    let ridl = r#"
+fn log(msg: string) -> void;
+fn log(n: i32) -> void;
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    let errs = ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode)
+        .expect_err("global overloads are not supported");
+    let msg = errs.to_string();
+    assert!(msg.contains("only supported on singleton/class methods"), "{msg}");
+}
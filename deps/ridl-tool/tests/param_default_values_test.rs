@@ -0,0 +1,110 @@
+use std::fs;
+
+#[test]
+fn glue_substitutes_defaults_and_api_receives_concrete_values() {
+    let ridl = r#"
+singleton net {
+    fn connect(host: string, port: i32 = 8080, secure: bool = false, label: string? = "main") -> bool;
+}
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+
+    // Required param keeps the missing-argument check; defaulted ones do not.
+    assert!(glue.contains("missing argument: host"));
+    assert!(!glue.contains("missing argument: port"));
+
+    // Missing or undefined => default value.
+    assert!(glue.contains("let port: i32 = if argc <= 1"));
+    assert!(glue.contains("JS_TAG_UNDEFINED"));
+    assert!(glue.contains("8080"));
+    assert!(glue.contains("let secure: bool = if argc <= 2"));
+    assert!(glue.contains("Some(String::from(\"main\"))"));
+
+    // Trait receives concrete values, and the defaults are documented.
+    assert!(api.contains("port: i32"));
+    assert!(api.contains("secure: bool"));
+    assert!(api.contains("/// Defaults: `port = 8080`, `secure = false`, `label = \"main\"`."));
+}
+
+#[test]
+fn global_function_defaults_are_substituted_and_documented_in_glue() {
+    let ridl = r#"
+fn connect(host: string, port: i32 = 8080, budget: i64 = -9007199254740991) -> bool;
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    assert!(glue.contains("missing argument: host"));
+    assert!(!glue.contains("missing argument: port"));
+    assert!(glue.contains("let port: i32 = if argc <= 1"));
+    assert!(glue.contains("-9007199254740991"));
+    assert!(glue.contains(
+        "/// Defaults: `port = 8080`, `budget = -9007199254740991`.\n#[unsafe(no_mangle)]\npub unsafe extern \"C\" fn js_connect("
+    ));
+
+    // Out-of-range i64 defaults are rejected at parse time.
+    let err = ridl_tool::parser::parse_ridl_file("fn f(n: i64 = 9007199254740992);").unwrap_err();
+    assert!(err.to_string().contains("out of i64 range"), "{err}");
+}
+
+#[test]
+fn string_default_spelled_null_is_not_the_null_literal() {
+    let ridl = r#"
+fn label(a: string? = "null", b: string? = null);
+"#;
+
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let a = glue
+        .split("let a: Option<String> = ")
+        .nth(1)
+        .expect("a glue");
+    assert!(a.starts_with("if argc <= 0"), "{a}");
+    let a_default = a.lines().nth(1).unwrap().trim();
+    assert_eq!(a_default, "Some(String::from(\"null\"))");
+    let b = glue
+        .split("let b: Option<String> = ")
+        .nth(1)
+        .expect("b glue");
+    assert_eq!(b.lines().nth(1).unwrap().trim(), "None");
+    assert!(glue.contains("/// Defaults: `a = \"null\"`, `b = null`."));
+}
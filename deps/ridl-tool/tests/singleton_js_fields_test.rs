@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ridl_tool::generator::generate_aggregate_consolidated;
+use ridl_tool::plan::{GeneratedPaths, RidlModule, RidlPlan};
+
+const RIDL: &str = r#"
+singleton TestJsFields {
+    var label: string = "hello";
+    var count: i32 = 3;
+    proto var enabled: bool = true;
+    fn getNullAny() -> any;
+}
+"#;
+
+#[test]
+fn singleton_js_fields_are_installed_by_context_init() {
+    let parsed = ridl_tool::parser::parse_ridl_file(RIDL).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "test_js_fields",
+    )
+    .expect("generate module files");
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+
+    assert!(glue.contains(
+        "pub unsafe fn ridl_install_test_js_fields_js_fields(ctx: *mut JSContext) -> JSValue"
+    ));
+    assert!(glue.contains(r#"CString::new("label")"#));
+    assert!(glue.contains(r#"CString::new("hello")"#));
+    assert!(glue.contains("JS_NewInt32(ctx, 3)"));
+    assert!(glue.contains(r#"CString::new("enabled")"#));
+
+    let out_dir = tmp.path().join("agg");
+    fs::create_dir_all(&out_dir).unwrap();
+    let ridl_path = tmp.path().join("test_js_fields.ridl");
+    fs::write(&ridl_path, RIDL).unwrap();
+    let plan = RidlPlan {
+        schema_version: 0,
+        cargo_toml: PathBuf::from("Cargo.toml"),
+        modules: vec![RidlModule {
+            crate_name: "ridl_test_g_js_fields".to_string(),
+            name: "test_js_fields".to_string(),
+            crate_dir: PathBuf::from("."),
+            ridl_files: vec![ridl_path],
+        }],
+        generated: GeneratedPaths {
+            out_dir: out_dir.clone(),
+            mquickjs_ridl_register_h: out_dir.join("mquickjs_ridl_register.h"),
+            mquickjs_ridl_module_class_ids_h: out_dir.join("mquickjs_ridl_module_class_ids.h"),
+            mqjs_ridl_user_class_ids_h: out_dir.join("mqjs_ridl_user_class_ids.h"),
+            ridl_class_id_rs: out_dir.join("ridl_class_id.rs"),
+        },
+        inputs: vec![],
+    };
+    generate_aggregate_consolidated(&plan, &out_dir).unwrap();
+    let ext = fs::read_to_string(out_dir.join("ridl_context_ext.rs")).unwrap();
+    assert!(
+        ext.contains("ridl_test_g_js_fields::ridl_install_test_js_fields_js_fields(ctx)"),
+        "{ext}"
+    );
+    // Installer failures must fail context init instead of being dropped.
+    assert!(
+        ext.contains(
+            "pub unsafe fn ridl_context_init(ctx: *mut mquickjs_ffi::JSContext) -> Result<(), String>"
+        ),
+        "{ext}"
+    );
+    assert!(
+        ext.contains(
+            "check_init(ctx, ridl_test_g_js_fields::ridl_install_test_js_fields_js_fields(ctx)"
+        ),
+        "{ext}"
+    );
+}
+
+#[test]
+fn singleton_js_field_name_clash_is_rejected() {
+    let ridl = r#"
+singleton S {
+    var width: i32 = 1;
+    readonly property width: i32;
+}
+"#;
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    let err = ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode)
+        .expect_err("clash must be rejected");
+    assert!(
+        err.to_string()
+            .contains("js-only fields cannot share name with native property in singleton 'S'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn module_singleton_js_fields_are_rejected() {
+    let ridl = r#"
+module system.test@1.0
+singleton S {
+    var width: i32 = 1;
+}
+"#;
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    let err = ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode)
+        .expect_err("module-mode var fields must be rejected");
+    assert!(
+        err.to_string()
+            .contains("JS-only field 'width' is not supported in module 'system.test'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn singleton_js_field_string_literals_are_escaped() {
+    let ridl = r#"
+singleton S {
+    var label: string = "tab\\there\\nnext";
+}
+"#;
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "s",
+    )
+    .expect("generate module files");
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    assert!(
+        glue.contains(r#"CString::new("tab\there\nnext")"#),
+        "{glue}"
+    );
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ridl_tool::generator::generate_aggregate_consolidated;
+use ridl_tool::plan::{GeneratedPaths, RidlModule, RidlPlan};
+
+fn generate(ridl: &str, crate_name: &str) -> (String, String) {
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        crate_name,
+    )
+    .expect("generate module files");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+
+    let out_dir = tmp.path().join("agg");
+    fs::create_dir_all(&out_dir).unwrap();
+    let ridl_path = tmp.path().join("m.ridl");
+    fs::write(&ridl_path, ridl).unwrap();
+    let plan = RidlPlan {
+        schema_version: 0,
+        cargo_toml: PathBuf::from("Cargo.toml"),
+        modules: vec![RidlModule {
+            crate_name: crate_name.to_string(),
+            name: crate_name.to_string(),
+            crate_dir: PathBuf::from("."),
+            ridl_files: vec![ridl_path],
+        }],
+        generated: GeneratedPaths {
+            out_dir: out_dir.clone(),
+            mquickjs_ridl_register_h: out_dir.join("mquickjs_ridl_register.h"),
+            mquickjs_ridl_module_class_ids_h: out_dir.join("mquickjs_ridl_module_class_ids.h"),
+            mqjs_ridl_user_class_ids_h: out_dir.join("mqjs_ridl_user_class_ids.h"),
+            ridl_class_id_rs: out_dir.join("ridl_class_id.rs"),
+        },
+        inputs: vec![],
+    };
+    generate_aggregate_consolidated(&plan, &out_dir).unwrap();
+    let ext = fs::read_to_string(out_dir.join("ridl_context_ext.rs")).unwrap();
+    (api, ext)
+}
+
+#[test]
+fn singleton_trait_object_names_its_slot() {
+    let (api, ext) = generate(
+        r#"
+singleton console {
+    fn log(message: string);
+}
+"#,
+        "stdlib",
+    );
+    assert!(
+        api.contains(
+            "unsafe impl mquickjs_rs::ridl_runtime::RidlSingleton for dyn ConsoleSingleton"
+        ),
+        "{api}"
+    );
+    assert!(api.contains(r#"const SLOT_KEY: &'static str = "singleton_global_console";"#));
+
+    // The aggregated context init prefers a host override over the module factory.
+    assert!(
+        ext.contains(r#"h.inner.take_singleton_override("singleton_global_console")"#),
+        "{ext}"
+    );
+    assert!(ext.contains("pub singleton_global_console: ErasedCtxSlot"));
+}
+
+#[test]
+fn module_singleton_key_includes_module_path() {
+    let (api, ext) = generate(
+        r#"
+module demo.log@1.0
+
+singleton Logger {
+    fn write(line: string);
+}
+"#,
+        "demo_log",
+    );
+    assert!(api.contains(r#"const SLOT_KEY: &'static str = "singleton_demo_log_logger";"#));
+    assert!(ext.contains(r#"take_singleton_override("singleton_demo_log_logger")"#));
+}
+
+#[test]
+fn global_singletons_are_gated_by_context_permissions() {
+    let (_, ext) = generate(
+        r#"
+singleton console {
+    fn log(message: string);
+}
+"#,
+        "stdlib",
+    );
+    assert!(
+        ext.contains(r#"if !h.inner.singleton_visible("console") {"#),
+        "{ext}"
+    );
+    assert!(
+        ext.contains(r#"::mquickjs_rs::ridl_runtime::hide_global(ctx, "console")?"#),
+        "{ext}"
+    );
+
+    // Module singletons are only reachable through `require`, which is checked instead.
+    let (_, ext) = generate(
+        r#"
+module demo.log@1.0
+
+singleton Logger {
+    fn write(line: string);
+}
+"#,
+        "demo_log",
+    );
+    assert!(!ext.contains("singleton_visible"), "{ext}");
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+fn generate(ridl: &str) -> (String, String) {
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+    (glue, api)
+}
+
+#[test]
+fn structs_are_declared_in_api_and_converted_in_glue() {
+    let (glue, api) = generate(
+        r#"
+struct DecoderOptions {
+    fatal: bool?;
+    ignoreBOM: bool?;
+}
+struct Stat {
+    size: f64;
+    isFile: bool;
+}
+class Decoder {
+    constructor(label: string? = null, options: DecoderOptions? = null);
+}
+fn stat(path: string) -> Stat;
+fn list(path: string) -> array<string>;
+"#,
+    );
+
+    // api.rs: plain Rust structs with snake_case fields, no conversion code.
+    assert!(api.contains("pub struct DecoderOptions {"));
+    assert!(api.contains("pub ignore_bom: Option<bool>,"));
+    assert!(api.contains("pub is_file: bool,"));
+    assert!(!api.contains("FromJs"));
+
+    // glue.rs: FromJs/IntoJs keep the JS property names.
+    assert!(glue.contains("impl<'ctx> mquickjs_rs::FromJs<'ctx> for crate::api::DecoderOptions {"));
+    assert!(glue.contains("ignore_bom: r.field(\"ignoreBOM\")?,"));
+    assert!(glue.contains("impl<'ctx> mquickjs_rs::IntoJs<'ctx> for crate::api::Stat {"));
+    assert!(glue.contains("w.field(\"isFile\", self.is_file)?;"));
+
+    // Optional struct param with a null default: decoded through FromJs, named in errors.
+    let ctor = glue
+        .split("pub unsafe extern \"C\" fn js_global_class_decoder_constructor(")
+        .nth(1)
+        .expect("constructor glue");
+    let ctor = ctor.split("\n}\n").next().unwrap();
+    assert!(ctor.contains("let options: Option<crate::api::DecoderOptions> = if argc <= 1"));
+    assert!(ctor.contains("<crate::api::DecoderOptions as mquickjs_rs::FromJs>::from_js("));
+    assert!(ctor.contains("invalid DecoderOptions argument: options:"));
+
+    // Struct and array results go through IntoJs.
+    for name in ["stat", "list"] {
+        let f = glue
+            .split(&format!("pub unsafe extern \"C\" fn js_{name}("))
+            .nth(1)
+            .expect("function glue");
+        let f = f.split("\n}\n").next().unwrap();
+        assert!(f.contains("mquickjs_rs::IntoJs::into_js(result, &__ridl_scope)"));
+    }
+}
+
+#[test]
+fn struct_fields_must_be_plain_values() {
+    let parsed = ridl_tool::parser::parse_ridl_file(
+        r#"
+struct Entry {
+    names: array<string>;
+}
+"#,
+    )
+    .expect("parse ridl");
+    let err = ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unsupported struct field type: Entry.names"));
+}
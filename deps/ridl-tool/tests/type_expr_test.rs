@@ -0,0 +1,40 @@
+use ridl_tool::parser::ast::{IDLItem, Type};
+use ridl_tool::parser::normalize::normalize_type;
+use ridl_tool::parser::{parse_ridl_file, parse_type_expr};
+
+#[test]
+fn type_expressions_parse_and_normalize_like_idl_types() {
+    assert_eq!(
+        parse_type_expr("array<string>").unwrap(),
+        Type::Array(Box::new(Type::String))
+    );
+    assert_eq!(
+        parse_type_expr(" i32 | string | null ").unwrap(),
+        Type::Optional(Box::new(Type::Union(vec![Type::I32, Type::String])))
+    );
+    assert_eq!(
+        parse_type_expr("map<string, Server?>").unwrap(),
+        Type::Map(
+            Box::new(Type::String),
+            Box::new(Type::Optional(Box::new(Type::Custom("Server".into()))))
+        )
+    );
+    assert_eq!(
+        parse_type_expr("(string | i32)?").unwrap(),
+        Type::Optional(Box::new(Type::Union(vec![Type::String, Type::I32])))
+    );
+    assert!(parse_type_expr("array<").is_err());
+    assert!(parse_type_expr("i32 extra").is_err());
+}
+
+#[test]
+fn using_aliases_are_normalized() {
+    let parsed = parse_ridl_file("using MaybeName = string | null;").unwrap();
+    let IDLItem::Using(u) = &parsed.items[0] else {
+        panic!("expected using, got {:?}", parsed.items[0]);
+    };
+    assert_eq!(u.alias_type, Type::Optional(Box::new(Type::String)));
+
+    // Normalization is idempotent, so callers may normalize already-normalized types.
+    assert_eq!(normalize_type(u.alias_type.clone()).unwrap(), u.alias_type);
+}
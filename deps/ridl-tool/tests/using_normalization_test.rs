@@ -0,0 +1,69 @@
+use std::fs;
+
+use ridl_tool::compat::{compare_sources, ChangeLevel};
+
+fn generate(ridl: &str) -> (String, String) {
+    let parsed = ridl_tool::parser::parse_ridl_file(ridl).expect("parse ridl");
+    ridl_tool::validator::validate_with_mode(&parsed.items, parsed.mode).expect("validate ridl");
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    ridl_tool::generator::generate_module_files(
+        &parsed.items,
+        parsed.module.clone(),
+        parsed.mode,
+        tmp.path(),
+        "demo",
+    )
+    .expect("generate module files");
+
+    let glue = fs::read_to_string(tmp.path().join("glue.rs")).expect("read glue");
+    let api = fs::read_to_string(tmp.path().join("api.rs")).expect("read api");
+    (glue, api)
+}
+
+#[test]
+fn alias_spellings_generate_the_same_code() {
+    // `using` aliases are normalized like every other type; they emit no Rust code, so the
+    // spelling of an alias must not leak into the generated files.
+    let (glue_union, api_union) = generate(
+        r#"
+using MaybeName = string | null;
+using Id = (i32 | string);
+fn greet(name: string?) -> string;
+"#,
+    );
+    let (glue_optional, api_optional) = generate(
+        r#"
+using MaybeName = string?;
+using Id = i32 | string;
+fn greet(name: string?) -> string;
+"#,
+    );
+
+    assert_eq!(api_union, api_optional);
+    assert_eq!(glue_union, glue_optional);
+    assert!(!api_union.contains("MaybeName"), "{api_union}");
+    assert!(!glue_union.contains("MaybeName"), "{glue_union}");
+}
+
+#[test]
+fn respelled_alias_is_not_a_change() {
+    let old = r#"
+module net.http@1.0.0
+using MaybeName = string | null;
+"#;
+    let new = r#"
+module net.http@1.0.0
+using MaybeName = string?;
+"#;
+    let report = compare_sources(old, new).unwrap();
+    assert!(report.changes.is_empty(), "{:?}", report.changes);
+
+    // A real change of the aliased type is still reported.
+    let widened = r#"
+module net.http@2.0.0
+using MaybeName = (string | i32)?;
+"#;
+    let report = compare_sources(old, widened).unwrap();
+    assert_eq!(report.required_bump(), Some(ChangeLevel::Breaking));
+}
@@ -0,0 +1,16 @@
+//! `std.fs`: sandboxed file access for scripts, obtained with `require("std.fs")`.
+//!
+//! The module is opt-in per context. Install `require_check` on the context builder so that
+//! `require` refuses `std.fs` unless the host called `enable` with an `FsConfig` for that
+//! context; scripts then only see the configured root directories.
+
+mquickjs_rs::ridl_include_module!();
+
+pub mod impls {
+    pub use crate::ops::{exists, list, read_bytes, read_text, stat, write_text};
+}
+
+mod ops;
+pub mod sandbox;
+
+pub use sandbox::{FsConfig, config, enable, require_check};
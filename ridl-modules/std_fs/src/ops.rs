@@ -0,0 +1,106 @@
+//! The functions of `std.fs`. Each one resolves its path against the calling context's
+//! `FsConfig` and reports failures with `ridl_runtime::throw_error`.
+
+use std::rc::Rc;
+use std::time::UNIX_EPOCH;
+
+use mquickjs_rs::Env;
+use mquickjs_rs::ridl_runtime::{JsErrorKind, throw_error};
+
+use crate::api::Stat;
+use crate::sandbox::{FsConfig, config};
+
+/// Sandbox refusals are TypeErrors; I/O failures are plain Errors.
+enum Failure {
+    Denied(String),
+    Io(String),
+}
+
+fn context_config(env: &Env<'_>) -> Result<Rc<FsConfig>, Failure> {
+    // The require check keeps the module away from other contexts; this covers a module
+    // object passed over from an enabled context.
+    config(env.scope().token())
+        .ok_or_else(|| Failure::Denied("file system access is not enabled".to_string()))
+}
+
+fn resolve(env: &Env<'_>, path: &str, write: bool) -> Result<std::path::PathBuf, Failure> {
+    context_config(env)?
+        .resolve(path, write)
+        .map_err(Failure::Denied)
+}
+
+fn io_error(path: &str, e: std::io::Error) -> Failure {
+    // The script path, not the host path: scripts should not learn where roots live.
+    Failure::Io(format!("{path}: {e}"))
+}
+
+fn or_throw<T>(r: Result<T, Failure>, fallback: T) -> T {
+    match r {
+        Ok(v) => v,
+        Err(Failure::Denied(msg)) => {
+            throw_error(JsErrorKind::TypeError, msg);
+            fallback
+        }
+        Err(Failure::Io(msg)) => {
+            throw_error(JsErrorKind::Error, msg);
+            fallback
+        }
+    }
+}
+
+pub fn read_text(env: &mut Env<'_>, path: String) -> String {
+    let r = resolve(env, &path, false).and_then(|p| {
+        let bytes = std::fs::read(p).map_err(|e| io_error(&path, e))?;
+        String::from_utf8(bytes).map_err(|_| Failure::Io(format!("{path}: not valid UTF-8")))
+    });
+    or_throw(r, String::new())
+}
+
+pub fn read_bytes(env: &mut Env<'_>, path: String) -> Vec<u8> {
+    let r =
+        resolve(env, &path, false).and_then(|p| std::fs::read(p).map_err(|e| io_error(&path, e)));
+    or_throw(r, Vec::new())
+}
+
+pub fn write_text(env: &mut Env<'_>, path: String, text: String) {
+    let r = resolve(env, &path, true)
+        .and_then(|p| std::fs::write(p, text).map_err(|e| io_error(&path, e)));
+    or_throw(r, ())
+}
+
+pub fn exists(env: &mut Env<'_>, path: String) -> bool {
+    let r = resolve(env, &path, false).map(|p| p.exists());
+    or_throw(r, false)
+}
+
+pub fn list(env: &mut Env<'_>, path: String) -> Vec<String> {
+    let r = resolve(env, &path, false).and_then(|p| {
+        let mut names = std::fs::read_dir(p)
+            .map_err(|e| io_error(&path, e))?
+            .map(|entry| {
+                entry
+                    .map(|e| e.file_name().to_string_lossy().into_owned())
+                    .map_err(|e| io_error(&path, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        names.sort();
+        Ok(names)
+    });
+    or_throw(r, Vec::new())
+}
+
+pub fn stat(env: &mut Env<'_>, path: String) -> Stat {
+    let r = resolve(env, &path, false)
+        .and_then(|p| std::fs::metadata(p).map_err(|e| io_error(&path, e)))
+        .map(|meta| Stat {
+            size: meta.len() as f64,
+            is_file: meta.is_file(),
+            is_directory: meta.is_dir(),
+            mtime_ms: meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs_f64() * 1000.0),
+        });
+    or_throw(r, Stat::default())
+}
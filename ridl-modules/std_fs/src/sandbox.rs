@@ -0,0 +1,142 @@
+//! Which directories a context may touch, and how script paths map onto them.
+//!
+//! A script path is `<root>/<relative path>` (a leading `/` is allowed), where `<root>` is a
+//! name from the context's `FsConfig`. Resolution rejects lexically first (`..`, backslashes,
+//! drive prefixes, unknown roots) and then canonicalizes the deepest existing part of the
+//! path, so a symlink inside a root cannot lead out of it.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use mquickjs_rs::NativeModule;
+use mquickjs_rs::context::ContextToken;
+
+/// Module base name as declared in `std_fs.ridl`.
+pub const MODULE_BASE: &str = "std.fs";
+
+#[derive(Clone, Debug)]
+struct Root {
+    name: String,
+    dir: PathBuf,
+    writable: bool,
+}
+
+/// Directories exposed to a context's scripts, each under a short name.
+#[derive(Clone, Debug, Default)]
+pub struct FsConfig {
+    roots: Vec<Root>,
+}
+
+impl FsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expose `dir` read-only as `<name>/...`.
+    pub fn root(self, name: &str, dir: impl Into<PathBuf>) -> Self {
+        self.add(name, dir.into(), false)
+    }
+
+    /// Expose `dir` as `<name>/...`; `writeText` is allowed below it.
+    pub fn writable_root(self, name: &str, dir: impl Into<PathBuf>) -> Self {
+        self.add(name, dir.into(), true)
+    }
+
+    fn add(mut self, name: &str, dir: PathBuf, writable: bool) -> Self {
+        // A later root of the same name replaces the earlier one.
+        self.roots.retain(|r| r.name != name);
+        self.roots.push(Root {
+            name: name.to_string(),
+            dir,
+            writable,
+        });
+        self
+    }
+
+    /// Map a script path onto the host file system; see the module docs.
+    ///
+    /// `write` requires a writable root. The target itself need not exist.
+    pub fn resolve(&self, path: &str, write: bool) -> Result<PathBuf, String> {
+        if path.contains(['\\', '\0']) {
+            return Err(format!("{path}: invalid path"));
+        }
+        let mut parts = Vec::new();
+        for seg in path.split('/') {
+            match seg {
+                "" | "." => {}
+                ".." => return Err(format!("{path}: '..' is not allowed")),
+                s => {
+                    // Rejects drive prefixes such as `C:` where the platform has them.
+                    let mut c = Path::new(s).components();
+                    if !matches!((c.next(), c.next()), (Some(Component::Normal(_)), None)) {
+                        return Err(format!("{path}: invalid path"));
+                    }
+                    parts.push(s);
+                }
+            }
+        }
+        let Some((name, rest)) = parts.split_first() else {
+            return Err(format!("{path}: path has no root"));
+        };
+        let root = self
+            .roots
+            .iter()
+            .find(|r| r.name == *name)
+            .ok_or_else(|| format!("{path}: unknown root '{name}'"))?;
+        if write && !root.writable {
+            return Err(format!("{path}: root '{name}' is read-only"));
+        }
+
+        let target = rest.iter().fold(root.dir.clone(), |p, s| p.join(s));
+        let real_root = root
+            .dir
+            .canonicalize()
+            .map_err(|e| format!("{path}: root '{name}' is unavailable: {e}"))?;
+        let real = deepest_existing(&target).map_err(|e| format!("{path}: {e}"))?;
+        if !real.starts_with(&real_root) {
+            return Err(format!("{path}: path leads outside of root '{name}'"));
+        }
+        Ok(target)
+    }
+}
+
+/// Canonical form of the longest existing prefix of `path`.
+///
+/// A dangling symlink counts as existing, so canonicalizing it fails instead of letting a
+/// write create its target outside the root.
+fn deepest_existing(path: &Path) -> Result<PathBuf, String> {
+    let mut p = path;
+    loop {
+        match p.symlink_metadata() {
+            Ok(_) => {
+                return p
+                    .canonicalize()
+                    .map_err(|e| format!("cannot resolve path: {e}"));
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("cannot resolve path: {e}")),
+        }
+        p = p
+            .parent()
+            .ok_or_else(|| "cannot resolve path".to_string())?;
+    }
+}
+
+/// Grant `std.fs` to the context, limited to `config`'s roots; replaces an earlier config.
+pub fn enable(token: &ContextToken, config: FsConfig) {
+    token.set_host_data(config);
+}
+
+/// The context's config; `None` if `std.fs` was never enabled for it.
+pub fn config(token: &ContextToken) -> Option<Rc<FsConfig>> {
+    token.host_data::<FsConfig>()
+}
+
+/// Require check (`ContextBuilder::require_check`): `std.fs` only for enabled contexts.
+pub fn require_check(token: &ContextToken, module: &NativeModule<'_>) -> Result<(), String> {
+    if module.base == MODULE_BASE && config(token).is_none() {
+        return Err("file system access is not enabled for this context".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,13 @@
+"use strict";
+
+// The test runner's contexts never enable std.fs, so the module must be refused.
+var err = null;
+try {
+  require("std.fs");
+} catch (e) {
+  err = e;
+}
+if (!(err instanceof TypeError)) throw new Error("expected a TypeError from require(\"std.fs\")");
+if (String(err.message).indexOf("not enabled") < 0) throw new Error("unexpected message: " + err.message);
+
+"ok";
@@ -0,0 +1,123 @@
+//! Time source of a context, shared by the stdlib host objects (timers, `performance`).
+//!
+//! Each context has one clock, stored as host data; it defaults to a `SystemClock`. Install a
+//! `VirtualClock` (see `EventLoop::with_clock`) for deterministic tests: time then only moves
+//! when the event loop or the test advances it.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use mquickjs_rs::context::ContextToken;
+
+pub trait Clock {
+    /// Monotonic time since the clock's origin.
+    fn now(&self) -> Duration;
+
+    /// Block until `now() >= deadline`; a virtual clock jumps there instead.
+    fn sleep_until(&self, deadline: Duration);
+
+    /// Unix time in milliseconds at which `now()` was zero (`performance.timeOrigin`).
+    fn time_origin(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Wall-clock time (`Instant`), measured from the clock's creation.
+pub struct SystemClock {
+    origin: Instant,
+    origin_unix_ms: f64,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        let origin_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .unwrap_or(0.0);
+        Self {
+            origin: Instant::now(),
+            origin_unix_ms,
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep_until(&self, deadline: Duration) {
+        let now = self.now();
+        if deadline > now {
+            std::thread::sleep(deadline - now);
+        }
+    }
+
+    fn time_origin(&self) -> f64 {
+        self.origin_unix_ms
+    }
+}
+
+/// Manually driven time, starting at zero.
+#[derive(Default)]
+pub struct VirtualClock {
+    now: Cell<Duration>,
+    time_origin: f64,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report `unix_ms` as `performance.timeOrigin` (default 0).
+    pub fn with_time_origin(mut self, unix_ms: f64) -> Self {
+        self.time_origin = unix_ms;
+        self
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep_until(&self, deadline: Duration) {
+        if deadline > self.now.get() {
+            self.now.set(deadline);
+        }
+    }
+
+    fn time_origin(&self) -> f64 {
+        self.time_origin
+    }
+}
+
+struct ContextClock(Rc<dyn Clock>);
+
+/// The clock of `token`'s context (installs a `SystemClock` on first use).
+pub fn context_clock(token: &ContextToken) -> Rc<dyn Clock> {
+    token
+        .host_data_or_insert_with(|| ContextClock(Rc::new(SystemClock::new())))
+        .0
+        .clone()
+}
+
+/// Replace the clock of `token`'s context.
+///
+/// Timers already scheduled keep their due times, which were taken from the previous clock;
+/// install the clock before running scripts.
+pub fn set_context_clock(token: &ContextToken, clock: Rc<dyn Clock>) {
+    token.set_host_data(ContextClock(clock));
+}
@@ -0,0 +1,384 @@
+//! `console`: levelled output with printf-style formatting and a host-provided sink.
+//!
+//! Arguments are rendered like Node's `util.format`: `%s %d %i %f %o %O %c %%` substitution
+//! when the first argument is a string, objects and arrays expanded via `Local::inspect`.
+//! Finished lines go to the context's `ConsoleSink` (default `StdioSink`) together with the
+//! running script's filename, so a host can route them to `log`/`tracing`.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mquickjs_rs::Env;
+use mquickjs_rs::context::ContextToken;
+use mquickjs_rs::handles::local::{Local, Value};
+use mquickjs_rs::handles::scope::Scope;
+use mquickjs_rs::handles::value_kind::ValueKind;
+
+use crate::clock::context_clock;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Level {
+    Debug,
+    Info,
+    Log,
+    Warn,
+    Error,
+    /// `console.trace`; the message is prefixed with `Trace`.
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Log => "log",
+            Level::Warn => "warn",
+            Level::Error => "error",
+            Level::Trace => "trace",
+        }
+    }
+
+    /// Levels `StdioSink` writes to stderr (as Node does).
+    pub fn is_stderr(&self) -> bool {
+        matches!(self, Level::Warn | Level::Error | Level::Trace)
+    }
+}
+
+/// One formatted console line.
+#[derive(Clone, Debug)]
+pub struct ConsoleRecord<'a> {
+    pub level: Level,
+    /// Fully formatted message, without group indentation.
+    pub message: &'a str,
+    /// `ContextToken::current_script` at the call; `None` e.g. in timer callbacks run by the
+    /// host's event loop.
+    pub filename: Option<&'a str>,
+    /// Current `console.group` nesting.
+    pub group_depth: usize,
+}
+
+/// Destination of console output for one context (see `set_console_sink`).
+pub trait ConsoleSink {
+    fn write(&self, record: &ConsoleRecord<'_>);
+}
+
+/// Default sink: stdout/stderr, indented two spaces per group level.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StdioSink;
+
+impl ConsoleSink for StdioSink {
+    fn write(&self, record: &ConsoleRecord<'_>) {
+        let indent = "  ".repeat(record.group_depth);
+        let text = record
+            .message
+            .split('\n')
+            .map(|line| format!("{indent}{line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if record.level.is_stderr() {
+            eprintln!("{text}");
+        } else {
+            println!("{text}");
+        }
+    }
+}
+
+struct ContextConsoleSink(Rc<dyn ConsoleSink>);
+
+/// The console sink of `token`'s context (installs a `StdioSink` on first use).
+pub fn console_sink(token: &ContextToken) -> Rc<dyn ConsoleSink> {
+    token
+        .host_data_or_insert_with(|| ContextConsoleSink(Rc::new(StdioSink)))
+        .0
+        .clone()
+}
+
+/// Route the console output of `token`'s context to `sink`.
+pub fn set_console_sink(token: &ContextToken, sink: Rc<dyn ConsoleSink>) {
+    token.set_host_data(ContextConsoleSink(sink));
+}
+
+/// Format console arguments the way Node's `util.format` does.
+///
+/// A leading string is a format string; specifiers without a matching argument are kept
+/// as-is, and arguments left over are appended separated by spaces.
+pub fn format_values<'ctx>(scope: &Scope<'ctx>, args: &[Local<'ctx, Value>]) -> String {
+    let mut parts = Vec::with_capacity(args.len());
+    let mut rest = args.iter();
+    if let Some(fmt) = args.first().and_then(|v| v.as_string(scope)) {
+        rest.next();
+        parts.push(substitute(scope, &fmt, &mut rest));
+    }
+    parts.extend(rest.map(|v| display(scope, *v)));
+    parts.join(" ")
+}
+
+fn substitute<'ctx>(
+    scope: &Scope<'ctx>,
+    fmt: &str,
+    args: &mut std::slice::Iter<'_, Local<'ctx, Value>>,
+) -> String {
+    let mut out = String::with_capacity(fmt.len());
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('%') => {
+                chars.next();
+                out.push('%');
+            }
+            Some(spec @ ('s' | 'd' | 'i' | 'f' | 'o' | 'O' | 'c')) => {
+                // No argument left: keep the specifier (the next iteration copies it).
+                let Some(arg) = args.next() else {
+                    out.push('%');
+                    continue;
+                };
+                chars.next();
+                match spec {
+                    'd' | 'i' => out.push_str(&js_number(number(scope, *arg).trunc())),
+                    'f' => out.push_str(&js_number(number(scope, *arg))),
+                    // CSS styling has no meaning on a text sink.
+                    'c' => {}
+                    _ => out.push_str(&display(scope, *arg)),
+                }
+            }
+            _ => out.push('%'),
+        }
+    }
+    out
+}
+
+fn display<'ctx>(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> String {
+    v.inspect(scope)
+        .unwrap_or_else(|e| format!("[inspect failed: {e}]"))
+}
+
+/// Numbers only; anything else is `NaN` (no coercion, so no user code runs).
+fn number<'ctx>(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> f64 {
+    v.as_f64(scope).unwrap_or(f64::NAN)
+}
+
+fn js_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n == f64::INFINITY {
+        "Infinity".to_string()
+    } else if n == f64::NEG_INFINITY {
+        "-Infinity".to_string()
+    } else if n == 0.0 {
+        "0".to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+fn truthy<'ctx>(scope: &Scope<'ctx>, v: Local<'ctx, Value>) -> bool {
+    match v.kind(scope) {
+        ValueKind::Undefined | ValueKind::Null | ValueKind::Exception => false,
+        ValueKind::Bool => v.as_bool() == Some(true),
+        ValueKind::Int | ValueKind::Float => {
+            v.as_f64(scope).is_some_and(|n| n != 0.0 && !n.is_nan())
+        }
+        ValueKind::String => v.as_string(scope).is_some_and(|s| !s.is_empty()),
+        _ => true,
+    }
+}
+
+/// Bind generated-glue arguments to `scope`'s lifetime.
+fn bind<'ctx>(scope: &Scope<'ctx>, args: &[Local<'_, Value>]) -> Vec<Local<'ctx, Value>> {
+    args.iter().map(|v| scope.value(v.as_raw())).collect()
+}
+
+const DEFAULT_LABEL: &str = "default";
+
+/// Per-context console state: `time` start times, `count` counters and the group depth.
+pub struct DefaultConsoleSingleton {
+    enabled: bool,
+    timers: HashMap<String, f64>,
+    counts: HashMap<String, u64>,
+    group_depth: usize,
+}
+
+impl Default for DefaultConsoleSingleton {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timers: HashMap::new(),
+            counts: HashMap::new(),
+            group_depth: 0,
+        }
+    }
+}
+
+impl DefaultConsoleSingleton {
+    fn emit(&self, token: &ContextToken, level: Level, message: &str) {
+        if !self.enabled {
+            return;
+        }
+        let filename = token.current_script();
+        console_sink(token).write(&ConsoleRecord {
+            level,
+            message,
+            filename: filename.as_deref(),
+            group_depth: self.group_depth,
+        });
+    }
+
+    fn print(&self, env: &Env<'_>, level: Level, args: &[Local<'_, Value>]) {
+        let scope = env.scope();
+        let message = format_values(scope, &bind(scope, args));
+        self.emit(scope.token(), level, &message);
+    }
+
+    fn elapsed_ms(&self, token: &ContextToken, label: &str) -> Option<f64> {
+        let start = self.timers.get(label)?;
+        Some(now_ms(token) - start)
+    }
+}
+
+fn now_ms(token: &ContextToken) -> f64 {
+    context_clock(token).now().as_secs_f64() * 1000.0
+}
+
+impl crate::impls::ConsoleSingleton for DefaultConsoleSingleton {
+    fn log(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        self.print(env, Level::Log, &args);
+    }
+
+    fn info(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        self.print(env, Level::Info, &args);
+    }
+
+    fn debug(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        self.print(env, Level::Debug, &args);
+    }
+
+    fn warn(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        self.print(env, Level::Warn, &args);
+    }
+
+    fn error(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        self.print(env, Level::Error, &args);
+    }
+
+    fn trace(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        let scope = env.scope();
+        let message = format_values(scope, &bind(scope, &args));
+        let message = if message.is_empty() {
+            "Trace".to_string()
+        } else {
+            format!("Trace: {message}")
+        };
+        self.emit(scope.token(), Level::Trace, &message);
+    }
+
+    fn assert(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        let scope = env.scope();
+        let args = bind(scope, &args);
+        let (ok, rest) = match args.split_first() {
+            Some((cond, rest)) => (truthy(scope, *cond), rest),
+            None => (false, &[][..]),
+        };
+        if ok {
+            return;
+        }
+        let message = if rest.is_empty() {
+            "Assertion failed".to_string()
+        } else {
+            format!("Assertion failed: {}", format_values(scope, rest))
+        };
+        self.emit(scope.token(), Level::Error, &message);
+    }
+
+    fn time(&mut self, env: &mut Env<'_>, label: Option<String>) {
+        let token = env.scope().token();
+        let label = label.unwrap_or_else(|| DEFAULT_LABEL.to_string());
+        if self.timers.contains_key(&label) {
+            self.emit(
+                token,
+                Level::Warn,
+                &format!("Timer '{label}' already exists"),
+            );
+            return;
+        }
+        self.timers.insert(label, now_ms(token));
+    }
+
+    fn time_log(&mut self, env: &mut Env<'_>, label: Option<String>, args: Vec<Local<'_, Value>>) {
+        let scope = env.scope();
+        let token = scope.token();
+        let label = label.unwrap_or_else(|| DEFAULT_LABEL.to_string());
+        let Some(ms) = self.elapsed_ms(token, &label) else {
+            self.emit(
+                token,
+                Level::Warn,
+                &format!("Timer '{label}' does not exist"),
+            );
+            return;
+        };
+        let mut message = format!("{label}: {ms:.3}ms");
+        if !args.is_empty() {
+            message.push(' ');
+            message.push_str(&format_values(scope, &bind(scope, &args)));
+        }
+        self.emit(token, Level::Log, &message);
+    }
+
+    fn time_end(&mut self, env: &mut Env<'_>, label: Option<String>) {
+        let token = env.scope().token();
+        let label = label.unwrap_or_else(|| DEFAULT_LABEL.to_string());
+        match self.elapsed_ms(token, &label) {
+            Some(ms) => {
+                self.timers.remove(&label);
+                self.emit(token, Level::Log, &format!("{label}: {ms:.3}ms"));
+            }
+            None => self.emit(
+                token,
+                Level::Warn,
+                &format!("Timer '{label}' does not exist"),
+            ),
+        }
+    }
+
+    fn count(&mut self, env: &mut Env<'_>, label: Option<String>) {
+        let label = label.unwrap_or_else(|| DEFAULT_LABEL.to_string());
+        let n = self.counts.entry(label.clone()).or_insert(0);
+        *n += 1;
+        let message = format!("{label}: {n}");
+        self.emit(env.scope().token(), Level::Log, &message);
+    }
+
+    fn count_reset(&mut self, env: &mut Env<'_>, label: Option<String>) {
+        let label = label.unwrap_or_else(|| DEFAULT_LABEL.to_string());
+        if self.counts.remove(&label).is_none() {
+            self.emit(
+                env.scope().token(),
+                Level::Warn,
+                &format!("Count for '{label}' does not exist"),
+            );
+        }
+    }
+
+    fn group(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        if !args.is_empty() {
+            self.print(env, Level::Log, &args);
+        }
+        self.group_depth += 1;
+    }
+
+    fn group_end(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+pub fn create_console_singleton() -> Box<dyn crate::api::ConsoleSingleton> {
+    Box::new(DefaultConsoleSingleton::default())
+}
@@ -0,0 +1,341 @@
+//! `crypto`: random bytes and UUIDs, SHA-256 / HMAC-SHA256, and the context's `Math.random`.
+//!
+//! Random bytes come from the calling context's entropy source, stored as host data like the
+//! clock: `OsEntropy` by default, or whatever the host installs with `set_context_entropy`
+//! (`SeededEntropy` makes `getRandomValues` / `randomUUID` reproducible in tests).
+//!
+//! The engine's own `Math.random` cannot be seeded from the host, so `install_math_random`
+//! replaces it with `crypto.random`: one generator per context, seeded from the entropy source
+//! on first use unless the host fixed a seed with `seed_math_random`. Not for key material.
+//!
+//! The digests are plain Rust functions, so the host can check what scripts compute.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use mquickjs_rs::context::ContextToken;
+use mquickjs_rs::handles::local::{Local, Object, Value};
+use mquickjs_rs::handles::typed_array::TypedArrayKind;
+use mquickjs_rs::ridl_runtime::{JsErrorKind, throw_error};
+use mquickjs_rs::{Env, Global};
+
+use crate::api::global::union::UnionBytesString;
+use crate::encoding::hex_encode;
+
+pub trait EntropySource {
+    /// Fill `buf` with random bytes.
+    fn fill(&self, buf: &mut [u8]);
+}
+
+/// Operating-system randomness (`/dev/urandom`).
+///
+/// Where that cannot be read, bytes are derived from std's per-process hash seeds instead:
+/// good enough for ids, not for keys. Hosts on such platforms should install their own
+/// source (e.g. a hardware RNG).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsEntropy;
+
+impl EntropySource for OsEntropy {
+    fn fill(&self, buf: &mut [u8]) {
+        use std::io::Read;
+
+        let read = std::fs::File::open("/dev/urandom").and_then(|mut f| f.read_exact(buf));
+        if read.is_err() {
+            fallback_fill(buf);
+        }
+    }
+}
+
+fn fallback_fill(buf: &mut [u8]) {
+    use std::hash::{BuildHasher, Hasher, RandomState};
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    for chunk in buf.chunks_mut(8) {
+        // Every `RandomState::new()` gets fresh keys.
+        let mut h = RandomState::new().build_hasher();
+        h.write_u128(nanos);
+        chunk.copy_from_slice(&h.finish().to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// Deterministic bytes (SplitMix64): the same seed always gives the same sequence. For tests.
+#[derive(Debug)]
+pub struct SeededEntropy {
+    state: Cell<u64>,
+}
+
+impl SeededEntropy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Cell::new(seed),
+        }
+    }
+}
+
+impl EntropySource for SeededEntropy {
+    fn fill(&self, buf: &mut [u8]) {
+        let mut state = self.state.get();
+        for chunk in buf.chunks_mut(8) {
+            chunk.copy_from_slice(&splitmix64(&mut state).to_le_bytes()[..chunk.len()]);
+        }
+        self.state.set(state);
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+struct ContextEntropy(Rc<dyn EntropySource>);
+
+/// The entropy source of `token`'s context (installs `OsEntropy` on first use).
+pub fn context_entropy(token: &ContextToken) -> Rc<dyn EntropySource> {
+    token
+        .host_data_or_insert_with(|| ContextEntropy(Rc::new(OsEntropy)))
+        .0
+        .clone()
+}
+
+/// Replace the entropy source of `token`'s context.
+///
+/// `Math.random` takes its seed from the source on first use; install the source (or call
+/// `seed_math_random`) before running scripts.
+pub fn set_context_entropy(token: &ContextToken, source: Rc<dyn EntropySource>) {
+    token.set_host_data(ContextEntropy(source));
+}
+
+/// `Math.random` generator state of a context (xorshift128+); `None` until first seeded.
+struct MathRandom(Cell<Option<[u64; 2]>>);
+
+fn seeded_state(seed: u64) -> [u64; 2] {
+    let mut s = seed;
+    let state = [splitmix64(&mut s), splitmix64(&mut s)];
+    // xorshift128+ must not start from all zeros.
+    if state == [0, 0] { [0, 1] } else { state }
+}
+
+/// Fix the seed of the context's `Math.random` (and `crypto.random`) sequence.
+pub fn seed_math_random(token: &ContextToken, seed: u64) {
+    token.set_host_data(MathRandom(Cell::new(Some(seeded_state(seed)))));
+}
+
+/// Next number in `[0, 1)` of the context's `Math.random` sequence.
+pub fn math_random(token: &ContextToken) -> f64 {
+    let generator = token.host_data_or_insert_with(|| MathRandom(Cell::new(None)));
+    let [mut s1, s0] = generator.0.get().unwrap_or_else(|| {
+        let mut seed = [0u8; 8];
+        context_entropy(token).fill(&mut seed);
+        seeded_state(u64::from_le_bytes(seed))
+    });
+    s1 ^= s1 << 23;
+    let next = s1 ^ s0 ^ (s1 >> 17) ^ (s0 >> 26);
+    generator.0.set(Some([s0, next]));
+    // The top 53 bits, as a double in [0, 1).
+    (next.wrapping_add(s0) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// `with_env` hook (`ContextBuilder::with_env`): point `Math.random` at `crypto.random`.
+///
+/// Does nothing when the context has no `crypto` singleton (e.g. denied by its permissions);
+/// `Math.random` then stays the engine's.
+pub fn install_math_random(env: &mut Env<'_>) -> Result<(), String> {
+    let scope = env.scope();
+    let crypto: Option<Local<'_, Object>> = env.global()?.as_local().get_path(env, "crypto")?;
+    if crypto.is_none() {
+        return Ok(());
+    }
+    // `get_path` may run getters, which allocate and move objects: keep `Math` rooted while
+    // reading the function, and read `crypto.random` last, right before the store.
+    let math: Local<'_, Object> = env.global()?.as_local().get_path(env, "Math")?;
+    let math = Global::new(scope, math);
+    let random: Local<'_, Value> = env.global()?.as_local().get_path(env, "crypto.random")?;
+    scope
+        .value(math.as_raw())
+        .try_into_object(scope)?
+        .set_property(scope, "random", random)
+        .map_err(|e| format!("{e} (Math.random)"))
+}
+
+/// Random version 4 UUID (RFC 9562), lowercase.
+pub fn random_uuid(source: &dyn EntropySource) -> String {
+    let mut b = [0u8; 16];
+    source.fill(&mut b);
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h = hex_encode(&b);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &h[..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..]
+    )
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 (FIPS 180-4) of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+    for block in msg.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for (k, wi) in K.iter().zip(w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(wi);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (o, x) in out.chunks_exact_mut(4).zip(h) {
+        o.copy_from_slice(&x.to_be_bytes());
+    }
+    out
+}
+
+/// HMAC-SHA256 (RFC 2104) of `data` under `key`.
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(data);
+    let mut outer: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+/// Most bytes one `getRandomValues` call fills (as in Web Crypto).
+pub const MAX_RANDOM_VALUES_BYTES: u32 = 65536;
+
+/// The `crypto` singleton; all state lives in the context (entropy source, `Math.random`).
+#[derive(Default)]
+pub struct DefaultCryptoSingleton;
+
+impl crate::impls::CryptoSingleton for DefaultCryptoSingleton {
+    fn get_random_values(&mut self, env: &mut Env<'_>, typed_array: Vec<Local<'_, Value>>) {
+        let scope = env.scope();
+        let Some(v) = typed_array.first().map(|v| scope.value(v.as_raw())) else {
+            throw_error(
+                JsErrorKind::TypeError,
+                "getRandomValues: an integer typed array is required",
+            );
+            return;
+        };
+        let arr = match v.try_into_typed_array(scope) {
+            Ok(arr)
+                if arr.kind(scope).is_ok_and(|k| {
+                    !matches!(k, TypedArrayKind::Float32 | TypedArrayKind::Float64)
+                }) =>
+            {
+                arr
+            }
+            _ => {
+                throw_error(
+                    JsErrorKind::TypeError,
+                    "TypeMismatchError: getRandomValues: an integer typed array is required",
+                );
+                return;
+            }
+        };
+        let r = arr.byte_length(scope).and_then(|len| {
+            if len > MAX_RANDOM_VALUES_BYTES {
+                return Err(format!(
+                    "QuotaExceededError: getRandomValues: {len} bytes requested, at most {MAX_RANDOM_VALUES_BYTES}"
+                ));
+            }
+            let mut bytes = vec![0u8; len as usize];
+            context_entropy(scope.token()).fill(&mut bytes);
+            arr.write_bytes(scope, &bytes)
+        });
+        if let Err(e) = r {
+            throw_error(JsErrorKind::Error, e);
+        }
+    }
+
+    fn random_uuid(&mut self, env: &mut Env<'_>) -> String {
+        random_uuid(&*context_entropy(env.scope().token()))
+    }
+
+    fn sha256(&mut self, data: UnionBytesString) -> Vec<u8> {
+        let digest = match data {
+            UnionBytesString::String(s) => sha256(s.as_bytes()),
+            UnionBytesString::Bytes(b) => sha256(&b),
+        };
+        digest.to_vec()
+    }
+
+    fn hmac_sha256(&mut self, key: Vec<u8>, data: Vec<u8>) -> Vec<u8> {
+        hmac_sha256(&key, &data).to_vec()
+    }
+
+    fn random(&mut self, env: &mut Env<'_>) -> f64 {
+        math_random(env.scope().token())
+    }
+}
+
+pub fn create_crypto_singleton() -> Box<dyn crate::impls::CryptoSingleton> {
+    Box::new(DefaultCryptoSingleton)
+}
@@ -0,0 +1,319 @@
+//! Text encoding: `TextEncoder` / `TextDecoder` (UTF-8, UTF-16LE), `btoa` / `atob` and the
+//! `encoding` singleton (hex and base64 over bytes).
+//!
+//! The codecs are plain Rust functions, so the host side of a protocol can build and parse
+//! the same frames the scripts do. The JS-facing wrappers only add error reporting
+//! (`ridl_runtime::throw_error`).
+
+use mquickjs_rs::ridl_runtime::{JsErrorKind, throw_error};
+
+/// Encodings understood by `TextEncoder` / `TextDecoder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+}
+
+impl Encoding {
+    /// Resolve a WHATWG encoding label (case-insensitive, surrounding ASCII whitespace ignored).
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label
+            .trim_matches(|c: char| c.is_ascii_whitespace())
+            .to_ascii_lowercase();
+        match label.as_str() {
+            "utf-8" | "utf8" | "unicode-1-1-utf-8" | "unicode11utf8" | "unicode20utf8"
+            | "x-unicode20utf8" => Some(Self::Utf8),
+            "utf-16le" | "utf-16" | "ucs-2" | "unicode" | "csunicode" | "iso-10646-ucs-2"
+            | "unicodefeff" => Some(Self::Utf16Le),
+            _ => None,
+        }
+    }
+
+    /// Canonical name, as reported by the `encoding` property.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Utf16Le => "utf-16le",
+        }
+    }
+
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Self::Utf16Le => &[0xFF, 0xFE],
+        }
+    }
+}
+
+/// Encode `s` (no BOM is written).
+pub fn encode(encoding: Encoding, s: &str) -> Vec<u8> {
+    match encoding {
+        Encoding::Utf8 => s.as_bytes().to_vec(),
+        Encoding::Utf16Le => s.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+    }
+}
+
+/// Decode `bytes`. Malformed sequences become U+FFFD, or an error when `fatal` is set.
+/// A leading BOM is dropped unless `ignore_bom` is set.
+pub fn decode(
+    encoding: Encoding,
+    bytes: &[u8],
+    fatal: bool,
+    ignore_bom: bool,
+) -> Result<String, String> {
+    let bytes = match bytes.strip_prefix(encoding.bom()) {
+        Some(rest) if !ignore_bom => rest,
+        _ => bytes,
+    };
+    let invalid = || {
+        format!(
+            "The encoded data was not valid for encoding {}",
+            encoding.name()
+        )
+    };
+    match encoding {
+        Encoding::Utf8 if fatal => std::str::from_utf8(bytes)
+            .map(str::to_string)
+            .map_err(|_| invalid()),
+        Encoding::Utf8 => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        Encoding::Utf16Le => {
+            let chunks = bytes.chunks_exact(2);
+            let odd = !chunks.remainder().is_empty();
+            let units = chunks.map(|c| u16::from_le_bytes([c[0], c[1]]));
+            let mut out = String::with_capacity(bytes.len() / 2);
+            for c in char::decode_utf16(units) {
+                match c {
+                    Ok(c) => out.push(c),
+                    Err(_) if fatal => return Err(invalid()),
+                    Err(_) => out.push(char::REPLACEMENT_CHARACTER),
+                }
+            }
+            if odd {
+                if fatal {
+                    return Err(invalid());
+                }
+                out.push(char::REPLACEMENT_CHARACTER);
+            }
+            Ok(out)
+        }
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// WHATWG "forgiving base64": ASCII whitespace is ignored and padding is optional.
+pub fn base64_decode(s: &str) -> Result<Vec<u8>, String> {
+    let mut data: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if data.len() % 4 == 0 {
+        for _ in 0..2 {
+            if data.last() == Some(&b'=') {
+                data.pop();
+            }
+        }
+    }
+    if data.len() % 4 == 1 {
+        return Err("invalid base64 length".to_string());
+    }
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for (i, b) in data.iter().enumerate() {
+        let Some(v) = BASE64_ALPHABET.iter().position(|a| a == b) else {
+            return Err(format!("invalid base64 character at offset {i}"));
+        };
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Lowercase hex.
+pub fn hex_encode(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        out.push(DIGITS[(b >> 4) as usize] as char);
+        out.push(DIGITS[(b & 0xF) as usize] as char);
+    }
+    out
+}
+
+/// Hex in either case; the length must be even.
+pub fn hex_decode(s: &str) -> Result<Vec<u8>, String> {
+    if s.len() % 2 != 0 {
+        return Err("hex string must have an even length".to_string());
+    }
+    s.as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let digit = |c: u8| (c as char).to_digit(16);
+            match (digit(pair[0]), digit(pair[1])) {
+                (Some(hi), Some(lo)) => Ok(((hi << 4) | lo) as u8),
+                _ => Err(format!("invalid hex digit at offset {}", i * 2)),
+            }
+        })
+        .collect()
+}
+
+fn label_or_throw(label: Option<String>) -> Encoding {
+    let Some(label) = label else {
+        return Encoding::Utf8;
+    };
+    Encoding::from_label(&label).unwrap_or_else(|| {
+        throw_error(
+            JsErrorKind::RangeError,
+            format!("The \"{label}\" encoding is not supported"),
+        );
+        Encoding::Utf8
+    })
+}
+
+pub struct TextEncoderImpl {
+    encoding: Encoding,
+}
+
+impl crate::impls::TextEncoderClass for TextEncoderImpl {
+    fn encode(&mut self, input: String) -> Vec<u8> {
+        encode(self.encoding, &input)
+    }
+
+    fn get_encoding(&mut self) -> String {
+        self.encoding.name().to_string()
+    }
+}
+
+pub fn text_encoder_constructor(label: Option<String>) -> Box<dyn crate::impls::TextEncoderClass> {
+    Box::new(TextEncoderImpl {
+        encoding: label_or_throw(label),
+    })
+}
+
+pub struct TextDecoderImpl {
+    encoding: Encoding,
+    fatal: bool,
+    ignore_bom: bool,
+}
+
+impl crate::impls::TextDecoderClass for TextDecoderImpl {
+    fn decode(&mut self, input: Option<Vec<u8>>) -> String {
+        let input = input.unwrap_or_default();
+        decode(self.encoding, &input, self.fatal, self.ignore_bom).unwrap_or_else(|e| {
+            throw_error(JsErrorKind::TypeError, e);
+            String::new()
+        })
+    }
+
+    fn get_encoding(&mut self) -> String {
+        self.encoding.name().to_string()
+    }
+
+    fn get_fatal(&mut self) -> bool {
+        self.fatal
+    }
+
+    fn get_ignore_bom(&mut self) -> bool {
+        self.ignore_bom
+    }
+}
+
+pub fn text_decoder_constructor(
+    label: Option<String>,
+    options: Option<crate::api::TextDecoderOptions>,
+) -> Box<dyn crate::impls::TextDecoderClass> {
+    let options = options.unwrap_or_default();
+    Box::new(TextDecoderImpl {
+        encoding: label_or_throw(label),
+        fatal: options.fatal.unwrap_or(false),
+        ignore_bom: options.ignore_bom.unwrap_or(false),
+    })
+}
+
+fn invalid_character(message: impl std::fmt::Display) {
+    throw_error(
+        JsErrorKind::Error,
+        format!("InvalidCharacterError: {message}"),
+    );
+}
+
+/// `btoa`: base64 of a binary string (one byte per character).
+pub fn btoa(data: String) -> String {
+    let mut bytes = Vec::with_capacity(data.len());
+    for c in data.chars() {
+        let Ok(b) = u8::try_from(c) else {
+            invalid_character("btoa: the string contains characters outside of the Latin1 range");
+            return String::new();
+        };
+        bytes.push(b);
+    }
+    base64_encode(&bytes)
+}
+
+/// `atob`: decode base64 into a binary string (one character per byte).
+pub fn atob(data: String) -> String {
+    match base64_decode(&data) {
+        Ok(bytes) => bytes.into_iter().map(char::from).collect(),
+        Err(e) => {
+            invalid_character(format!("atob: {e}"));
+            String::new()
+        }
+    }
+}
+
+/// The `encoding` singleton; stateless.
+#[derive(Default)]
+pub struct DefaultEncodingSingleton;
+
+fn bytes_or_throw(r: Result<Vec<u8>, String>) -> Vec<u8> {
+    r.unwrap_or_else(|e| {
+        throw_error(JsErrorKind::TypeError, e);
+        Vec::new()
+    })
+}
+
+impl crate::impls::EncodingSingleton for DefaultEncodingSingleton {
+    fn to_hex(&mut self, data: Vec<u8>) -> String {
+        hex_encode(&data)
+    }
+
+    fn from_hex(&mut self, hex: String) -> Vec<u8> {
+        bytes_or_throw(hex_decode(&hex))
+    }
+
+    fn to_base64(&mut self, data: Vec<u8>) -> String {
+        base64_encode(&data)
+    }
+
+    fn from_base64(&mut self, data: String) -> Vec<u8> {
+        bytes_or_throw(base64_decode(&data))
+    }
+}
+
+pub fn create_encoding_singleton() -> Box<dyn crate::impls::EncodingSingleton> {
+    Box::new(DefaultEncodingSingleton)
+}
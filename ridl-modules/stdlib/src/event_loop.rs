@@ -0,0 +1,132 @@
+//! `EventLoop`: host-side driver for the stdlib timers of one context.
+//!
+//! Scripts only enqueue work (`setTimeout`, `queueMicrotask`, ...); the host decides when it
+//! runs:
+//!
+//! ```ignore
+//! let clock = Rc::new(VirtualClock::new());
+//! let event_loop = EventLoop::with_clock(ctx.token(), clock.clone());
+//! ctx.eval("setTimeout(() => done = true, 1000)")?;
+//! event_loop.run_for(Duration::from_secs(1))?; // returns at once: virtual time
+//! ```
+//!
+//! Microtasks run before each timer callback and after it, so they always drain first.
+//! An uncaught exception in a callback stops the run and is returned as `Err`; the
+//! remaining work stays queued and the next `run_*` call continues with it.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use mquickjs_rs::Env;
+use mquickjs_rs::context::ContextToken;
+use mquickjs_rs::handles::local::{Local, Value};
+
+use crate::clock::{Clock, context_clock, set_context_clock};
+use crate::timers::{Task, TimerQueue};
+
+pub struct EventLoop {
+    token: ContextToken,
+}
+
+impl EventLoop {
+    /// Drive the context of `token` with its current clock (`SystemClock` by default).
+    pub fn new(token: ContextToken) -> Self {
+        Self { token }
+    }
+
+    /// Install `clock` as the context clock first (e.g. a `VirtualClock` in tests).
+    pub fn with_clock(token: ContextToken, clock: Rc<dyn Clock>) -> Self {
+        set_context_clock(&token, clock);
+        Self { token }
+    }
+
+    pub fn clock(&self) -> Rc<dyn Clock> {
+        context_clock(&self.token)
+    }
+
+    /// True while timers or microtasks are queued (an uncleared `setInterval` keeps it true).
+    pub fn has_pending(&self) -> bool {
+        TimerQueue::of(&self.token).is_some_and(|q| !q.is_idle())
+    }
+
+    /// Run microtasks and the timers already due; never waits.
+    pub fn run_pending(&self) -> Result<(), String> {
+        let queue = self.queue()?;
+        self.run_microtasks(&queue)?;
+        let now = self.clock().now();
+        while let Some(task) = queue.take_due(now) {
+            self.call(&task)?;
+            self.run_microtasks(&queue)?;
+        }
+        Ok(())
+    }
+
+    /// Run until no timers or microtasks are left, waiting for each timer to become due.
+    ///
+    /// Does not return while an interval is active; use `run_for` to bound the run.
+    pub fn run_until_idle(&self) -> Result<(), String> {
+        let queue = self.queue()?;
+        loop {
+            self.run_microtasks(&queue)?;
+            let Some(due) = queue.next_due() else {
+                return Ok(());
+            };
+            self.fire(&queue, due)?;
+        }
+    }
+
+    /// Run the timers that become due within `duration` from now, then wait out the rest
+    /// of it (with a `VirtualClock`, the clock ends exactly `duration` later).
+    pub fn run_for(&self, duration: Duration) -> Result<(), String> {
+        let queue = self.queue()?;
+        let clock = self.clock();
+        let deadline = clock.now() + duration;
+        loop {
+            self.run_microtasks(&queue)?;
+            match queue.next_due() {
+                Some(due) if due <= deadline => self.fire(&queue, due)?,
+                _ => {
+                    clock.sleep_until(deadline);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn queue(&self) -> Result<Rc<TimerQueue>, String> {
+        if !self.token.is_alive() {
+            return Err("EventLoop: context has been dropped".to_string());
+        }
+        Ok(self.token.host_data_or_insert_with(TimerQueue::default))
+    }
+
+    /// Wait until `due` and run the earliest due timer.
+    fn fire(&self, queue: &TimerQueue, due: Duration) -> Result<(), String> {
+        let clock = self.clock();
+        clock.sleep_until(due);
+        match queue.take_due(clock.now()) {
+            Some(task) => self.call(&task),
+            None => Ok(()),
+        }
+    }
+
+    fn run_microtasks(&self, queue: &TimerQueue) -> Result<(), String> {
+        while let Some(task) = queue.pop_microtask() {
+            self.call(&task)?;
+        }
+        Ok(())
+    }
+
+    fn call(&self, task: &Task) -> Result<(), String> {
+        // `clearTimeout` & co. find their queue through the current context.
+        let _current = self.token.enter_current();
+        let scope = self.token.enter_scope();
+        let env = Env::new(&scope);
+        let callback = scope
+            .value(task.callback.as_raw())
+            .try_into_function(&scope)?;
+        let args: Vec<Local<'_, Value>> =
+            task.args.iter().map(|a| scope.value(a.as_raw())).collect();
+        callback.call_with::<()>(&env, (), args)
+    }
+}
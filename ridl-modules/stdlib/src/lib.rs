@@ -3,9 +3,33 @@ mquickjs_rs::ridl_include_module!();
 pub mod impls {
     pub use crate::api::ConsoleSingleton;
 
-    pub use crate::stdlib_impl::DefaultConsoleSingleton;
+    pub use crate::console::{DefaultConsoleSingleton, create_console_singleton};
 
-    pub use crate::stdlib_impl::create_console_singleton;
+    pub use crate::api::PerformanceSingleton;
+    pub use crate::performance::{DefaultPerformanceSingleton, create_performance_singleton};
+
+    pub use crate::api::{EncodingSingleton, TextDecoderClass, TextEncoderClass};
+    pub use crate::encoding::{
+        atob, btoa, create_encoding_singleton, text_decoder_constructor, text_encoder_constructor,
+    };
+
+    pub use crate::api::CryptoSingleton;
+    pub use crate::crypto::{DefaultCryptoSingleton, create_crypto_singleton};
+
+    pub use crate::timers::{
+        clear_interval, clear_timeout, queue_microtask, set_interval, set_timeout,
+    };
 }
 
-mod stdlib_impl;
+pub mod clock;
+pub mod console;
+pub mod crypto;
+pub mod encoding;
+pub mod event_loop;
+mod performance;
+mod timers;
+
+pub use clock::{Clock, SystemClock, VirtualClock};
+pub use console::{ConsoleRecord, ConsoleSink, StdioSink};
+pub use crypto::{EntropySource, OsEntropy, SeededEntropy};
+pub use event_loop::EventLoop;
@@ -0,0 +1,73 @@
+//! `performance`: monotonic time for scripts.
+//!
+//! Times come from the context clock (`crate::clock`), the same source the timers use, so a
+//! host-installed clock (RTOS tick counter, `VirtualClock` in tests) drives both.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mquickjs_rs::Env;
+
+use crate::clock::{Clock, context_clock};
+
+/// Per-context `performance` state: named marks.
+#[derive(Default)]
+pub struct DefaultPerformanceSingleton {
+    marks: HashMap<String, f64>,
+}
+
+/// The clock of the calling context; the glue passes its `Env` (`[env]` in the RIDL).
+fn clock(env: &Env<'_>) -> Rc<dyn Clock> {
+    context_clock(env.scope().token())
+}
+
+fn now_ms(env: &Env<'_>) -> f64 {
+    clock(env).now().as_secs_f64() * 1000.0
+}
+
+impl crate::impls::PerformanceSingleton for DefaultPerformanceSingleton {
+    fn now(&mut self, env: &mut Env<'_>) -> f64 {
+        now_ms(env)
+    }
+
+    fn mark(&mut self, env: &mut Env<'_>, name: String) -> f64 {
+        let t = now_ms(env);
+        self.marks.insert(name, t);
+        t
+    }
+
+    fn measure(
+        &mut self,
+        env: &mut Env<'_>,
+        _name: String,
+        start_mark: Option<String>,
+        end_mark: Option<String>,
+    ) -> Option<f64> {
+        let start = match start_mark {
+            Some(m) => *self.marks.get(&m)?,
+            None => 0.0,
+        };
+        let end = match end_mark {
+            Some(m) => *self.marks.get(&m)?,
+            None => now_ms(env),
+        };
+        Some(end - start)
+    }
+
+    fn clear_marks(&mut self, name: Option<String>) {
+        match name {
+            Some(name) => {
+                self.marks.remove(&name);
+            }
+            None => self.marks.clear(),
+        }
+    }
+
+    fn time_origin(&self, env: &mut Env<'_>) -> f64 {
+        clock(env).time_origin()
+    }
+}
+
+pub fn create_performance_singleton() -> Box<dyn crate::api::PerformanceSingleton> {
+    Box::new(DefaultPerformanceSingleton::default())
+}
@@ -0,0 +1,190 @@
+//! Timers: `setTimeout`, `setInterval`, `clearTimeout`, `clearInterval` and `queueMicrotask`.
+//!
+//! Callbacks (and their bound arguments) are rooted as `Global`s in a per-context
+//! `TimerQueue` (host data). Nothing runs on its own: the host drives the queue with
+//! `crate::EventLoop`.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+use std::time::Duration;
+
+use mquickjs_rs::Env;
+use mquickjs_rs::context::ContextToken;
+use mquickjs_rs::handles::global::Global;
+use mquickjs_rs::handles::local::{Function, Local, Value};
+
+use crate::clock::context_clock;
+
+/// Intervals fire at most once per millisecond (as in browsers/Node), so a zero-delay
+/// `setInterval` cannot spin the loop at a fixed virtual time.
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A callback with its bound arguments.
+pub(crate) struct Task {
+    pub(crate) callback: Global<Function>,
+    pub(crate) args: Vec<Global<Value>>,
+}
+
+impl Task {
+    fn new<'ctx>(
+        env: &Env<'ctx>,
+        callback: Local<'ctx, Function>,
+        args: Vec<Local<'ctx, Value>>,
+    ) -> Self {
+        let scope = env.scope();
+        Self {
+            callback: Global::new(scope, callback),
+            args: args.into_iter().map(|a| Global::new(scope, a)).collect(),
+        }
+    }
+}
+
+struct Timer {
+    due: Duration,
+    interval: Option<Duration>,
+    task: Rc<Task>,
+}
+
+#[derive(Default)]
+struct TimerState {
+    next_id: i32,
+    timers: BTreeMap<i32, Timer>,
+    microtasks: VecDeque<Task>,
+}
+
+/// Pending timers and microtasks of one context.
+#[derive(Default)]
+pub(crate) struct TimerQueue {
+    state: RefCell<TimerState>,
+}
+
+impl TimerQueue {
+    pub(crate) fn of(token: &ContextToken) -> Option<Rc<Self>> {
+        token.host_data::<Self>()
+    }
+
+    fn schedule(&self, due: Duration, interval: Option<Duration>, task: Task) -> i32 {
+        let mut st = self.state.borrow_mut();
+        // Ids are shared by timeouts and intervals and start at 1 (0 is never a valid id).
+        st.next_id = st.next_id.checked_add(1).unwrap_or(1);
+        let id = st.next_id;
+        st.timers.insert(
+            id,
+            Timer {
+                due,
+                interval,
+                task: Rc::new(task),
+            },
+        );
+        id
+    }
+
+    fn clear(&self, id: i32) {
+        self.state.borrow_mut().timers.remove(&id);
+    }
+
+    pub(crate) fn pop_microtask(&self) -> Option<Task> {
+        self.state.borrow_mut().microtasks.pop_front()
+    }
+
+    /// Due time of the earliest timer.
+    pub(crate) fn next_due(&self) -> Option<Duration> {
+        self.state.borrow().timers.values().map(|t| t.due).min()
+    }
+
+    /// Take the earliest timer due at `now` (ties fire in scheduling order).
+    ///
+    /// Timeouts are removed; intervals are rescheduled before their callback runs, so
+    /// `clearInterval` from inside the callback works.
+    pub(crate) fn take_due(&self, now: Duration) -> Option<Rc<Task>> {
+        let mut st = self.state.borrow_mut();
+        let id = st
+            .timers
+            .iter()
+            .filter(|(_, t)| t.due <= now)
+            .min_by_key(|(id, t)| (t.due, **id))
+            .map(|(id, _)| *id)?;
+        let timer = st.timers.get_mut(&id)?;
+        match timer.interval {
+            Some(interval) => {
+                timer.due = now + interval;
+                Some(timer.task.clone())
+            }
+            None => st.timers.remove(&id).map(|t| t.task),
+        }
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        let st = self.state.borrow();
+        st.timers.is_empty() && st.microtasks.is_empty()
+    }
+}
+
+fn queue_of(env: &Env<'_>) -> Rc<TimerQueue> {
+    env.scope()
+        .token()
+        .host_data_or_insert_with(TimerQueue::default)
+}
+
+/// JS delays are milliseconds; negative, NaN and infinite delays mean "as soon as possible".
+fn delay_from_ms(ms: f64) -> Duration {
+    if ms.is_finite() && ms > 0.0 {
+        Duration::from_secs_f64(ms.min(i32::MAX as f64) / 1000.0)
+    } else {
+        Duration::ZERO
+    }
+}
+
+fn schedule<'ctx>(
+    env: &mut Env<'ctx>,
+    handler: Local<'ctx, Function>,
+    delay: f64,
+    args: Vec<Local<'ctx, Value>>,
+    repeat: bool,
+) -> i32 {
+    let mut delay = delay_from_ms(delay);
+    if repeat {
+        delay = delay.max(MIN_INTERVAL);
+    }
+    let due = context_clock(env.scope().token()).now() + delay;
+    let task = Task::new(env, handler, args);
+    queue_of(env).schedule(due, repeat.then_some(delay), task)
+}
+
+pub fn set_timeout<'ctx>(
+    env: &mut Env<'ctx>,
+    handler: Local<'ctx, Function>,
+    delay: f64,
+    args: Vec<Local<'ctx, Value>>,
+) -> i32 {
+    schedule(env, handler, delay, args, false)
+}
+
+pub fn set_interval<'ctx>(
+    env: &mut Env<'ctx>,
+    handler: Local<'ctx, Function>,
+    delay: f64,
+    args: Vec<Local<'ctx, Value>>,
+) -> i32 {
+    schedule(env, handler, delay, args, true)
+}
+
+pub fn clear_timeout(env: &mut Env<'_>, id: Option<i32>) {
+    let Some(id) = id else {
+        return;
+    };
+    if let Some(queue) = TimerQueue::of(env.scope().token()) {
+        queue.clear(id);
+    }
+}
+
+/// Same id space as `clearTimeout`.
+pub fn clear_interval(env: &mut Env<'_>, id: Option<i32>) {
+    clear_timeout(env, id)
+}
+
+pub fn queue_microtask<'ctx>(env: &mut Env<'ctx>, handler: Local<'ctx, Function>) {
+    let task = Task::new(env, handler, Vec::new());
+    queue_of(env).state.borrow_mut().microtasks.push_back(task);
+}
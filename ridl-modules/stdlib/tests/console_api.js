@@ -0,0 +1,34 @@
+"use strict";
+
+// Every console method exists and accepts the usual argument shapes.
+var methods = [
+  "log", "info", "debug", "warn", "error", "trace", "assert",
+  "time", "timeLog", "timeEnd", "count", "countReset", "group", "groupEnd",
+];
+for (var i = 0; i < methods.length; i++) {
+  if (typeof console[methods[i]] !== "function") {
+    throw new Error("console." + methods[i] + " should be a function");
+  }
+}
+
+console.info("%s has %d items: %o", "list", 2.9, [1, { a: "x" }]);
+console.debug("100%% done", { nested: { deeper: { deepest: {} } } });
+console.warn("missing arg %s");
+console.assert(true, "not printed");
+console.assert(0, "printed as error");
+
+console.time();
+console.timeLog(undefined, "checkpoint");
+console.timeEnd();
+console.timeEnd("never-started");
+
+console.count("hits");
+console.count("hits");
+console.countReset("hits");
+
+console.group("outer");
+console.log("indented");
+console.groupEnd();
+console.groupEnd();
+
+"ok";
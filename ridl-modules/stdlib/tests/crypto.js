@@ -0,0 +1,71 @@
+"use strict";
+
+function assertEq(actual, expected, what) {
+  if (actual !== expected) {
+    throw new Error(what + ": expected " + JSON.stringify(expected) + ", got " + JSON.stringify(actual));
+  }
+}
+
+function assertThrows(f, name, what) {
+  try {
+    f();
+  } catch (e) {
+    assertEq(e.name, name, what);
+    return;
+  }
+  throw new Error(what + ": expected an exception");
+}
+
+// sha256 takes a string (UTF-8) or bytes; both give the same result for the same bytes.
+assertEq(encoding.toHex(crypto.sha256("")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855", "sha256('')");
+assertEq(encoding.toHex(crypto.sha256("abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", "sha256('abc')");
+assertEq(encoding.toHex(crypto.sha256(new Uint8Array([0x61, 0x62, 0x63]))), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", "sha256(bytes)");
+assertEq(encoding.toHex(crypto.sha256("é")), encoding.toHex(crypto.sha256(new TextEncoder().encode("é"))), "sha256 utf-8");
+assertEq(crypto.sha256(new ArrayBuffer(0)).length, 32, "sha256(ArrayBuffer)");
+
+assertEq(encoding.toHex(crypto.sha256("a\0b")), encoding.toHex(crypto.sha256(new Uint8Array([0x61, 0, 0x62]))), "sha256 keeps NULs");
+
+// RFC 4231 test cases 1 and 2; hmacSha256 takes bytes only.
+var utf8 = new TextEncoder();
+assertEq(
+  encoding.toHex(crypto.hmacSha256(encoding.fromHex("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b"), utf8.encode("Hi There"))),
+  "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+  "hmac case 1"
+);
+assertEq(
+  encoding.toHex(crypto.hmacSha256(utf8.encode("Jefe"), utf8.encode("what do ya want for nothing?"))),
+  "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+  "hmac case 2"
+);
+assertThrows(function () { crypto.sha256(42); }, "TypeError", "sha256 of a number");
+assertThrows(function () { crypto.sha256([0x61]); }, "TypeError", "sha256 of a plain array");
+assertThrows(function () { crypto.hmacSha256(utf8.encode("key")); }, "TypeError", "hmac without data");
+assertThrows(function () { crypto.hmacSha256("key", utf8.encode("data")); }, "TypeError", "hmac with a string key");
+
+// getRandomValues fills integer typed arrays in place.
+var u8 = new Uint8Array(64);
+assertEq(crypto.getRandomValues(u8), undefined, "getRandomValues result");
+var nonZero = 0;
+for (var i = 0; i < u8.length; i++) if (u8[i] !== 0) nonZero++;
+if (nonZero === 0) throw new Error("getRandomValues left the array zeroed");
+var u32 = new Uint32Array(4);
+crypto.getRandomValues(u32);
+assertEq(u32[0] >= 0 && u32[0] <= 0xffffffff, true, "Uint32Array element range");
+assertThrows(function () { crypto.getRandomValues(new Float64Array(2)); }, "TypeError", "float array");
+assertThrows(function () { crypto.getRandomValues([1, 2]); }, "TypeError", "plain array");
+assertThrows(function () { crypto.getRandomValues(new Uint8Array(65537)); }, "Error", "quota");
+
+var uuid = crypto.randomUUID();
+if (!/^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/.test(uuid)) {
+  throw new Error("randomUUID format: " + uuid);
+}
+if (crypto.randomUUID() === uuid) throw new Error("randomUUID repeated");
+
+// Math.random is the context's generator.
+assertEq(Math.random, crypto.random, "Math.random installed");
+for (var j = 0; j < 100; j++) {
+  var r = Math.random();
+  if (!(r >= 0 && r < 1)) throw new Error("Math.random out of range: " + r);
+}
+
+"ok";
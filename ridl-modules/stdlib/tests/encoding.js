@@ -0,0 +1,77 @@
+"use strict";
+
+function assertEq(actual, expected, what) {
+  if (actual !== expected) {
+    throw new Error(what + ": expected " + JSON.stringify(expected) + ", got " + JSON.stringify(actual));
+  }
+}
+
+function bytesOf(u8) {
+  var out = [];
+  for (var i = 0; i < u8.length; i++) out.push(u8[i]);
+  return out.join(",");
+}
+
+function assertThrows(f, what) {
+  var threw = false;
+  try {
+    f();
+  } catch (e) {
+    threw = true;
+  }
+  if (!threw) throw new Error(what + ": expected an exception");
+}
+
+// UTF-8 round trip, including non-BMP characters and interior NULs.
+var enc = new TextEncoder();
+assertEq(enc.encoding, "utf-8", "TextEncoder.encoding");
+var bytes = enc.encode("hé€😀\u0000!");
+if (!(bytes instanceof Uint8Array)) throw new Error("encode should return a Uint8Array");
+assertEq(bytesOf(bytes), "104,195,169,226,130,172,240,159,152,128,0,33", "utf-8 bytes");
+var dec = new TextDecoder();
+assertEq(dec.decode(bytes), "hé€😀\u0000!", "utf-8 round trip");
+assertEq(dec.decode(), "", "decode() without input");
+assertEq(dec.decode(bytes.buffer), "hé€😀\u0000!", "decode(ArrayBuffer)");
+
+// Malformed input: replacement by default, TypeError when fatal.
+assertEq(dec.decode(new Uint8Array([0x61, 0xff, 0x62])), "a�b", "lossy utf-8");
+assertThrows(function () { new TextDecoder("utf-8", { fatal: true }).decode(new Uint8Array([0xc3])); }, "fatal utf-8");
+assertEq(new TextDecoder("utf-8", { fatal: true }).fatal, true, "fatal option");
+assertEq(new TextDecoder("utf-8", {}).fatal, false, "options default to false");
+assertThrows(function () { new TextDecoder("utf-8", true); }, "options must be an object");
+
+// BOM handling.
+assertEq(dec.decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x61])), "a", "utf-8 BOM skipped");
+assertEq(new TextDecoder("utf-8", { ignoreBOM: true }).decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x61])), "﻿a", "ignoreBOM");
+
+// UTF-16LE.
+var enc16 = new TextEncoder("UTF-16");
+assertEq(enc16.encoding, "utf-16le", "label alias");
+assertEq(bytesOf(enc16.encode("A€")), "65,0,172,32", "utf-16le bytes");
+var dec16 = new TextDecoder("utf-16le");
+assertEq(dec16.decode(new Uint8Array([0xff, 0xfe, 0x41, 0x00, 0x3d, 0xd8, 0x00, 0xde])), "A😀", "utf-16le decode");
+assertEq(dec16.decode(new Uint8Array([0x41, 0x00, 0x42])), "A�", "odd utf-16le length");
+
+assertThrows(function () { new TextDecoder("latin2"); }, "unknown label");
+
+// btoa / atob on binary strings.
+assertEq(btoa(""), "", "btoa empty");
+assertEq(btoa("hello"), "aGVsbG8=", "btoa");
+assertEq(btoa("\u0000ÿ\u0080"), "AP+A", "btoa binary");
+assertEq(atob("aGVsbG8="), "hello", "atob");
+assertEq(atob(" aGVs bG8 "), "hello", "atob whitespace, no padding");
+assertEq(atob("AP+A"), "\u0000ÿ\u0080", "atob binary");
+assertThrows(function () { btoa("€"); }, "btoa non-latin1");
+assertThrows(function () { atob("a"); }, "atob bad length");
+assertThrows(function () { atob("a*bc"); }, "atob bad character");
+
+// Byte-level hex / base64.
+var frame = new Uint8Array([0, 1, 0xab, 0xff]);
+assertEq(encoding.toHex(frame), "0001abff", "toHex");
+assertEq(bytesOf(encoding.fromHex("0001ABff")), "0,1,171,255", "fromHex");
+assertEq(encoding.toBase64(frame), "AAGr/w==", "toBase64");
+assertEq(bytesOf(encoding.fromBase64("AAGr/w")), "0,1,171,255", "fromBase64");
+assertThrows(function () { encoding.fromHex("abc"); }, "odd hex");
+assertThrows(function () { encoding.fromHex("zz"); }, "bad hex");
+
+"ok";
@@ -0,0 +1,42 @@
+"use strict";
+
+if (typeof performance === "undefined") {
+  throw new Error("performance is undefined");
+}
+
+var t0 = performance.now();
+var t1 = performance.now();
+if (typeof t0 !== "number" || t0 < 0 || t1 < t0) {
+  throw new Error("performance.now should be monotonic: " + t0 + ", " + t1);
+}
+
+if (!(performance.timeOrigin > 0)) {
+  throw new Error("timeOrigin should be a Unix time in ms: " + performance.timeOrigin);
+}
+
+var start = performance.mark("start");
+if (start < t1) {
+  throw new Error("mark should return its timestamp");
+}
+performance.mark("end");
+
+if (!(performance.measure("span", "start", "end") >= 0)) {
+  throw new Error("measure between marks should be >= 0");
+}
+if (!(performance.measure("sinceStart", "start") >= 0)) {
+  throw new Error("measure to now should be >= 0");
+}
+if (performance.measure("missing", "nope") !== null) {
+  throw new Error("measure with an unknown mark should be null");
+}
+
+performance.clearMarks("start");
+if (performance.measure("cleared", "start") !== null) {
+  throw new Error("cleared mark should be gone");
+}
+performance.clearMarks();
+if (performance.measure("cleared", "end") !== null) {
+  throw new Error("clearMarks() should remove all marks");
+}
+
+"ok";
@@ -0,0 +1,39 @@
+"use strict";
+
+// Callbacks only run when the host drives stdlib::EventLoop; here we check scheduling.
+var names = ["setTimeout", "setInterval", "clearTimeout", "clearInterval", "queueMicrotask"];
+for (var i = 0; i < names.length; i++) {
+  if (typeof globalThis[names[i]] !== "function") {
+    throw new Error(names[i] + " should be a function");
+  }
+}
+
+var fired = false;
+var a = setTimeout(function () { fired = true; }, 10);
+var b = setInterval(function (x) { fired = x; }, 5, "extra");
+if (typeof a !== "number" || a <= 0 || b <= a) {
+  throw new Error("timer ids should be positive and increasing: " + a + ", " + b);
+}
+
+clearTimeout(a);
+clearInterval(b);
+clearTimeout();
+clearTimeout(null);
+clearTimeout(12345);
+queueMicrotask(function () { fired = true; });
+
+var threw = false;
+try {
+  setTimeout("fired = true", 0);
+} catch (e) {
+  threw = e instanceof TypeError;
+}
+if (!threw) {
+  throw new Error("setTimeout with a string should throw TypeError");
+}
+
+if (fired) {
+  throw new Error("callbacks must not run synchronously");
+}
+
+"ok";
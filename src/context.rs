@@ -6,18 +6,40 @@ pub struct Context {
 
 impl Context {
     pub fn new(memory_capacity: usize) -> Result<Self, Box<dyn std::error::Error>> {
-        let inner = mquickjs_rs::Context::new(memory_capacity)?;
+        Self::build(Self::builder().memory_capacity(memory_capacity))
+    }
+
+    /// Builder preconfigured with this app's RIDL init; add `.singleton(...)` overrides etc.
+    /// and pass it to `Context::build`.
+    pub fn builder() -> mquickjs_rs::ContextBuilder {
+        let builder = mquickjs_rs::Context::builder();
 
         // Context-level RIDL init is application-specific and only available when this crate
         // is built with ridl-extensions.
         #[cfg(feature = "ridl-extensions")]
-        unsafe {
-            crate::ridl_context_ext::ridl_context_init(inner.ctx as *mut mquickjs_ffi::JSContext);
+        let builder = builder.ridl_init(|ctx| unsafe {
+            crate::ridl_context_ext::ridl_context_init(ctx.ctx as *mut mquickjs_ffi::JSContext)?;
+
+            let _rc = mquickjs_ffi::JS_RIDL_StdlibInit(ctx.ctx as *mut mquickjs_ffi::JSContext);
+            Ok(())
+        });
+
+        // `std.fs` is linked in, but only contexts granted it through `enable_fs` may require it.
+        #[cfg(feature = "ridl-extensions")]
+        let builder = builder.require_check(std_fs::require_check);
+
+        // The engine's `Math.random` cannot be seeded; use the stdlib generator instead
+        // (`seed_random`).
+        #[cfg(feature = "ridl-extensions")]
+        let builder = builder.with_env(stdlib::crypto::install_math_random);
 
-            let _rc = mquickjs_ffi::JS_RIDL_StdlibInit(inner.ctx as *mut mquickjs_ffi::JSContext);
-        }
+        builder
+    }
 
-        Ok(Self { inner })
+    pub fn build(builder: mquickjs_rs::ContextBuilder) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            inner: builder.build()?,
+        })
     }
 
     pub fn default() -> Self {
@@ -27,6 +49,28 @@ impl Context {
     pub fn eval(&mut self, code: &str) -> Result<String, String> {
         self.inner.eval(code)
     }
+
+    /// `eval` with a script name (error locations, console records).
+    pub fn eval_file(&mut self, code: &str, filename: &str) -> Result<String, String> {
+        self.inner.eval_file(code, filename)
+    }
+
+    /// Let this context's scripts `require("std.fs")`, limited to the roots in `config`.
+    #[cfg(feature = "ridl-extensions")]
+    pub fn enable_fs(&self, config: std_fs::FsConfig) {
+        std_fs::enable(&self.inner.token(), config);
+    }
+
+    /// Make `Math.random` (and `crypto.random`) a reproducible sequence for this context.
+    #[cfg(feature = "ridl-extensions")]
+    pub fn seed_random(&self, seed: u64) {
+        stdlib::crypto::seed_math_random(&self.inner.token(), seed);
+    }
+
+    /// Token of the underlying context, e.g. for `stdlib::EventLoop::new`.
+    pub fn token(&self) -> mquickjs_rs::context::ContextToken {
+        self.inner.token()
+    }
 }
 
 impl Default for Context {
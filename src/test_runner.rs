@@ -54,15 +54,19 @@ pub fn run_one_js_file(path: &Path) -> Result<(), String> {
     // ridl_context_init() is applied and singleton slots are filled.
     let mut context = crate::Context::default();
 
-    context.eval(&script).map(|_result| ()).map_err(|e| {
-        // Include file path and a short prefix to help diagnose syntax errors.
-        let prefix: String = script.chars().take(80).collect();
-        format!(
-            "eval failed: {e}\n  file: {}\n  prefix: {:?}",
-            path.display(),
-            prefix
-        )
-    })
+    let filename = path.to_string_lossy();
+    context
+        .eval_file(&script, &filename)
+        .map(|_result| ())
+        .map_err(|e| {
+            // Include file path and a short prefix to help diagnose syntax errors.
+            let prefix: String = script.chars().take(80).collect();
+            format!(
+                "eval failed: {e}\n  file: {}\n  prefix: {:?}",
+                path.display(),
+                prefix
+            )
+        })
 }
 
 #[derive(Debug, Clone, Copy, Default)]
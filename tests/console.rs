@@ -0,0 +1,192 @@
+//! stdlib console: formatting, levels and the per-context sink.
+#![cfg(feature = "ridl-extensions")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use mquickjs_demo::Context;
+use mquickjs_rs::Env;
+use mquickjs_rs::handles::local::{Local, Object};
+use stdlib::console::{ConsoleRecord, ConsoleSink, Level, set_console_sink};
+use stdlib::{Clock, EventLoop, VirtualClock};
+
+#[derive(Default)]
+struct Capture {
+    lines: RefCell<Vec<(Level, String, Option<String>, usize)>>,
+}
+
+impl ConsoleSink for Capture {
+    fn write(&self, record: &ConsoleRecord<'_>) {
+        self.lines.borrow_mut().push((
+            record.level,
+            record.message.to_string(),
+            record.filename.map(str::to_string),
+            record.group_depth,
+        ));
+    }
+}
+
+impl Capture {
+    fn take(&self) -> Vec<(Level, String)> {
+        self.lines
+            .borrow_mut()
+            .drain(..)
+            .map(|(level, message, _, _)| (level, message))
+            .collect()
+    }
+}
+
+fn setup() -> (Context, Rc<Capture>) {
+    mquickjs_rs::ridl_bootstrap!();
+    let ctx = Context::default();
+    let capture = Rc::new(Capture::default());
+    set_console_sink(&ctx.token(), capture.clone());
+    (ctx, capture)
+}
+
+#[test]
+fn formats_and_inspects_arguments() {
+    let (mut ctx, capture) = setup();
+    ctx.eval(
+        r#"
+        console.log("%s=%d (%i) %f%%", "n", 4.7, -2.5, 0.5, "extra", { a: [1, "two"] });
+        console.log({ nested: { deeper: { deepest: { x: 1 } } } });
+        console.log("unmatched %s %o");
+        console.log(1, null, undefined, true, function f() {});
+        console.log("%c styled", "color: red");
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        capture.take(),
+        vec![
+            (
+                Level::Log,
+                "n=4 (-2) 0.5% extra { a: [ 1, 'two' ] }".to_string()
+            ),
+            (
+                Level::Log,
+                "{ nested: { deeper: { deepest: [Object] } } }".to_string()
+            ),
+            (Level::Log, "unmatched %s %o".to_string()),
+            (
+                Level::Log,
+                "1 null undefined true [Function: f]".to_string()
+            ),
+            (Level::Log, " styled".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn levels_assert_count_and_groups() {
+    let (mut ctx, capture) = setup();
+    ctx.eval_file(
+        r#"
+        console.info("i"); console.debug("d"); console.warn("w"); console.error("e");
+        console.trace("t");
+        console.assert(1 === 1, "fine");
+        console.assert("", "empty %s", "string");
+        console.count(); console.count(); console.count("x");
+        console.countReset(); console.count();
+        console.countReset("nope");
+        console.group("g");
+        console.log("inside");
+        console.groupEnd();
+        console.log("outside");
+        "#,
+        "levels.js",
+    )
+    .unwrap();
+
+    let lines = capture.lines.borrow().clone();
+    assert!(lines.iter().all(|l| l.2.as_deref() == Some("levels.js")));
+    let depths: Vec<usize> = lines.iter().map(|l| l.3).collect();
+    assert_eq!(&depths[depths.len() - 3..], &[0, 1, 0]);
+
+    assert_eq!(
+        capture.take(),
+        vec![
+            (Level::Info, "i".to_string()),
+            (Level::Debug, "d".to_string()),
+            (Level::Warn, "w".to_string()),
+            (Level::Error, "e".to_string()),
+            (Level::Trace, "Trace: t".to_string()),
+            (Level::Error, "Assertion failed: empty string".to_string()),
+            (Level::Log, "default: 1".to_string()),
+            (Level::Log, "default: 2".to_string()),
+            (Level::Log, "x: 1".to_string()),
+            (Level::Log, "default: 1".to_string()),
+            (Level::Warn, "Count for 'nope' does not exist".to_string()),
+            (Level::Log, "g".to_string()),
+            (Level::Log, "inside".to_string()),
+            (Level::Log, "outside".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn timers_use_the_context_clock() {
+    let (mut ctx, capture) = setup();
+    let clock = Rc::new(VirtualClock::new());
+    let event_loop = EventLoop::with_clock(ctx.token(), clock.clone());
+
+    ctx.eval(
+        r#"
+        console.time("load");
+        console.time("load");
+        setTimeout(function () { console.timeLog("load", "half"); }, 5);
+        setTimeout(function () { console.timeEnd("load"); console.timeEnd("load"); }, 10);
+        "#,
+    )
+    .unwrap();
+    event_loop.run_until_idle().unwrap();
+    assert_eq!(clock.now(), Duration::from_millis(10));
+
+    let lines = capture.lines.borrow().clone();
+    // Timer callbacks run from the host loop, outside any script.
+    assert_eq!(lines.last().unwrap().2, None);
+    assert_eq!(
+        capture.take(),
+        vec![
+            (Level::Warn, "Timer 'load' already exists".to_string()),
+            (Level::Log, "load: 5.000ms half".to_string()),
+            (Level::Log, "load: 10.000ms".to_string()),
+            (Level::Warn, "Timer 'load' does not exist".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn counters_and_timers_work_from_host_calls() {
+    let (ctx, capture) = setup();
+    let clock = Rc::new(VirtualClock::new());
+    let _event_loop = EventLoop::with_clock(ctx.token(), clock.clone());
+
+    // The host calls console methods directly, without `Context::eval` entering the context.
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let console: Local<Object> = global.get_path(&env, "console").unwrap();
+    console.call_method::<()>(&env, "count", ("host",)).unwrap();
+    console
+        .call_method::<()>(&env, "countReset", ("missing",))
+        .unwrap();
+    console.call_method::<()>(&env, "time", ("t",)).unwrap();
+    clock.advance(Duration::from_millis(5));
+    console.call_method::<()>(&env, "timeEnd", ("t",)).unwrap();
+
+    assert_eq!(
+        capture.take(),
+        vec![
+            (Level::Log, "host: 1".to_string()),
+            (
+                Level::Warn,
+                "Count for 'missing' does not exist".to_string()
+            ),
+            (Level::Log, "t: 5.000ms".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,114 @@
+//! stdlib `crypto`: host-seeded `Math.random` and injectable entropy, per context.
+#![cfg(feature = "ridl-extensions")]
+
+use std::rc::Rc;
+
+use mquickjs_demo::Context;
+use mquickjs_rs::handles::local::{Local, Object};
+use mquickjs_rs::{Env, Permissions};
+use stdlib::SeededEntropy;
+use stdlib::crypto::{hmac_sha256, random_uuid, set_context_entropy, sha256};
+use stdlib::encoding::hex_encode;
+
+const DRAW: &str = "[Math.random(), Math.random(), crypto.random()].join(',')";
+
+#[test]
+fn seeded_math_random_is_reproducible_per_context() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut a = Context::default();
+    let mut b = Context::default();
+    a.seed_random(42);
+    b.seed_random(42);
+
+    let first = a.eval(DRAW).unwrap();
+    assert_eq!(b.eval(DRAW).unwrap(), first);
+    // Each context keeps its own position in the sequence.
+    assert_ne!(a.eval(DRAW).unwrap(), first);
+
+    let mut c = Context::default();
+    c.seed_random(7);
+    assert_ne!(c.eval(DRAW).unwrap(), first);
+
+    // Re-seeding restarts the sequence.
+    a.seed_random(42);
+    assert_eq!(a.eval(DRAW).unwrap(), first);
+}
+
+#[test]
+fn injected_entropy_drives_random_values_and_uuids() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut ctx = Context::default();
+    set_context_entropy(&ctx.token(), Rc::new(SeededEntropy::new(1)));
+
+    let script = "var b = new Uint8Array(5); crypto.getRandomValues(b); \
+                  Array.prototype.join.call(b, ',') + ' ' + crypto.randomUUID()";
+    let expected_uuid = {
+        let source = SeededEntropy::new(1);
+        let mut skipped = [0u8; 5];
+        stdlib::EntropySource::fill(&source, &mut skipped);
+        assert_eq!(skipped, [193, 92, 2, 137, 236]);
+        random_uuid(&source)
+    };
+    assert_eq!(
+        ctx.eval(script).unwrap(),
+        format!("193,92,2,137,236 {expected_uuid}")
+    );
+
+    // Math.random is seeded from the injected source when the host sets no seed.
+    let mut other = Context::default();
+    set_context_entropy(&other.token(), Rc::new(SeededEntropy::new(1)));
+    let mut again = Context::default();
+    set_context_entropy(&again.token(), Rc::new(SeededEntropy::new(1)));
+    assert_eq!(other.eval(DRAW).unwrap(), again.eval(DRAW).unwrap());
+}
+
+#[test]
+fn host_calls_use_the_context_entropy() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut seeded = Context::default();
+    seeded.seed_random(42);
+    set_context_entropy(&seeded.token(), Rc::new(SeededEntropy::new(1)));
+    let expected_random: f64 = seeded.eval("crypto.random()").unwrap().parse().unwrap();
+    let expected_uuid = random_uuid(&SeededEntropy::new(1));
+
+    let mut ctx = Context::default();
+    ctx.seed_random(42);
+    set_context_entropy(&ctx.token(), Rc::new(SeededEntropy::new(1)));
+
+    // The host calls into the singleton without entering the context through `eval`.
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let crypto: Local<Object> = global.get_path(&env, "crypto").unwrap();
+
+    let uuid: String = crypto.call_method(&env, "randomUUID", ()).unwrap();
+    assert_eq!(uuid, expected_uuid);
+    let r: f64 = crypto.call_method(&env, "random", ()).unwrap();
+    assert_eq!(r, expected_random);
+}
+
+#[test]
+fn script_digests_match_the_rust_functions() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut ctx = Context::default();
+    assert_eq!(
+        ctx.eval("encoding.toHex(crypto.sha256('payload'))")
+            .unwrap(),
+        hex_encode(&sha256(b"payload"))
+    );
+    assert_eq!(
+        ctx.eval("var e = new TextEncoder(); encoding.toHex(crypto.hmacSha256(e.encode('k'), e.encode('payload')))")
+            .unwrap(),
+        hex_encode(&hmac_sha256(b"k", b"payload"))
+    );
+}
+
+#[test]
+fn contexts_without_crypto_keep_the_engine_random() {
+    mquickjs_rs::ridl_bootstrap!();
+    let permissions = Permissions::allow_all().deny_singleton("crypto");
+    let mut ctx = Context::build(Context::builder().permissions(permissions)).unwrap();
+    assert_eq!(ctx.eval("typeof crypto").unwrap(), "undefined");
+    assert_eq!(ctx.eval("typeof Math.random()").unwrap(), "number");
+}
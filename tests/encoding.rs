@@ -0,0 +1,69 @@
+//! stdlib text encoding: the Rust codecs and the errors scripts see.
+#![cfg(feature = "ridl-extensions")]
+
+use mquickjs_demo::Context;
+use stdlib::encoding::{
+    Encoding, base64_decode, base64_encode, decode, encode, hex_decode, hex_encode,
+};
+
+#[test]
+fn rust_codecs_match_the_script_api() {
+    assert_eq!(
+        Encoding::from_label(" Unicode-1-1-UTF-8 "),
+        Some(Encoding::Utf8)
+    );
+    assert_eq!(Encoding::from_label("ucs-2"), Some(Encoding::Utf16Le));
+    assert_eq!(Encoding::from_label("latin1"), None);
+
+    let frame = encode(Encoding::Utf16Le, "Hi😀");
+    assert_eq!(frame, [0x48, 0, 0x69, 0, 0x3d, 0xd8, 0x00, 0xde]);
+    assert_eq!(
+        decode(Encoding::Utf16Le, &frame, true, false).unwrap(),
+        "Hi😀"
+    );
+    assert!(decode(Encoding::Utf8, &[0xc3], true, false).is_err());
+    assert_eq!(
+        decode(Encoding::Utf8, &[0xc3], false, false).unwrap(),
+        "\u{fffd}"
+    );
+
+    for data in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+        assert_eq!(base64_decode(&base64_encode(data)).unwrap(), data);
+    }
+    assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64_encode(b"fo"), "Zm8=");
+    assert!(base64_decode("Zm9v=YmFy").is_err());
+
+    assert_eq!(hex_encode(&[0x00, 0x7f, 0xff]), "007fff");
+    assert_eq!(hex_decode("007FfF").unwrap(), [0x00, 0x7f, 0xff]);
+    assert!(hex_decode("0g").is_err());
+}
+
+#[test]
+fn invalid_input_throws_the_documented_errors() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut ctx = Context::default();
+    let caught = |ctx: &mut Context, expr: &str| {
+        ctx.eval(&format!(
+            "(function () {{ try {{ {expr}; return 'no error'; }} catch (e) {{ return e.name + ': ' + e.message; }} }})()"
+        ))
+        .unwrap()
+    };
+
+    assert_eq!(
+        caught(&mut ctx, "new TextDecoder('koi8-r')"),
+        r#"RangeError: The "koi8-r" encoding is not supported"#
+    );
+    assert_eq!(
+        caught(
+            &mut ctx,
+            "new TextDecoder('utf-8', { fatal: true }).decode(new Uint8Array([0xff]))"
+        ),
+        "TypeError: The encoded data was not valid for encoding utf-8"
+    );
+    assert!(caught(&mut ctx, "btoa('\\u0100')").starts_with("Error: InvalidCharacterError: "));
+    assert!(caught(&mut ctx, "encoding.fromHex('x')").starts_with("TypeError: "));
+
+    // A failed call leaves nothing pending for the next one.
+    assert_eq!(ctx.eval("atob(btoa('ok'))").unwrap(), "ok");
+}
@@ -0,0 +1,170 @@
+//! stdlib timers driven by `stdlib::EventLoop` on a virtual clock (which also drives
+//! `performance`).
+#![cfg(feature = "ridl-extensions")]
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use mquickjs_demo::Context;
+use mquickjs_rs::Env;
+use mquickjs_rs::handles::local::{Function, Local, Object};
+use stdlib::{Clock, EventLoop, VirtualClock};
+
+fn setup() -> (Context, EventLoop, Rc<VirtualClock>) {
+    mquickjs_rs::ridl_bootstrap!();
+    let ctx = Context::default();
+    let clock = Rc::new(VirtualClock::new());
+    let event_loop = EventLoop::with_clock(ctx.token(), clock.clone());
+    (ctx, event_loop, clock)
+}
+
+#[test]
+fn timers_fire_in_due_order_with_args() {
+    let (mut ctx, event_loop, clock) = setup();
+    ctx.eval(
+        r#"
+        var log = [];
+        setTimeout(function (tag) { log.push(tag + "@" + 30); }, 30, "c");
+        setTimeout(function () { log.push("a@10"); }, 10);
+        setTimeout(function () {
+            log.push("b@10");
+            queueMicrotask(function () { log.push("micro"); });
+        }, 10);
+        queueMicrotask(function () { log.push("first"); });
+        "#,
+    )
+    .unwrap();
+    assert!(event_loop.has_pending());
+
+    event_loop.run_for(Duration::from_millis(20)).unwrap();
+    assert_eq!(clock.now(), Duration::from_millis(20));
+    assert_eq!(ctx.eval("log.join(',')").unwrap(), "first,a@10,b@10,micro");
+
+    event_loop.run_until_idle().unwrap();
+    assert_eq!(clock.now(), Duration::from_millis(30));
+    assert_eq!(
+        ctx.eval("log.join(',')").unwrap(),
+        "first,a@10,b@10,micro,c@30"
+    );
+    assert!(!event_loop.has_pending());
+}
+
+#[test]
+fn intervals_repeat_until_cleared() {
+    let (mut ctx, event_loop, clock) = setup();
+    ctx.eval(
+        r#"
+        var ticks = 0;
+        var id = setInterval(function () {
+            ticks++;
+            if (ticks === 3) clearInterval(id);
+        }, 100);
+        var cancelled = setTimeout(function () { ticks = -1; }, 50);
+        clearTimeout(cancelled);
+        "#,
+    )
+    .unwrap();
+
+    event_loop.run_for(Duration::from_millis(250)).unwrap();
+    assert_eq!(ctx.eval("ticks").unwrap(), "2");
+
+    event_loop.run_until_idle().unwrap();
+    assert_eq!(ctx.eval("ticks").unwrap(), "3");
+    assert_eq!(clock.now(), Duration::from_millis(300));
+}
+
+#[test]
+fn clear_timeout_works_from_a_host_call() {
+    let (mut ctx, event_loop, _clock) = setup();
+    ctx.eval("var fired = false; var id = setTimeout(function () { fired = true; }, 10);")
+        .unwrap();
+
+    // The host calls `clearTimeout` without entering the context through `eval`.
+    {
+        let token = ctx.token();
+        let scope = token.enter_scope();
+        let mut env = Env::new(&scope);
+        let global = env.global().unwrap().as_local();
+        let id: i32 = global.get_path(&env, "id").unwrap();
+        let clear: Local<Function> = global.get_path(&env, "clearTimeout").unwrap();
+        clear.call_with::<()>(&env, (), (id,)).unwrap();
+    }
+
+    event_loop.run_until_idle().unwrap();
+    assert_eq!(ctx.eval("fired").unwrap(), "false");
+}
+
+#[test]
+fn callback_exception_stops_the_run() {
+    let (mut ctx, event_loop, _clock) = setup();
+    ctx.eval(
+        r#"
+        var after = false;
+        setTimeout(function () { throw new Error("tick failed"); }, 1);
+        setTimeout(function () { after = true; }, 2);
+        "#,
+    )
+    .unwrap();
+
+    let err = event_loop.run_until_idle().unwrap_err();
+    assert!(err.contains("tick failed"), "{err}");
+    assert_eq!(ctx.eval("after").unwrap(), "false");
+
+    // The remaining work is still queued.
+    event_loop.run_until_idle().unwrap();
+    assert_eq!(ctx.eval("after").unwrap(), "true");
+}
+
+#[test]
+fn dropped_context_is_reported() {
+    let (ctx, event_loop, _clock) = setup();
+    drop(ctx);
+    assert!(event_loop.run_pending().is_err());
+}
+
+#[test]
+fn performance_follows_the_context_clock() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut ctx = Context::default();
+    let clock = Rc::new(VirtualClock::new().with_time_origin(1_700_000_000_000.0));
+    let event_loop = EventLoop::with_clock(ctx.token(), clock.clone());
+
+    assert_eq!(ctx.eval("performance.timeOrigin").unwrap(), "1700000000000");
+    ctx.eval(
+        r#"
+        performance.mark("start");
+        setTimeout(function () { performance.mark("fired"); }, 250);
+        "#,
+    )
+    .unwrap();
+
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(ctx.eval("performance.now()").unwrap(), "100");
+
+    event_loop.run_until_idle().unwrap();
+    assert_eq!(
+        ctx.eval("performance.measure('m', 'start', 'fired')")
+            .unwrap(),
+        "250"
+    );
+}
+
+#[test]
+fn performance_uses_the_context_clock_from_a_host_call() {
+    mquickjs_rs::ridl_bootstrap!();
+    let ctx = Context::default();
+    let clock = Rc::new(VirtualClock::new().with_time_origin(1_000.0));
+    let _event_loop = EventLoop::with_clock(ctx.token(), clock.clone());
+    clock.advance(Duration::from_millis(40));
+
+    // Called straight from the host: the glue still hands the impl this context's clock.
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let performance: Local<Object> = global.get_path(&env, "performance").unwrap();
+    let now: f64 = performance.call_method(&env, "now", ()).unwrap();
+    assert_eq!(now, 40.0);
+    let origin: f64 = global.get_path(&env, "performance.timeOrigin").unwrap();
+    assert_eq!(origin, 1_000.0);
+}
@@ -0,0 +1,178 @@
+//! `std.fs`: granted per context, confined to the configured roots.
+#![cfg(feature = "ridl-extensions")]
+
+use std::path::{Path, PathBuf};
+
+use mquickjs_demo::Context;
+use mquickjs_rs::Env;
+use mquickjs_rs::handles::local::{Function, Local, Object};
+use std_fs::FsConfig;
+
+/// Fresh directory with `data/` (read-only root), `out/` (writable root) and `secret/`
+/// (not exposed).
+fn sandbox_dirs(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join(format!("mquickjs_fs_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&base);
+    for d in ["data/sub", "out", "secret"] {
+        std::fs::create_dir_all(base.join(d)).unwrap();
+    }
+    std::fs::write(base.join("data/hello.txt"), "héllo").unwrap();
+    std::fs::write(base.join("data/bin"), [0u8, 1, 255]).unwrap();
+    std::fs::write(base.join("secret/key"), "s3cret").unwrap();
+    base
+}
+
+fn enabled_context(base: &Path) -> Context {
+    let ctx = Context::default();
+    ctx.enable_fs(
+        FsConfig::new()
+            .root("data", base.join("data"))
+            .writable_root("out", base.join("out")),
+    );
+    ctx
+}
+
+fn error_of(ctx: &mut Context, code: &str) -> String {
+    ctx.eval(&format!(
+        "(function () {{ try {{ {code}; return 'no error'; }} catch (e) {{ return e.name + ': ' + e.message; }} }})()"
+    ))
+    .unwrap()
+}
+
+#[test]
+fn scripts_read_and_write_inside_roots() {
+    mquickjs_rs::ridl_bootstrap!();
+    let base = sandbox_dirs("rw");
+    let mut ctx = enabled_context(&base);
+    ctx.eval("var fs = require('std.fs');").unwrap();
+
+    assert_eq!(ctx.eval("fs.readText('data/hello.txt')").unwrap(), "héllo");
+    assert_eq!(
+        ctx.eval("Array.prototype.join.call(fs.readBytes('/data/bin'), ',')")
+            .unwrap(),
+        "0,1,255"
+    );
+    assert_eq!(
+        ctx.eval("fs.list('data').join(',')").unwrap(),
+        "bin,hello.txt,sub"
+    );
+    assert_eq!(
+        ctx.eval("var s = fs.stat('data/hello.txt'); [s.size, s.isFile, s.isDirectory, typeof s.mtimeMs].join(',')")
+            .unwrap(),
+        "6,true,false,number"
+    );
+    assert_eq!(ctx.eval("fs.stat('data/sub').isDirectory").unwrap(), "true");
+    assert_eq!(
+        ctx.eval("[fs.exists('data/hello.txt'), fs.exists('data/missing')].join(',')")
+            .unwrap(),
+        "true,false"
+    );
+
+    ctx.eval("fs.writeText('out/report.txt', 'done')").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(base.join("out/report.txt")).unwrap(),
+        "done"
+    );
+
+    // I/O failures carry the script path, not the host path.
+    let err = error_of(&mut ctx, "fs.readText('data/missing')");
+    assert!(err.starts_with("Error: data/missing:"), "{err}");
+    assert!(!err.contains(base.to_str().unwrap()), "{err}");
+
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[test]
+fn ops_find_the_sandbox_from_host_calls() {
+    mquickjs_rs::ridl_bootstrap!();
+    let base = sandbox_dirs("host");
+    let mut ctx = enabled_context(&base);
+    ctx.eval("var fs = require('std.fs');").unwrap();
+
+    // The host calls into the module without entering the context through `eval`.
+    let token = ctx.token();
+    let scope = token.enter_scope();
+    let mut env = Env::new(&scope);
+    let global = env.global().unwrap().as_local();
+    let fs: Local<Object> = global.get_path(&env, "fs").unwrap();
+
+    let text: String = fs
+        .call_method(&env, "readText", ("data/hello.txt",))
+        .unwrap();
+    assert_eq!(text, "héllo");
+    let names: Local<Object> = fs.call_method(&env, "list", ("data",)).unwrap();
+    let first: String = names.get_path(&env, "0").unwrap();
+    assert_eq!(first, "bin");
+    let stat: Local<Object> = fs.call_method(&env, "stat", ("data/sub",)).unwrap();
+    let is_dir: bool = stat.get_path(&env, "isDirectory").unwrap();
+    assert!(is_dir);
+
+    let stat_fn: Local<Function> = fs.get_path(&env, "stat").unwrap();
+    let err = stat_fn
+        .call_with::<()>(&env, (), ("data/missing",))
+        .unwrap_err();
+    assert!(err.contains("data/missing"), "{err}");
+
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[test]
+fn paths_outside_roots_are_rejected() {
+    mquickjs_rs::ridl_bootstrap!();
+    let base = sandbox_dirs("escape");
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(base.join("secret"), base.join("data/link")).unwrap();
+    let mut ctx = enabled_context(&base);
+    ctx.eval("var fs = require('std.fs');").unwrap();
+
+    for (code, expected) in [
+        ("fs.readText('data/../secret/key')", "'..' is not allowed"),
+        ("fs.readText('secret/key')", "unknown root 'secret'"),
+        ("fs.exists('data\\\\..\\\\secret')", "invalid path"),
+        (
+            "fs.writeText('data/new.txt', 'x')",
+            "root 'data' is read-only",
+        ),
+        ("fs.list('')", "path has no root"),
+    ] {
+        let err = error_of(&mut ctx, code);
+        assert!(err.starts_with("TypeError: "), "{code}: {err}");
+        assert!(err.contains(expected), "{code}: {err}");
+    }
+
+    #[cfg(unix)]
+    {
+        let err = error_of(&mut ctx, "fs.readText('data/link/key')");
+        assert!(err.contains("outside of root 'data'"), "{err}");
+    }
+    assert!(!base.join("data/new.txt").exists());
+
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[test]
+fn contexts_without_fs_cannot_require_it() {
+    mquickjs_rs::ridl_bootstrap!();
+    let base = sandbox_dirs("gate");
+    let mut trusted = enabled_context(&base);
+    let mut untrusted = Context::default();
+
+    let err = error_of(&mut untrusted, "require('std.fs')");
+    assert_eq!(
+        err,
+        "TypeError: require std.fs@1.0 failed: file system access is not enabled for this context"
+    );
+    assert_eq!(
+        trusted.eval("typeof require('std.fs').readText").unwrap(),
+        "function"
+    );
+    // Other native modules are unaffected by the check.
+    assert_eq!(
+        untrusted
+            .eval("typeof require('test_module_basic').mping")
+            .unwrap(),
+        "function"
+    );
+
+    let _ = std::fs::remove_dir_all(&base);
+}
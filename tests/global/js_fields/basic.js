@@ -1,4 +1,17 @@
-var t = globalThis.TestJsFields;
+(function () {
+  var t = globalThis.TestJsFields;
 
-t.getNullAny();
+  t.getNullAny();
 
+  // JS-only fields are installed by ridl_context_init.
+  if (t.label !== "hello") throw new Error("TestJsFields.label init mismatch");
+  if (t.count !== 3) throw new Error("TestJsFields.count init mismatch");
+  if (t.empty !== null) throw new Error("TestJsFields.empty should be null");
+
+  // singleton has a single object: proto var is an own property as well.
+  if (t.enabled !== true) throw new Error("TestJsFields.enabled init mismatch");
+
+  // var fields are writable.
+  t.count = 4;
+  if (t.count !== 4) throw new Error("TestJsFields.count should be writable");
+})();
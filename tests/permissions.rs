@@ -0,0 +1,81 @@
+//! Per-context `Permissions`: trusted and untrusted contexts side by side in one process.
+#![cfg(feature = "ridl-extensions")]
+
+use mquickjs_demo::Context;
+use mquickjs_rs::Permissions;
+
+fn error_of(ctx: &mut Context, code: &str) -> String {
+    ctx.eval(&format!(
+        "(function () {{ try {{ {code}; return 'no error'; }} catch (e) {{ return e.name + ': ' + e.message; }} }})()"
+    ))
+    .unwrap()
+}
+
+fn untrusted() -> Context {
+    let permissions = Permissions::deny_all()
+        .allow_module("test_module_basic@>=1")
+        .unwrap()
+        .allow_singleton("console");
+    Context::build(Context::builder().permissions(permissions)).unwrap()
+}
+
+#[test]
+fn untrusted_context_only_gets_what_it_was_granted() {
+    mquickjs_rs::ridl_bootstrap!();
+    let mut sandboxed = untrusted();
+    let mut trusted = Context::default();
+
+    assert_eq!(
+        sandboxed
+            .eval("require('test_module_basic').mping()")
+            .unwrap(),
+        "7"
+    );
+    assert_eq!(
+        error_of(&mut sandboxed, "require('test_module_single_1_0')"),
+        "TypeError: require test_module_single_1_0@1.0 failed: not permitted in this context"
+    );
+    assert_eq!(sandboxed.eval("typeof console.log").unwrap(), "function");
+    assert_eq!(sandboxed.eval("typeof performance").unwrap(), "undefined");
+    assert_eq!(sandboxed.eval("typeof encoding").unwrap(), "undefined");
+
+    // The trusted context next to it is unaffected.
+    assert_eq!(
+        trusted
+            .eval("typeof require('test_module_single_1_0')")
+            .unwrap(),
+        "object"
+    );
+    assert_eq!(trusted.eval("typeof performance.now()").unwrap(), "number");
+}
+
+#[test]
+fn deny_rules_match_version_ranges() {
+    mquickjs_rs::ridl_bootstrap!();
+    let permissions = Permissions::allow_all()
+        .deny_module("test_module_basic@<2")
+        .unwrap();
+    let mut ctx = Context::build(Context::builder().permissions(permissions)).unwrap();
+
+    assert_eq!(
+        error_of(&mut ctx, "require('test_module_basic@1.0')"),
+        "TypeError: require test_module_basic@1.0 failed: not permitted in this context"
+    );
+    assert_eq!(
+        ctx.eval("typeof require('test_module_single_1_0')")
+            .unwrap(),
+        "object"
+    );
+}
+
+#[test]
+fn permissions_are_checked_before_module_specific_gates() {
+    mquickjs_rs::ridl_bootstrap!();
+    // Even with fs enabled, the permission set decides first.
+    let mut ctx = untrusted();
+    ctx.enable_fs(std_fs::FsConfig::new().root("tmp", std::env::temp_dir()));
+    assert_eq!(
+        error_of(&mut ctx, "require('std.fs')"),
+        "TypeError: require std.fs@1.0 failed: not permitted in this context"
+    );
+}
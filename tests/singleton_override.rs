@@ -0,0 +1,84 @@
+//! Host-provided singleton implementations installed through `Context::builder()`.
+#![cfg(feature = "ridl-extensions")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mquickjs_demo::Context;
+use mquickjs_rs::{Env, Local, Value};
+use stdlib::impls::ConsoleSingleton;
+
+/// Console that records `log` calls and ignores everything else.
+struct RecordingConsole {
+    lines: Rc<RefCell<Vec<String>>>,
+}
+
+impl ConsoleSingleton for RecordingConsole {
+    fn log(&mut self, env: &mut Env<'_>, args: Vec<Local<'_, Value>>) {
+        let scope = env.scope();
+        let line = args
+            .iter()
+            .map(|v| scope.value(v.as_raw()).inspect(scope).unwrap())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.lines.borrow_mut().push(line);
+    }
+    fn info(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn debug(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn warn(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn error(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn trace(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn assert(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn time(&mut self, _label: Option<String>) {}
+    fn time_log(
+        &mut self,
+        _env: &mut Env<'_>,
+        _label: Option<String>,
+        _args: Vec<Local<'_, Value>>,
+    ) {
+    }
+    fn time_end(&mut self, _label: Option<String>) {}
+    fn count(&mut self, _label: Option<String>) {}
+    fn count_reset(&mut self, _label: Option<String>) {}
+    fn group(&mut self, _env: &mut Env<'_>, _args: Vec<Local<'_, Value>>) {}
+    fn group_end(&mut self) {}
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn override_replaces_console_for_that_context_only() {
+    mquickjs_rs::ridl_bootstrap!();
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let mut custom = Context::build(Context::builder().singleton::<dyn ConsoleSingleton>(
+        Box::new(RecordingConsole {
+            lines: lines.clone(),
+        }),
+    ))
+    .unwrap();
+    let mut plain = Context::default();
+
+    custom.eval(r#"console.log("hi", { a: 1 }, [2]);"#).unwrap();
+    assert_eq!(*lines.borrow(), vec!["hi { a: 1 } [ 2 ]".to_string()]);
+    assert_eq!(custom.eval("console.enabled").unwrap(), "false");
+
+    // The default implementation is untouched in other contexts.
+    assert_eq!(plain.eval("console.enabled").unwrap(), "true");
+    assert_eq!(lines.borrow().len(), 1);
+}
+
+#[test]
+fn override_is_dropped_with_its_context() {
+    mquickjs_rs::ridl_bootstrap!();
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let ctx = Context::build(
+        Context::builder().singleton::<dyn ConsoleSingleton>(Box::new(RecordingConsole {
+            lines: lines.clone(),
+        })),
+    )
+    .unwrap();
+    assert_eq!(Rc::strong_count(&lines), 2);
+    drop(ctx);
+    assert_eq!(Rc::strong_count(&lines), 1);
+}